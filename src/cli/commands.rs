@@ -1,6 +1,10 @@
 // CLI command definitions
 
-use super::k8s::{DeleteCommand, DeployCommand, ListCommand, StatusCommand, UpdateCommand};
+use super::k8s::{
+    ConfigCommand, CvCommand, DeleteCommand, DeployCommand, DiagnoseCommand, DoctorCommand,
+    InitCommand, ListCommand, LogsCommand, PortForwardCommand, RbacCommand, StatusCommand,
+    UpdateCommand, ValidateCommand,
+};
 use clap::Parser;
 
 #[derive(Parser, Debug)]
@@ -17,6 +21,9 @@ pub struct CliArgs {
 
 #[derive(clap::Subcommand, Debug)]
 pub enum Commands {
+    /// Generate a commented curvine-cluster.toml from a preset (dev, prod, cache-only, ha)
+    Init(InitCommand),
+
     /// Deploy a new Curvine cluster to Kubernetes (creates all resources)
     Deploy(DeployCommand),
 
@@ -31,4 +38,29 @@ pub enum Commands {
 
     /// Delete a cluster
     Delete(DeleteCommand),
+
+    /// Stream logs from all cluster pods
+    Logs(LogsCommand),
+
+    /// Run the Curvine cv tool inside a master pod (`cv -c <id> -- <args>`)
+    Cv(CvCommand),
+
+    /// Forward named ports of the first ready master or worker pod to localhost
+    PortForward(PortForwardCommand),
+
+    /// Collect a support bundle (objects, events, logs, problems) as tar.gz
+    Diagnose(DiagnoseCommand),
+
+    /// Preflight checks (API version, RBAC, quotas, nodes, pull secrets) before deploy
+    Doctor(DoctorCommand),
+
+    /// Check a configuration file offline and report diagnostics by file/key
+    Validate(ValidateCommand),
+
+    /// Configuration helpers (list -D property keys, render profiles, convert formats,
+    /// resolve the effective config, export the JSON Schema)
+    Config(ConfigCommand),
+
+    /// RBAC helpers (print the ClusterRole required by this CLI)
+    Rbac(RbacCommand),
 }
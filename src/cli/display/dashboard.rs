@@ -0,0 +1,293 @@
+//! Full-screen dashboard for `status --watch`
+//!
+//! Holds the latest pods, StatefulSets and Warning events of one cluster as
+//! delivered by watchers and renders them as a single screen.
+
+use super::output::format_age;
+use super::{ColorTheme, StatusIcon};
+use crate::domain::cluster::descriptor::PodStatus;
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Cell, CellAlignment, Color, ContentArrangement, Table};
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::{Event, Pod};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Number of pod transitions and warnings kept on screen
+const HISTORY_LIMIT: usize = 10;
+
+/// ANSI sequence clearing the screen and moving the cursor home
+pub const CLEAR_SCREEN: &str = "\x1B[2J\x1B[H";
+
+pub struct Dashboard {
+    cluster_id: String,
+    namespace: String,
+    theme: ColorTheme,
+    pods: BTreeMap<String, PodStatus>,
+    statefulsets: BTreeMap<String, StatefulSet>,
+    transitions: VecDeque<String>,
+    warnings: VecDeque<String>,
+    pods_synced: bool,
+}
+
+impl Dashboard {
+    pub fn new(cluster_id: String, namespace: String) -> Self {
+        Self {
+            cluster_id,
+            namespace,
+            theme: ColorTheme::default(),
+            pods: BTreeMap::new(),
+            statefulsets: BTreeMap::new(),
+            transitions: VecDeque::new(),
+            warnings: VecDeque::new(),
+            pods_synced: false,
+        }
+    }
+
+    /// Record a pod add/update, logging phase and readiness transitions
+    pub fn apply_pod(&mut self, pod: &Pod, now: DateTime<Utc>) {
+        let status = PodStatus::from_pod(pod);
+        let state = pod_state(&status);
+        match self.pods.get(&status.name).map(pod_state) {
+            Some(previous) if previous != state => {
+                self.push_transition(now, format!("{}: {} → {}", status.name, previous, state))
+            }
+            Some(_) => {}
+            None if self.pods_synced => {
+                self.push_transition(now, format!("{}: created ({})", status.name, state))
+            }
+            None => {}
+        }
+        self.pods.insert(status.name.clone(), status);
+    }
+
+    pub fn delete_pod(&mut self, pod: &Pod, now: DateTime<Utc>) {
+        let name = pod.metadata.name.clone().unwrap_or_default();
+        if self.pods.remove(&name).is_some() {
+            self.push_transition(now, format!("{}: deleted", name));
+        }
+    }
+
+    pub fn apply_statefulset(&mut self, statefulset: &StatefulSet) {
+        let name = statefulset.metadata.name.clone().unwrap_or_default();
+        self.statefulsets.insert(name, statefulset.clone());
+    }
+
+    pub fn delete_statefulset(&mut self, statefulset: &StatefulSet) {
+        let name = statefulset.metadata.name.clone().unwrap_or_default();
+        self.statefulsets.remove(&name);
+    }
+
+    /// Record a Warning event if it concerns one of the cluster's objects
+    pub fn apply_event(&mut self, event: &Event) {
+        let object = event.involved_object.name.clone().unwrap_or_default();
+        if event.type_.as_deref() != Some("Warning")
+            || !object.starts_with(&format!("{}-", self.cluster_id))
+        {
+            return;
+        }
+
+        let time = event
+            .last_timestamp
+            .as_ref()
+            .map(|t| t.0)
+            .or_else(|| event.event_time.as_ref().map(|t| t.0))
+            .map(|t| t.format("%H:%M:%S").to_string())
+            .unwrap_or_else(|| "--:--:--".to_string());
+        let line = format!(
+            "{} {} {}: {}",
+            time,
+            object,
+            event.reason.as_deref().unwrap_or("Unknown"),
+            event.message.as_deref().unwrap_or("").trim()
+        );
+        self.push_warning(line);
+    }
+
+    /// Mark the initial pod listing complete; pods appearing afterwards are
+    /// logged as created
+    pub fn pods_synced(&mut self) {
+        self.pods_synced = true;
+    }
+
+    pub fn push_warning(&mut self, line: String) {
+        push_bounded(&mut self.warnings, line);
+    }
+
+    fn push_transition(&mut self, now: DateTime<Utc>, line: String) {
+        push_bounded(
+            &mut self.transitions,
+            format!("{} {}", now.format("%H:%M:%S"), line),
+        );
+    }
+
+    /// A rollout is in progress while a StatefulSet has not converged on its
+    /// update revision or ready replica count
+    pub fn rollout_in_progress(statefulset: &StatefulSet) -> bool {
+        let replicas = statefulset
+            .spec
+            .as_ref()
+            .and_then(|s| s.replicas)
+            .unwrap_or(0);
+        let Some(status) = statefulset.status.as_ref() else {
+            return replicas > 0;
+        };
+
+        let revision_pending = match (&status.current_revision, &status.update_revision) {
+            (Some(current), Some(update)) => current != update,
+            _ => false,
+        };
+        revision_pending
+            || status.updated_replicas.unwrap_or(0) < replicas
+            || status.ready_replicas.unwrap_or(0) < replicas
+    }
+
+    pub fn render(&self, now: DateTime<Utc>) -> String {
+        let mut output = String::new();
+        output.push_str(&format!(
+            "{} {} | Namespace: {} | {}\n\n",
+            "📊 Curvine Cluster".bold(),
+            self.cluster_id.bold(),
+            self.namespace,
+            now.format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string()
+                .bright_black()
+        ));
+
+        output.push_str(&self.render_statefulsets());
+        output.push('\n');
+        output.push_str(&self.render_pods(now));
+        output.push('\n');
+
+        output.push_str(&format!("{}\n", "Pod transitions".bold()));
+        if self.transitions.is_empty() {
+            output.push_str(&format!("  {}\n", "none yet".bright_black()));
+        }
+        for line in &self.transitions {
+            output.push_str(&format!("  {}\n", line));
+        }
+
+        output.push_str(&format!("\n{}\n", "Warnings".bold()));
+        if self.warnings.is_empty() {
+            output.push_str(&format!("  {}\n", "none".bright_black()));
+        }
+        for line in &self.warnings {
+            output.push_str(&format!("  {} {}\n", StatusIcon::WARNING.yellow(), line));
+        }
+
+        output.push_str(&format!("\n{}\n", "Press Ctrl+C to exit".bright_black()));
+        output
+    }
+
+    fn render_statefulsets(&self) -> String {
+        if self.statefulsets.is_empty() {
+            return "No StatefulSets found\n".to_string();
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("STATEFULSET").set_alignment(CellAlignment::Left),
+                Cell::new("READY").set_alignment(CellAlignment::Center),
+                Cell::new("UPDATED").set_alignment(CellAlignment::Center),
+                Cell::new("ROLLOUT").set_alignment(CellAlignment::Left),
+            ]);
+
+        for (name, statefulset) in &self.statefulsets {
+            let replicas = statefulset
+                .spec
+                .as_ref()
+                .and_then(|s| s.replicas)
+                .unwrap_or(0) as u32;
+            let status = statefulset.status.as_ref();
+            let ready = status.and_then(|s| s.ready_replicas).unwrap_or(0) as u32;
+            let updated = status.and_then(|s| s.updated_replicas).unwrap_or(0) as u32;
+
+            let (rollout, rollout_color) = if Self::rollout_in_progress(statefulset) {
+                (
+                    format!("{} In progress", StatusIcon::PENDING),
+                    self.theme.warning,
+                )
+            } else {
+                (
+                    format!("{} Complete", StatusIcon::SUCCESS),
+                    self.theme.success,
+                )
+            };
+
+            table.add_row(vec![
+                Cell::new(name),
+                Cell::new(format!(
+                    "{} {}/{}",
+                    StatusIcon::get_replica_icon(ready, replicas),
+                    ready,
+                    replicas
+                ))
+                .fg(self.theme.get_replica_color(ready, replicas)),
+                Cell::new(format!("{}/{}", updated, replicas)),
+                Cell::new(rollout).fg(rollout_color),
+            ]);
+        }
+
+        format!("{}\n", table)
+    }
+
+    fn render_pods(&self, now: DateTime<Utc>) -> String {
+        if self.pods.is_empty() {
+            return "No pods found\n".to_string();
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("POD").set_alignment(CellAlignment::Left),
+                Cell::new("PHASE").set_alignment(CellAlignment::Left),
+                Cell::new("READY").set_alignment(CellAlignment::Center),
+                Cell::new("RESTARTS").set_alignment(CellAlignment::Right),
+                Cell::new("NODE").set_alignment(CellAlignment::Left),
+                Cell::new("AGE").set_alignment(CellAlignment::Right),
+            ]);
+
+        for pod in self.pods.values() {
+            let (ready_icon, ready_color) = if pod.ready {
+                (StatusIcon::SUCCESS, self.theme.success)
+            } else {
+                (StatusIcon::ERROR, self.theme.error)
+            };
+
+            table.add_row(vec![
+                Cell::new(&pod.name),
+                Cell::new(pod.phase.as_deref().unwrap_or("Unknown")),
+                Cell::new(ready_icon).fg(ready_color),
+                Cell::new(pod.restarts).fg(if pod.restarts > 0 {
+                    self.theme.warning
+                } else {
+                    Color::Reset
+                }),
+                Cell::new(pod.node.as_deref().unwrap_or("<none>")),
+                Cell::new(format_age(pod.created_at.as_deref(), now)),
+            ]);
+        }
+
+        format!("{}\n", table)
+    }
+}
+
+fn pod_state(pod: &PodStatus) -> String {
+    format!(
+        "{}{}",
+        pod.phase.as_deref().unwrap_or("Unknown"),
+        if pod.ready { "/Ready" } else { "" }
+    )
+}
+
+fn push_bounded(lines: &mut VecDeque<String>, line: String) {
+    if lines.len() == HISTORY_LIMIT {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
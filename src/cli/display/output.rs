@@ -0,0 +1,84 @@
+//! Structured output formats for scripting (`-o json|yaml|wide`)
+//!
+//! JSON and YAML documents are wrapped in a versioned envelope:
+//!
+//! ```text
+//! schema_version: curvine-kube/v1
+//! kind: ClusterList | ClusterStatus
+//! ...                       # fields of the payload
+//! ```
+//!
+//! Fields are only ever added within a schema version; renames or removals
+//! bump `schema_version`.
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// Schema version embedded in every JSON/YAML document
+pub const OUTPUT_SCHEMA_VERSION: &str = "curvine-kube/v1";
+
+/// Output format selected with `-o/--output`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human readable table
+    #[default]
+    Table,
+    /// Table plus per-pod rows (node, IP, restarts, age, phase)
+    Wide,
+    /// Versioned JSON document
+    Json,
+    /// Versioned YAML document
+    Yaml,
+}
+
+/// Versioned envelope around a payload
+#[derive(Debug, Serialize)]
+pub struct Versioned<'a, T: Serialize> {
+    pub schema_version: &'static str,
+    pub kind: &'static str,
+    #[serde(flatten)]
+    pub data: &'a T,
+}
+
+/// Payload of `list -o json|yaml`
+#[derive(Debug, Serialize)]
+pub struct ItemList<'a, T: Serialize> {
+    pub items: &'a [T],
+}
+
+/// Render `data` as a versioned JSON or YAML document.
+///
+/// Returns `None` for the table formats.
+pub fn render_structured<T: Serialize>(
+    format: OutputFormat,
+    kind: &'static str,
+    data: &T,
+) -> anyhow::Result<Option<String>> {
+    let document = Versioned {
+        schema_version: OUTPUT_SCHEMA_VERSION,
+        kind,
+        data,
+    };
+
+    match format {
+        OutputFormat::Json => Ok(Some(serde_json::to_string_pretty(&document)?)),
+        OutputFormat::Yaml => Ok(Some(serde_yaml::to_string(&document)?)),
+        OutputFormat::Table | OutputFormat::Wide => Ok(None),
+    }
+}
+
+/// Format the time elapsed since an RFC 3339 timestamp like kubectl (`45s`, `12m`, `3h`, `5d`)
+pub fn format_age(created_at: Option<&str>, now: DateTime<Utc>) -> String {
+    let Some(created) = created_at.and_then(|t| DateTime::parse_from_rfc3339(t).ok()) else {
+        return "<unknown>".to_string();
+    };
+
+    let seconds = (now - created.with_timezone(&Utc)).num_seconds().max(0);
+    match seconds {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86400),
+    }
+}
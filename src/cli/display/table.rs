@@ -1,6 +1,11 @@
 //! Table rendering for CLI output
 
+use super::output::format_age;
 use super::{ColorTheme, StatusIcon};
+use crate::domain::cluster::descriptor::{ClusterHealth, ClusterStatus, PodStatus};
+use crate::domain::cluster::doctor::{CheckStatus, DoctorReport};
+use crate::domain::config::properties::PropertySpec;
+use crate::domain::config::resolver::{Layer, ResolvedEntry};
 use colored::Colorize;
 use comfy_table::{presets::UTF8_FULL, Cell, CellAlignment, Color, ContentArrangement, Table};
 
@@ -9,10 +14,13 @@ use comfy_table::{presets::UTF8_FULL, Cell, CellAlignment, Color, ContentArrange
 pub struct ClusterInfo {
     pub cluster_id: String,
     pub namespace: String,
+    pub context: Option<String>,
     pub master_ready: u32,
     pub master_replicas: u32,
     pub worker_ready: u32,
     pub worker_replicas: u32,
+    pub version: Option<String>,
+    pub health: ClusterHealth,
 }
 
 /// Table renderer for formatted output
@@ -40,17 +48,25 @@ impl TableRenderer {
             return "No Curvine clusters found".to_string();
         }
 
+        let show_context = clusters.iter().any(|c| c.context.is_some());
+        let mut header = Vec::new();
+        if show_context {
+            header.push(Cell::new("CONTEXT").set_alignment(CellAlignment::Left));
+        }
+        header.extend([
+            Cell::new("CLUSTER").set_alignment(CellAlignment::Left),
+            Cell::new("NAMESPACE").set_alignment(CellAlignment::Left),
+            Cell::new("VERSION").set_alignment(CellAlignment::Left),
+            Cell::new("MASTER").set_alignment(CellAlignment::Center),
+            Cell::new("WORKER").set_alignment(CellAlignment::Center),
+            Cell::new("HEALTH").set_alignment(CellAlignment::Center),
+        ]);
+
         let mut table = Table::new();
         table
             .load_preset(UTF8_FULL)
             .set_content_arrangement(ContentArrangement::Dynamic)
-            .set_header(vec![
-                Cell::new("CLUSTER").set_alignment(CellAlignment::Left),
-                Cell::new("NAMESPACE").set_alignment(CellAlignment::Left),
-                Cell::new("MASTER").set_alignment(CellAlignment::Center),
-                Cell::new("WORKER").set_alignment(CellAlignment::Center),
-                Cell::new("STATUS").set_alignment(CellAlignment::Center),
-            ]);
+            .set_header(header);
 
         for cluster in clusters {
             let master_icon = StatusIcon::get_replica_icon(
@@ -62,23 +78,11 @@ impl TableRenderer {
                 cluster.worker_replicas,
             );
 
-            // Determine overall status
-            let overall_status = if cluster.master_ready == cluster.master_replicas
-                && cluster.worker_ready == cluster.worker_replicas
-            {
-                StatusIcon::get_status_text(cluster.master_ready, cluster.master_replicas)
-            } else if cluster.master_ready > 0 || cluster.worker_ready > 0 {
-                "Degraded"
-            } else {
-                "Failed"
-            };
-
-            let status_icon = if overall_status == "Running" {
-                StatusIcon::SUCCESS
-            } else if overall_status == "Degraded" {
-                StatusIcon::WARNING
-            } else {
-                StatusIcon::ERROR
+            let status_icon = match cluster.health {
+                ClusterHealth::Healthy => StatusIcon::SUCCESS,
+                ClusterHealth::Degraded => StatusIcon::WARNING,
+                ClusterHealth::Failed => StatusIcon::ERROR,
+                ClusterHealth::Unknown => StatusIcon::UNKNOWN,
             };
 
             // Determine colors
@@ -90,17 +94,21 @@ impl TableRenderer {
                 cluster.worker_ready,
                 cluster.worker_replicas,
             );
-            let status_color = if overall_status == "Running" {
-                Color::Green
-            } else if overall_status == "Degraded" {
-                Color::Yellow
-            } else {
-                Color::Red
+            let status_color = match cluster.health {
+                ClusterHealth::Healthy => Color::Green,
+                ClusterHealth::Degraded => Color::Yellow,
+                ClusterHealth::Failed => Color::Red,
+                ClusterHealth::Unknown => Color::DarkGrey,
             };
 
-            table.add_row(vec![
+            let mut row = Vec::new();
+            if show_context {
+                row.push(Cell::new(cluster.context.as_deref().unwrap_or("-")));
+            }
+            row.extend([
                 Cell::new(&cluster.cluster_id),
                 Cell::new(&cluster.namespace),
+                Cell::new(cluster.version.as_deref().unwrap_or("-")),
                 Cell::new(format!(
                     "{} {}/{}",
                     master_icon, cluster.master_ready, cluster.master_replicas
@@ -111,16 +119,15 @@ impl TableRenderer {
                     worker_icon, cluster.worker_ready, cluster.worker_replicas
                 ))
                 .fg(worker_color),
-                Cell::new(format!("{} {}", status_icon, overall_status)).fg(status_color),
+                Cell::new(format!("{} {}", status_icon, cluster.health.as_str())).fg(status_color),
             ]);
+            table.add_row(row);
         }
 
         let mut output = String::new();
         output.push_str(&format!(
             "╭─ Curvine Clusters {} ─╮\n",
-            format!("[{} clusters]", clusters.len())
-                .bright_black()
-                .to_string()
+            format!("[{} clusters]", clusters.len()).bright_black()
         ));
         output.push_str(&table.to_string());
         output.push('\n');
@@ -135,6 +142,7 @@ impl TableRenderer {
     }
 
     /// Render cluster status in a compact three-column format
+    #[allow(clippy::too_many_arguments)]
     pub fn render_cluster_status(
         &self,
         cluster_id: &str,
@@ -185,7 +193,7 @@ impl TableRenderer {
 
         // Header
         table.add_row(vec![
-            Cell::new(format!("📊 Curvine Cluster Status")).set_alignment(CellAlignment::Center)
+            Cell::new("📊 Curvine Cluster Status").set_alignment(CellAlignment::Center)
         ]);
 
         // Basic info
@@ -244,6 +252,227 @@ impl TableRenderer {
 
         table.to_string()
     }
+
+    /// Render per-pod detail for `status`: component, readiness, PVCs and
+    /// recent Warning events, followed by details that could not be looked up
+    pub fn render_pod_details(&self, status: &ClusterStatus) -> String {
+        if status.pods.is_empty() {
+            return "No pods found".to_string();
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("POD").set_alignment(CellAlignment::Left),
+                Cell::new("COMPONENT").set_alignment(CellAlignment::Left),
+                Cell::new("PHASE").set_alignment(CellAlignment::Left),
+                Cell::new("READY").set_alignment(CellAlignment::Center),
+                Cell::new("RESTARTS").set_alignment(CellAlignment::Right),
+                Cell::new("NODE").set_alignment(CellAlignment::Left),
+                Cell::new("PVC").set_alignment(CellAlignment::Left),
+            ]);
+
+        for pod in &status.pods {
+            let (ready_icon, ready_color) = if pod.ready {
+                (StatusIcon::SUCCESS, Color::Green)
+            } else {
+                (StatusIcon::ERROR, Color::Red)
+            };
+            let pvc_bound = pod.pvcs.iter().all(|p| p.phase.as_deref() == Some("Bound"));
+            let pvcs = pod
+                .pvcs
+                .iter()
+                .map(|p| format!("{}: {}", p.name, p.phase.as_deref().unwrap_or("Unknown")))
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            table.add_row(vec![
+                Cell::new(&pod.name),
+                Cell::new(pod.component.as_deref().unwrap_or("-")),
+                Cell::new(pod.phase.as_deref().unwrap_or("Unknown")),
+                Cell::new(ready_icon).fg(ready_color),
+                Cell::new(pod.restarts).fg(if pod.restarts > 0 {
+                    Color::Yellow
+                } else {
+                    Color::Reset
+                }),
+                Cell::new(pod.node.as_deref().unwrap_or("<none>")),
+                Cell::new(if pvcs.is_empty() {
+                    "-".to_string()
+                } else {
+                    pvcs
+                })
+                .fg(if pvc_bound { Color::Reset } else { Color::Red }),
+            ]);
+        }
+
+        let mut output = table.to_string();
+        output.push('\n');
+
+        for pod in status.pods.iter().filter(|p| !p.warnings.is_empty()) {
+            output.push_str(&format!(
+                "{} Warning events for {}:\n",
+                StatusIcon::WARNING,
+                pod.name
+            ));
+            for event in &pod.warnings {
+                output.push_str(&format!(
+                    "  {} {} (x{}): {}\n",
+                    event.last_seen.as_deref().unwrap_or("-").bright_black(),
+                    event.reason.as_deref().unwrap_or("Unknown").yellow(),
+                    event.count,
+                    event.message.as_deref().unwrap_or("").trim()
+                ));
+            }
+        }
+
+        let mut unavailable: Vec<&String> =
+            status.pods.iter().flat_map(|p| &p.unavailable).collect();
+        unavailable.sort();
+        unavailable.dedup();
+        for detail in unavailable {
+            output.push_str(&format!("{} {}\n", StatusIcon::WARNING, detail));
+        }
+
+        output
+    }
+
+    /// Render one row per pod with node, IP, restarts, age and phase
+    pub fn render_pods_wide(&self, pods: &[PodStatus]) -> String {
+        if pods.is_empty() {
+            return "No pods found".to_string();
+        }
+
+        let now = chrono::Utc::now();
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("POD").set_alignment(CellAlignment::Left),
+                Cell::new("COMPONENT").set_alignment(CellAlignment::Left),
+                Cell::new("PHASE").set_alignment(CellAlignment::Left),
+                Cell::new("READY").set_alignment(CellAlignment::Center),
+                Cell::new("RESTARTS").set_alignment(CellAlignment::Right),
+                Cell::new("NODE").set_alignment(CellAlignment::Left),
+                Cell::new("IP").set_alignment(CellAlignment::Left),
+                Cell::new("AGE").set_alignment(CellAlignment::Right),
+            ]);
+
+        for pod in pods {
+            let (ready_icon, ready_color) = if pod.ready {
+                (StatusIcon::SUCCESS, Color::Green)
+            } else {
+                (StatusIcon::ERROR, Color::Red)
+            };
+            let restarts_color = if pod.restarts > 0 {
+                Color::Yellow
+            } else {
+                Color::Reset
+            };
+
+            table.add_row(vec![
+                Cell::new(&pod.name),
+                Cell::new(pod.component.as_deref().unwrap_or("-")),
+                Cell::new(pod.phase.as_deref().unwrap_or("Unknown")),
+                Cell::new(ready_icon).fg(ready_color),
+                Cell::new(pod.restarts).fg(restarts_color),
+                Cell::new(pod.node.as_deref().unwrap_or("<none>")),
+                Cell::new(pod.pod_ip.as_deref().unwrap_or("<none>")),
+                Cell::new(format_age(pod.created_at.as_deref(), now)),
+            ]);
+        }
+
+        table.to_string()
+    }
+
+    /// Render the `-D` property registry
+    pub fn render_property_keys(&self, keys: &[&PropertySpec]) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("KEY").set_alignment(CellAlignment::Left),
+                Cell::new("TYPE").set_alignment(CellAlignment::Left),
+                Cell::new("COMMANDS").set_alignment(CellAlignment::Left),
+                Cell::new("DESCRIPTION").set_alignment(CellAlignment::Left),
+            ]);
+
+        for key in keys {
+            table.add_row(vec![
+                Cell::new(key.key),
+                Cell::new(key.value_type.name()),
+                Cell::new(key.commands.join(", ")),
+                Cell::new(key.description),
+            ]);
+        }
+
+        table.to_string()
+    }
+
+    /// Render the effective configuration with the source of each value
+    pub fn render_resolved_config(&self, entries: &[ResolvedEntry]) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("KEY").set_alignment(CellAlignment::Left),
+                Cell::new("VALUE").set_alignment(CellAlignment::Left),
+                Cell::new("SOURCE").set_alignment(CellAlignment::Left),
+            ]);
+
+        for entry in entries {
+            let value = match &entry.value {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            let source = match entry.source {
+                Layer::Default => Cell::new(entry.source.as_str()).fg(Color::DarkGrey),
+                _ => Cell::new(entry.source.as_str()).fg(Color::Cyan),
+            };
+            table.add_row(vec![Cell::new(&entry.path), Cell::new(value), source]);
+        }
+
+        table.to_string()
+    }
+
+    /// Render preflight check results with a pass/warn/fail summary line
+    pub fn render_doctor_report(&self, report: &DoctorReport) -> String {
+        let mut table = Table::new();
+        table
+            .load_preset(UTF8_FULL)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec![
+                Cell::new("CHECK").set_alignment(CellAlignment::Left),
+                Cell::new("STATUS").set_alignment(CellAlignment::Center),
+                Cell::new("DETAILS").set_alignment(CellAlignment::Left),
+            ]);
+
+        for check in &report.checks {
+            let (icon, color) = match check.status {
+                CheckStatus::Pass => (StatusIcon::SUCCESS, Color::Green),
+                CheckStatus::Warn => (StatusIcon::WARNING, Color::Yellow),
+                CheckStatus::Fail => (StatusIcon::ERROR, Color::Red),
+            };
+            table.add_row(vec![
+                Cell::new(&check.name),
+                Cell::new(format!("{} {}", icon, check.status.as_str())).fg(color),
+                Cell::new(&check.message),
+            ]);
+        }
+
+        format!(
+            "{}\n{} passed, {} warnings, {} failed",
+            table,
+            report.count(CheckStatus::Pass),
+            report.count(CheckStatus::Warn),
+            report.count(CheckStatus::Fail)
+        )
+    }
 }
 
 #[cfg(test)]
@@ -267,6 +496,9 @@ mod tests {
             master_replicas: 3,
             worker_ready: 5,
             worker_replicas: 5,
+            context: None,
+            version: Some("v1.0.0".to_string()),
+            health: ClusterHealth::Healthy,
         }];
 
         let output = renderer.render_clusters_list(&clusters);
@@ -274,5 +506,30 @@ mod tests {
         assert!(output.contains("default"));
         assert!(output.contains("3/3"));
         assert!(output.contains("5/5"));
+        assert!(output.contains("v1.0.0"));
+        assert!(output.contains("Healthy"));
+        assert!(!output.contains("CONTEXT"));
+    }
+
+    #[test]
+    fn test_render_pods_wide() {
+        let renderer = TableRenderer::new();
+        let pods = vec![PodStatus {
+            name: "test-cluster-worker-0".to_string(),
+            component: Some("worker".to_string()),
+            phase: Some("Running".to_string()),
+            ready: true,
+            restarts: 2,
+            node: Some("node-a".to_string()),
+            pod_ip: Some("10.0.0.7".to_string()),
+            ..Default::default()
+        }];
+
+        let output = renderer.render_pods_wide(&pods);
+        assert!(output.contains("test-cluster-worker-0"));
+        assert!(output.contains("node-a"));
+        assert!(output.contains("10.0.0.7"));
+        assert!(output.contains("Running"));
+        assert!(output.contains("<unknown>"));
     }
 }
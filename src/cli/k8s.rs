@@ -1,45 +1,67 @@
 //! Kubernetes deployment commands
 
+use crate::cli::display::dashboard::{Dashboard, CLEAR_SCREEN};
+use crate::cli::display::output::{render_structured, ItemList, OutputFormat};
+use crate::cli::display::TableRenderer;
+use crate::domain::cluster::compat;
+use crate::domain::cluster::descriptor::{ClusterInfo, PodStatus};
+use crate::domain::cluster::diagnose::collect_bundle;
+use crate::domain::cluster::doctor::CheckStatus;
+use crate::domain::cluster::lint;
+use crate::domain::config::format::ConfigFormat;
+use crate::domain::config::overlay;
+use crate::domain::config::properties::{validate_properties, PROPERTIES};
+use crate::domain::config::resolver::{ConfigResolver, ResolvedConfig};
+use crate::domain::config::scaffold::{self, ClusterFacts, Preset, ScaffoldOptions};
+use crate::domain::config::schema;
 use crate::domain::config::ClusterConf;
-use crate::{
-    CurvineClusterDescriptor, KubernetesConfig, MasterConfig, ServiceConfig, ServiceType,
-    StorageConfig, WorkerConfig,
+use crate::infrastructure::constants::{
+    COMPONENT_MASTER, COMPONENT_WORKER, CONTAINER_NAME_MASTER, CONTAINER_NAME_WORKER,
+    CURVINE_CV_BIN, LABEL_APP, PORT_FORWARD_RETRIES, PORT_FORWARD_RETRY_DELAY,
 };
-use clap::Parser;
+use crate::infrastructure::kubernetes::exec::{exec_attached, ExecRequest};
+use crate::infrastructure::kubernetes::portforward::{self, resolve_port_mappings};
+use crate::infrastructure::kubernetes::resources::build_cli_cluster_role;
+use crate::{CurvineClusterDescriptor, CurvineKubeClientImpl, KubernetesConfig};
+use clap::{Args, Parser};
+use colored::{Color, Colorize};
+use futures::{AsyncBufReadExt, StreamExt, TryStreamExt};
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::{Event, Node, Pod};
+use k8s_openapi::api::storage::v1::StorageClass;
+use kube::api::LogParams;
+use kube::runtime::{watcher, WatchStreamExt};
+use kube::Api;
 use std::collections::HashMap;
-
-#[derive(Parser, Debug, Clone)]
-pub struct DeployCommand {
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+/// Configuration arguments shared by `deploy`, `doctor`, `validate` and
+/// `config resolve`
+#[derive(Args, Debug, Clone)]
+pub struct DeployArgs {
     /// Cluster ID (must be a valid Kubernetes name)
     /// If not provided, will use client.kubernetes.cluster_id from config file
     #[arg(long, short = 'c')]
     pub cluster_id: Option<String>,
 
-    /// Kubernetes namespace
-    #[arg(long, short = 'n', default_value = "default")]
-    pub namespace: String,
+    /// Kubernetes namespace (default: "default")
+    #[arg(long, short = 'n')]
+    pub namespace: Option<String>,
 
-    /// Path to kubeconfig file
-    /// If not specified, uses default kubeconfig resolution (KUBECONFIG env or ~/.kube/config)
+    /// Image for both master and worker (default: docker.io/curvine:latest)
     #[arg(long)]
-    pub kubeconfig: Option<String>,
+    pub image: Option<String>,
 
-    /// Kubernetes context to use
-    /// If not specified, uses current context from kubeconfig
+    /// Master replicas, must be odd for Raft (default: 3)
     #[arg(long)]
-    pub context: Option<String>,
-
-    /// Image for both master and worker
-    #[arg(long, default_value = "docker.io/curvine:latest")]
-    pub image: String,
-
-    /// Master replicas (must be odd for Raft)
-    #[arg(long, default_value = "3")]
-    pub master_replicas: u32,
+    pub master_replicas: Option<u32>,
 
-    /// Worker replicas
-    #[arg(long, default_value = "3")]
-    pub worker_replicas: u32,
+    /// Worker replicas (default: 3)
+    #[arg(long)]
+    pub worker_replicas: Option<u32>,
 
     /// Master Pod template file path (YAML)
     #[arg(long)]
@@ -69,22 +91,35 @@ pub struct DeployCommand {
     #[arg(long)]
     pub worker_storage_size: Option<String>,
 
-    /// Service type (ClusterIP, NodePort, LoadBalancer)
-    #[arg(long, default_value = "ClusterIP")]
-    pub service_type: String,
+    /// Service type: ClusterIP, NodePort or LoadBalancer (default: ClusterIP)
+    #[arg(long)]
+    pub service_type: Option<String>,
 
-    /// Image pull policy
-    #[arg(long, default_value = "IfNotPresent")]
-    pub image_pull_policy: String,
+    /// Image pull policy (default: IfNotPresent)
+    #[arg(long)]
+    pub image_pull_policy: Option<String>,
 
     /// Path to Curvine cluster configuration file (curvine-cluster.toml)
     /// If not provided, only Kubernetes deployment will be created (no Curvine config)
     #[arg(long, value_name = "PATH")]
     pub config_file: Option<String>,
 
+    /// Profile overlay merged over the configuration file: `prod` loads
+    /// curvine-cluster.prod.toml next to curvine-cluster.toml
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Format of the configuration file: toml, yaml or json (default: from the extension)
+    #[arg(long, value_name = "FORMAT")]
+    pub config_format: Option<ConfigFormat>,
+
+    /// Curvine version of the images (default: from the image tag)
+    #[arg(long, value_name = "VERSION")]
+    pub curvine_version: Option<String>,
+
     /// Dynamic configuration properties to override any settings (-D key=value)
+    /// Unknown keys and invalid values are rejected; `curvine-kube config keys` lists all keys
     ///
-    /// Basic: kubernetes.namespace, kubernetes.cluster-id, kubernetes.config.file, kubernetes.context
     /// Images: kubernetes.container.image (sets both master and worker), kubernetes.image.pull-policy
     /// Replicas: kubernetes.master.replicas, kubernetes.worker.replicas
     /// Resources: kubernetes.master.cpu, kubernetes.master.memory, kubernetes.worker.cpu, kubernetes.worker.memory
@@ -94,22 +129,44 @@ pub struct DeployCommand {
     /// Security: kubernetes.master.service-account, kubernetes.worker.service-account
     /// Environment: kubernetes.master.env.VAR_NAME, kubernetes.worker.env.VAR_NAME (dynamic env vars)
     /// DNS & Priority: kubernetes.pod.dns-policy, kubernetes.pod.priority-class
-    /// Storage: kubernetes.storage.class, kubernetes.storage.size
+    /// Storage: kubernetes.storage.class, kubernetes.storage.size, kubernetes.storage.master-size, kubernetes.storage.worker-size
     /// Service: kubernetes.service.type, kubernetes.service.external-ips (comma-separated)
-    /// Templates: kubernetes.master.pod-template, kubernetes.worker.pod-template (for complex config like tolerations)
+    /// Templates: kubernetes.master.pod-template, kubernetes.worker.pod-template
+    /// Any field: kubernetes.<path> sets that KubernetesConfig field, values as JSON/YAML
+    /// (e.g. kubernetes.master.tolerations='[{"key":"dedicated","operator":"Exists"}]')
     ///
     /// Example: -Dkubernetes.master.cpu=2.0 -Dkubernetes.master.labels=app=curvine,tier=master
     #[arg(short = 'D', value_name = "KEY=VALUE")]
     pub properties: Vec<String>,
 }
 
+#[derive(Parser, Debug, Clone)]
+pub struct DeployCommand {
+    #[command(flatten)]
+    pub args: DeployArgs,
+
+    /// Path to kubeconfig file
+    /// If not specified, uses default kubeconfig resolution (KUBECONFIG env or ~/.kube/config)
+    #[arg(long)]
+    pub kubeconfig: Option<String>,
+
+    /// Kubernetes context to use
+    /// If not specified, uses current context from kubeconfig
+    #[arg(long)]
+    pub context: Option<String>,
+
+    /// Skip the preflight checks run before deploying (see `doctor`)
+    #[arg(long)]
+    pub skip_preflight: bool,
+}
+
 #[derive(Parser, Debug, Clone)]
 pub struct UpdateCommand {
     #[arg(long, short = 'c')]
     pub cluster_id: Option<String>,
 
-    #[arg(long, short = 'n', default_value = "default")]
-    pub namespace: String,
+    #[arg(long, short = 'n')]
+    pub namespace: Option<String>,
 
     #[arg(long)]
     pub kubeconfig: Option<String>,
@@ -124,6 +181,19 @@ pub struct UpdateCommand {
     #[arg(long, num_args = 0..=1, default_missing_value = "${CURVINE_CONF_FILE}", value_name = "PATH")]
     pub config_file: Option<String>,
 
+    /// Profile overlay merged over the configuration file (e.g. `prod` for
+    /// curvine-cluster.prod.toml)
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Format of the configuration file: toml, yaml or json (default: from the extension)
+    #[arg(long, value_name = "FORMAT")]
+    pub config_format: Option<ConfigFormat>,
+
+    /// Curvine version of the images (default: from the image tag)
+    #[arg(long, value_name = "VERSION")]
+    pub curvine_version: Option<String>,
+
     /// Image for both master and worker
     #[arg(long)]
     pub image: Option<String>,
@@ -146,14 +216,36 @@ pub struct UpdateCommand {
     #[arg(long)]
     pub image_pull_policy: Option<String>,
 
+    /// Dynamic configuration properties (-D key=value), see `curvine-kube config keys --command update`
     #[arg(short = 'D', value_name = "KEY=VALUE")]
     pub properties: Vec<String>,
 }
 
 #[derive(Parser, Debug)]
 pub struct ListCommand {
-    #[arg(long, short = 'n')]
+    /// Kubernetes namespace (defaults to the namespace of the kubeconfig context)
+    #[arg(long, short = 'n', conflicts_with = "all_namespaces")]
     pub namespace: Option<String>,
+
+    /// List clusters in all namespaces
+    #[arg(long, short = 'A')]
+    pub all_namespaces: bool,
+
+    /// Kubeconfig file path
+    #[arg(long)]
+    pub kubeconfig: Option<String>,
+
+    /// Kubernetes context
+    #[arg(long, conflicts_with = "contexts")]
+    pub context: Option<String>,
+
+    /// Aggregate clusters across several kubeconfig contexts (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "CONTEXT,...")]
+    pub contexts: Vec<String>,
+
+    /// Output format
+    #[arg(long, short = 'o', value_enum, default_value = "table")]
+    pub output: OutputFormat,
 }
 
 #[derive(Parser, Debug)]
@@ -173,6 +265,14 @@ pub struct StatusCommand {
     /// Kubernetes context
     #[arg(long)]
     pub context: Option<String>,
+
+    /// Output format
+    #[arg(long, short = 'o', value_enum, default_value = "table")]
+    pub output: OutputFormat,
+
+    /// Watch pods, StatefulSets and events and redraw a live dashboard
+    #[arg(long, short = 'w')]
+    pub watch: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -198,238 +298,359 @@ pub struct DeleteCommand {
     pub context: Option<String>,
 }
 
-impl DeployCommand {
-    pub async fn execute(&self) -> anyhow::Result<()> {
-        // Load cluster configuration - optional, use defaults if not provided
-        let cluster_conf = if let Some(ref config_path) = self.config_file {
-            ClusterConf::from(config_path)?
-        } else if let Ok(env_path) = std::env::var("CURVINE_CONF_FILE") {
-            // Try to use CURVINE_CONF_FILE environment variable if set
-            ClusterConf::from(&env_path)?
-        } else {
-            // Use default configuration if no config file is provided
-            println!("ℹ️  No configuration file specified, using default settings");
-            ClusterConf::default()
-        };
-        // Parse dynamic configurations
-        let dynamic_configs = if !self.properties.is_empty() {
-            Some(
-                parse_dynamic_configs(&self.properties)
-                    .map_err(|e| anyhow::anyhow!("Failed to parse dynamic configs: {}", e))?,
-            )
-        } else {
-            None
-        };
+#[derive(Parser, Debug)]
+pub struct RbacCommand {
+    #[command(subcommand)]
+    pub action: RbacAction,
+}
 
-        // Note: Dynamic configs are now applied directly to KubernetesConfig
-        // via crate::domain::config::dynamic::apply_to_kube_config() later in this function.
-        let cmd = self;
+#[derive(clap::Subcommand, Debug)]
+pub enum RbacAction {
+    /// Print the minimal ClusterRole required by curvine-kube itself (YAML)
+    Print {
+        /// ClusterRole name
+        #[arg(long, default_value = "curvine-kube")]
+        name: String,
+    },
+}
 
-        // Get Kubernetes configuration from ClientConf.kubernetes
-        // Priority: command line > config file > defaults
-        let kube_conf = cluster_conf.client.kubernetes.as_ref();
+#[derive(Parser, Debug)]
+pub struct ConfigCommand {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
 
-        // Resolve cluster_id: command line > config file > error
-        let cluster_id = cmd
-            .cluster_id
-            .clone()
-            .or_else(|| kube_conf.and_then(|k| k.cluster_id.clone()))
-            .ok_or_else(|| anyhow::anyhow!("cluster_id is required (use --cluster-id)"))?;
+#[derive(clap::Subcommand, Debug)]
+pub enum ConfigAction {
+    /// List the supported -D property keys with their types and descriptions
+    Keys {
+        /// Only list keys applicable to this command (deploy, update)
+        #[arg(long)]
+        command: Option<String>,
+
+        /// Output format
+        #[arg(long, short = 'o', value_enum, default_value = "table")]
+        output: OutputFormat,
+    },
+
+    /// Print the configuration file with the profile overlay merged over it
+    Render {
+        /// Path to the base configuration file (default: $CURVINE_CONF_FILE)
+        #[arg(long, value_name = "PATH")]
+        config_file: Option<String>,
+
+        /// Profile overlay to merge (e.g. `prod` for curvine-cluster.prod.toml)
+        #[arg(long, value_name = "NAME")]
+        profile: Option<String>,
+
+        /// Format of the configuration file (default: from the extension)
+        #[arg(long, value_name = "FORMAT")]
+        config_format: Option<ConfigFormat>,
+
+        /// Output format (table prints TOML)
+        #[arg(long, short = 'o', value_enum, default_value = "table")]
+        output: OutputFormat,
+    },
+
+    /// Convert a configuration file between TOML, YAML and JSON; `${...}`
+    /// references are kept, comments are not
+    Convert {
+        /// Path to the configuration file (default: $CURVINE_CONF_FILE)
+        #[arg(long, value_name = "PATH")]
+        config_file: Option<String>,
+
+        /// Format of the configuration file (default: from the extension)
+        #[arg(long, value_name = "FORMAT")]
+        config_format: Option<ConfigFormat>,
+
+        /// Target format: toml, yaml or json
+        #[arg(long, value_name = "FORMAT")]
+        to: ConfigFormat,
+    },
+
+    /// Print the effective deployment configuration and where each value came from
+    /// (default, config-file, environment, property or flag)
+    Resolve {
+        #[command(flatten)]
+        args: Box<DeployArgs>,
+
+        /// Output format
+        #[arg(long, short = 'o', value_enum, default_value = "table")]
+        output: OutputFormat,
+    },
+
+    /// Print the JSON Schema of the configuration file, for editor validation
+    /// and completion (taplo, yaml-language-server)
+    Schema,
+}
 
-        // Resolve namespace: command line > config file > default
-        let namespace = if !cmd.namespace.is_empty() && cmd.namespace != "default" {
-            cmd.namespace.clone()
-        } else {
-            kube_conf
-                .map(|k| k.namespace.clone())
-                .unwrap_or_else(|| cmd.namespace.clone())
-        };
+#[derive(Parser, Debug)]
+pub struct LogsCommand {
+    /// Cluster ID
+    #[arg(long, short = 'c')]
+    pub cluster_id: Option<String>,
 
-        // Resolve service type: command line > config file > default
-        let service_type_str = if cmd.service_type != "ClusterIP" {
-            cmd.service_type.clone()
-        } else {
-            kube_conf
-                .map(|k| k.service.service_type.clone())
-                .unwrap_or_else(|| cmd.service_type.clone())
-        };
-        let service_type = service_type_str
-            .parse::<ServiceType>()
-            .map_err(|e| anyhow::anyhow!("Invalid service type: {}", e))?;
+    /// Kubernetes namespace
+    #[arg(long, short = 'n', default_value = "default")]
+    pub namespace: String,
 
-        // Resolve master config: command line > config file > defaults
-        let master_replicas = if cmd.master_replicas != 3 {
-            cmd.master_replicas
-        } else {
-            kube_conf
-                .map(|k| k.master.replicas)
-                .unwrap_or(cmd.master_replicas)
-        };
+    /// Only show logs of this component
+    #[arg(long, value_enum)]
+    pub component: Option<LogComponent>,
 
-        // Priority: --image > config file > default
-        let master_image = if cmd.image != "docker.io/curvine:latest" {
-            cmd.image.clone()
-        } else {
-            kube_conf
-                .map(|k| k.master.image.clone())
-                .unwrap_or_else(|| cmd.image.clone())
-        };
+    /// Only show logs of the pod with this StatefulSet ordinal
+    #[arg(long, value_name = "N")]
+    pub pod: Option<u32>,
 
-        let master_pod_template = cmd
-            .master_pod_template
-            .clone()
-            .or_else(|| kube_conf.and_then(|k| k.master.pod_template.clone()));
+    /// Follow the log streams
+    #[arg(long, short = 'f')]
+    pub follow: bool,
 
-        let master_node_selector = kube_conf.and_then(|k| k.master.node_selector.clone());
+    /// Only return logs newer than a relative duration like 30s, 10m or 2h
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration_secs)]
+    pub since: Option<i64>,
 
-        // Resolve worker config: command line > config file > defaults
-        let worker_replicas = if cmd.worker_replicas != 3 {
-            cmd.worker_replicas
-        } else {
-            kube_conf
-                .map(|k| k.worker.replicas)
-                .unwrap_or(cmd.worker_replicas)
-        };
+    /// Show logs of the previous container instance
+    #[arg(long)]
+    pub previous: bool,
 
-        // Priority: --image > config file > default
-        let worker_image = if cmd.image != "docker.io/curvine:latest" {
-            cmd.image.clone()
-        } else {
-            kube_conf
-                .map(|k| k.worker.image.clone())
-                .unwrap_or_else(|| cmd.image.clone())
-        };
+    /// Write each pod's log to <DIR>/<pod>.log instead of the terminal
+    #[arg(long, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
 
-        let worker_pod_template = cmd
-            .worker_pod_template
-            .clone()
-            .or_else(|| kube_conf.and_then(|k| k.worker.pod_template.clone()));
+    /// Kubeconfig file path
+    #[arg(long)]
+    pub kubeconfig: Option<String>,
 
-        let worker_node_selector = kube_conf.and_then(|k| k.worker.node_selector.clone());
+    /// Kubernetes context
+    #[arg(long)]
+    pub context: Option<String>,
+}
 
-        // Resolve storage config: command line > config file
-        let storage_class = cmd
-            .storage_class
-            .clone()
-            .or_else(|| kube_conf.and_then(|k| k.worker.storage_class.clone()))
-            .or_else(|| {
-                kube_conf.and_then(|k| k.storage.as_ref().map(|s| s.storage_class.clone()))
-            });
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogComponent {
+    Master,
+    Worker,
+}
 
-        let master_storage_size = cmd.master_storage_size.clone().or_else(|| {
-            kube_conf.and_then(|k| k.storage.as_ref().and_then(|s| s.master_size.clone()))
-        });
+impl LogComponent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogComponent::Master => COMPONENT_MASTER,
+            LogComponent::Worker => COMPONENT_WORKER,
+        }
+    }
+}
 
-        let worker_storage_size = cmd.worker_storage_size.clone().or_else(|| {
-            kube_conf.and_then(|k| k.storage.as_ref().and_then(|s| s.worker_size.clone()))
-        });
+#[derive(Parser, Debug)]
+pub struct CvCommand {
+    /// Cluster ID
+    #[arg(long, short = 'c')]
+    pub cluster_id: Option<String>,
 
-        // Resolve image pull policy: command line > config file > default
-        let image_pull_policy = if cmd.image_pull_policy != "IfNotPresent" {
-            cmd.image_pull_policy.clone()
-        } else {
-            kube_conf
-                .map(|k| k.image_pull_policy.clone())
-                .unwrap_or_else(|| cmd.image_pull_policy.clone())
-        };
+    /// Kubernetes namespace
+    #[arg(long, short = 'n', default_value = "default")]
+    pub namespace: String,
 
-        // Build Kubernetes configuration
-        let mut kube_config = KubernetesConfig {
-            cluster_id,
-            namespace: namespace.clone(),
-            master: MasterConfig {
-                replicas: master_replicas,
-                image: master_image,
-                resources: None,
-                node_selector: master_node_selector,
-                affinity: None,
-                pod_template: master_pod_template,
-                graceful_shutdown: kube_conf
-                    .map(|k| k.master.graceful_shutdown)
-                    .unwrap_or(true),
-                labels: HashMap::new(),
-                annotations: HashMap::new(),
-                tolerations: Vec::new(),
-                service_account: None,
-                env_vars: HashMap::new(),
-                dns_policy: None,
-                priority_class: None,
-            },
-            worker: WorkerConfig {
-                replicas: worker_replicas,
-                image: worker_image,
-                resources: None,
-                node_selector: worker_node_selector,
-                anti_affinity: false,
-                pod_template: worker_pod_template,
-                storage_class: storage_class.clone(),
-                graceful_shutdown: kube_conf
-                    .map(|k| k.worker.graceful_shutdown)
-                    .unwrap_or(true),
-                host_network: kube_conf.map(|k| k.worker.host_network).unwrap_or(false),
-                init_container: kube_conf.map(|k| k.worker.init_container).unwrap_or(false),
-                host_path_storage: None,
-                labels: HashMap::new(),
-                annotations: HashMap::new(),
-                tolerations: Vec::new(),
-                service_account: None,
-                env_vars: HashMap::new(),
-                dns_policy: None,
-                priority_class: None,
-            },
-            service: ServiceConfig {
-                service_type,
-                annotations: kube_conf
-                    .map(|k| k.service.annotations.clone())
-                    .unwrap_or_default(),
-                session_affinity: kube_conf.and_then(|k| k.service.session_affinity.clone()),
-                external_ips: kube_conf
-                    .map(|k| k.service.external_ips.clone())
-                    .unwrap_or_default(),
-                load_balancer_source_ranges: Vec::new(),
-            },
-            storage: storage_class
-                .as_ref()
-                .or(cmd.master_storage_class.as_ref())
-                .or(cmd.worker_storage_class.as_ref())
-                .or(master_storage_size.as_ref())
-                .or(worker_storage_size.as_ref())
-                .map(|_| {
-                    let fallback_sc = cmd
-                        .master_storage_class
-                        .as_ref()
-                        .or(cmd.worker_storage_class.as_ref())
-                        .cloned()
-                        .unwrap_or_default();
-                    StorageConfig {
-                        storage_class: storage_class.clone().unwrap_or(fallback_sc),
-                        master_storage_class: cmd.master_storage_class.clone(),
-                        worker_storage_class: cmd.worker_storage_class.clone(),
-                        master_size: master_storage_size.clone(),
-                        worker_size: worker_storage_size.clone(),
-                    }
-                }),
-            image_pull_policy,
-            image_pull_secrets: kube_conf
-                .map(|k| k.image_pull_secrets.clone())
-                .unwrap_or_default(),
-            cluster_domain: "cluster.local".to_string(),
-        };
+    /// Master pod to run in (defaults to the first ready master, which need
+    /// not be the Raft leader)
+    #[arg(long)]
+    pub pod: Option<String>,
 
-        // Apply advanced dynamic configurations directly to kube_config
-        if let Some(ref configs) = dynamic_configs {
-            crate::domain::config::dynamic::apply_to_kube_config(configs, &mut kube_config);
-        }
+    /// Pass stdin to cv; the exec ends when stdin does (implied in a terminal)
+    #[arg(long, short = 'i')]
+    pub stdin: bool,
+
+    /// Kubeconfig file path
+    #[arg(long)]
+    pub kubeconfig: Option<String>,
+
+    /// Kubernetes context
+    #[arg(long)]
+    pub context: Option<String>,
+
+    /// Arguments passed to cv, after `--`
+    #[arg(last = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct DiagnoseCommand {
+    /// Cluster ID
+    #[arg(long, short = 'c')]
+    pub cluster_id: Option<String>,
+
+    /// Kubernetes namespace
+    #[arg(long, short = 'n', default_value = "default")]
+    pub namespace: String,
+
+    /// Archive to write (defaults to <cluster-id>-diagnose-<timestamp>.tar.gz)
+    #[arg(long, short = 'o', value_name = "PATH")]
+    pub out: Option<PathBuf>,
+
+    /// Kubeconfig file path
+    #[arg(long)]
+    pub kubeconfig: Option<String>,
+
+    /// Kubernetes context
+    #[arg(long)]
+    pub context: Option<String>,
+}
+
+/// Runs the deploy preflight checks without deploying; accepts the same
+/// configuration arguments as `deploy`
+#[derive(Parser, Debug)]
+pub struct DoctorCommand {
+    #[command(flatten)]
+    pub args: DeployArgs,
+
+    /// Path to kubeconfig file
+    #[arg(long)]
+    pub kubeconfig: Option<String>,
+
+    /// Kubernetes context
+    #[arg(long)]
+    pub context: Option<String>,
+
+    /// Output format
+    #[arg(long, short = 'o', value_enum, default_value = "table")]
+    pub output: OutputFormat,
+}
+
+/// Generates a commented curvine-cluster.toml from a preset
+#[derive(Parser, Debug)]
+pub struct InitCommand {
+    /// Preset: dev, prod, cache-only or ha (default: dev, or asked interactively)
+    #[arg(long, value_name = "PRESET")]
+    pub preset: Option<Preset>,
+
+    /// Cluster ID (default: curvine, or asked interactively)
+    #[arg(long, short = 'c')]
+    pub cluster_id: Option<String>,
+
+    /// Kubernetes namespace (default: default, or asked interactively)
+    #[arg(long, short = 'n')]
+    pub namespace: Option<String>,
+
+    /// Image for both master and worker (default: docker.io/curvine:latest)
+    #[arg(long)]
+    pub image: Option<String>,
+
+    /// Storage class for PVCs (default: the cluster default with --discover)
+    #[arg(long)]
+    pub storage_class: Option<String>,
+
+    /// File to write, `-` for stdout
+    #[arg(
+        long,
+        short = 'o',
+        default_value = "curvine-cluster.toml",
+        value_name = "PATH"
+    )]
+    pub output: String,
+
+    /// Overwrite an existing file
+    #[arg(long)]
+    pub force: bool,
+
+    /// Query the cluster for StorageClasses, node labels and zones to prefill values
+    #[arg(long)]
+    pub discover: bool,
+
+    /// Never prompt; take values from the flags and the preset (for CI).
+    /// Implied when stdin is not a terminal
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Kubeconfig file path (with --discover)
+    #[arg(long)]
+    pub kubeconfig: Option<String>,
+
+    /// Kubernetes context (with --discover)
+    #[arg(long)]
+    pub context: Option<String>,
+}
+
+/// Checks a configuration file offline (no cluster access); accepts the same
+/// configuration arguments as `deploy`
+#[derive(Parser, Debug)]
+pub struct ValidateCommand {
+    #[command(flatten)]
+    pub args: DeployArgs,
+
+    /// Output format (json and yaml emit machine-readable diagnostics)
+    #[arg(long, short = 'o', value_enum, default_value = "table")]
+    pub output: OutputFormat,
+}
+
+#[derive(Parser, Debug)]
+pub struct PortForwardCommand {
+    /// Cluster ID
+    #[arg(long, short = 'c')]
+    pub cluster_id: Option<String>,
+
+    /// Kubernetes namespace
+    #[arg(long, short = 'n', default_value = "default")]
+    pub namespace: String,
+
+    /// Component to forward to (the first ready pod of it)
+    #[arg(long, value_enum, default_value = "master")]
+    pub component: LogComponent,
+
+    /// Named container ports to forward, optionally with a local port (NAME[:LOCAL])
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "web",
+        value_name = "NAME[:LOCAL],..."
+    )]
+    pub ports: Vec<String>,
+
+    /// Local address to listen on
+    #[arg(long, default_value = "127.0.0.1")]
+    pub address: String,
+
+    /// Kubeconfig file path
+    #[arg(long)]
+    pub kubeconfig: Option<String>,
+
+    /// Kubernetes context
+    #[arg(long)]
+    pub context: Option<String>,
+}
+
+impl DeployCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        let (cluster_conf, kube_config) = self.args.resolve_configs()?;
+        // Not part of the skippable preflight: an incompatible image is refused
+        check_version_compatibility(&cluster_conf, &kube_config)?;
 
         // Create cluster descriptor with kubeconfig options
         let descriptor = CurvineClusterDescriptor::new_with_config(
-            namespace,
-            cmd.kubeconfig.clone(),
-            cmd.context.clone(),
+            kube_config.namespace.clone(),
+            self.kubeconfig.clone(),
+            self.context.clone(),
         )
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create cluster descriptor: {}", e))?;
 
+        if !self.skip_preflight {
+            let report = descriptor.preflight(&cluster_conf, &kube_config).await;
+            if report.has_failures() {
+                println!("{}", TableRenderer::new().render_doctor_report(&report));
+                anyhow::bail!(
+                    "Preflight checks failed ({} failed); fix them or rerun with --skip-preflight",
+                    report.count(CheckStatus::Fail)
+                );
+            }
+            // The version checks were reported above
+            for check in report.checks.iter().filter(|c| {
+                c.status == CheckStatus::Warn && !c.name.starts_with(compat::VERSION_CHECK)
+            }) {
+                println!("⚠️  Preflight {}: {}", check.name, check.message);
+            }
+        }
+
         // Deploy cluster
         descriptor
             .deploy_cluster(&cluster_conf, &kube_config)
@@ -449,17 +670,88 @@ impl DeployCommand {
     }
 }
 
-impl UpdateCommand {
-    pub async fn execute(&self) -> anyhow::Result<()> {
-        // Load cluster configuration - required
-        let cluster_conf = if let Some(ref config_file_path) = self.config_file {
-            // Handle ${CURVINE_CONF_FILE} placeholder
-            let actual_path = if config_file_path == "${CURVINE_CONF_FILE}" {
-                std::env::var("CURVINE_CONF_FILE").map_err(|_| {
-                    anyhow::anyhow!("❌ CURVINE_CONF_FILE environment variable not set\n\n  Usage:\n  export CURVINE_CONF_FILE=/path/to/your/curvine-cluster.toml\n  curvine-kube update -c test --config-file")
-                })?
-            } else {
-                config_file_path.clone()
+impl DeployArgs {
+    /// Resolve the Curvine and Kubernetes configuration a deployment would use
+    pub fn resolve_configs(&self) -> anyhow::Result<(ClusterConf, KubernetesConfig)> {
+        let (cluster_conf, resolved) = self.resolve()?;
+        Ok((cluster_conf, resolved.config))
+    }
+
+    /// Resolve the configuration layer by layer (defaults < config file <
+    /// environment < -D properties < flags), keeping each value's source
+    pub fn resolve(&self) -> anyhow::Result<(ClusterConf, ResolvedConfig)> {
+        // Load cluster configuration - optional, use defaults if not provided
+        let profile = self.profile.as_deref();
+        let cluster_conf = if let Some(ref config_path) = self.config_file {
+            ClusterConf::load(config_path, profile, self.config_format)?
+        } else if let Ok(env_path) = std::env::var("CURVINE_CONF_FILE") {
+            // Try to use CURVINE_CONF_FILE environment variable if set
+            ClusterConf::load(&env_path, profile, self.config_format)?
+        } else if profile.is_some() {
+            anyhow::bail!("--profile requires a configuration file (use --config-file)");
+        } else {
+            // Use default configuration if no config file is provided
+            eprintln!("ℹ️  No configuration file specified, using default settings");
+            ClusterConf::default()
+        };
+
+        let properties = parse_dynamic_configs(&self.properties)
+            .map_err(|e| anyhow::anyhow!("Failed to parse dynamic configs: {}", e))?;
+        validate_properties(&properties, "deploy", &file_kube_config(&cluster_conf)?)?;
+
+        let mut resolver = layered_resolver(&cluster_conf, &properties)?;
+
+        let master_replicas = self.master_replicas.map(|n| n.to_string());
+        let worker_replicas = self.worker_replicas.map(|n| n.to_string());
+        for (path, value) in [
+            ("cluster_id", self.cluster_id.as_deref()),
+            ("namespace", self.namespace.as_deref()),
+            ("master.image", self.image.as_deref()),
+            ("worker.image", self.image.as_deref()),
+            ("master.replicas", master_replicas.as_deref()),
+            ("worker.replicas", worker_replicas.as_deref()),
+            ("master.pod_template", self.master_pod_template.as_deref()),
+            ("worker.pod_template", self.worker_pod_template.as_deref()),
+            ("storage.storage_class", self.storage_class.as_deref()),
+            ("worker.storage_class", self.storage_class.as_deref()),
+            (
+                "storage.master_storage_class",
+                self.master_storage_class.as_deref(),
+            ),
+            (
+                "storage.worker_storage_class",
+                self.worker_storage_class.as_deref(),
+            ),
+            ("storage.master_size", self.master_storage_size.as_deref()),
+            ("storage.worker_size", self.worker_storage_size.as_deref()),
+            ("service.service_type", self.service_type.as_deref()),
+            ("image_pull_policy", self.image_pull_policy.as_deref()),
+            ("curvine_version", self.curvine_version.as_deref()),
+        ] {
+            resolver.flag(path, value)?;
+        }
+
+        let resolved = resolver.finish();
+        if resolved.config.cluster_id.is_empty() {
+            anyhow::bail!("cluster_id is required (use --cluster-id)");
+        }
+        let mut cluster_conf = cluster_conf;
+        apply_version_defaults(&mut cluster_conf, &resolved.config)?;
+        Ok((cluster_conf, resolved))
+    }
+}
+
+impl UpdateCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        // Load cluster configuration - required
+        let cluster_conf = if let Some(ref config_file_path) = self.config_file {
+            // Handle ${CURVINE_CONF_FILE} placeholder
+            let actual_path = if config_file_path == "${CURVINE_CONF_FILE}" {
+                std::env::var("CURVINE_CONF_FILE").map_err(|_| {
+                    anyhow::anyhow!("❌ CURVINE_CONF_FILE environment variable not set\n\n  Usage:\n  export CURVINE_CONF_FILE=/path/to/your/curvine-cluster.toml\n  curvine-kube update -c test --config-file")
+                })?
+            } else {
+                config_file_path.clone()
             };
 
             // Validate file exists
@@ -468,11 +760,18 @@ impl UpdateCommand {
             }
 
             // Load the new configuration
-            let conf = ClusterConf::from(&actual_path).map_err(|e| {
-                anyhow::anyhow!("Failed to load configuration from {}: {}", actual_path, e)
-            })?;
-
-            println!("✓ Loaded new configuration from: {}", actual_path);
+            let conf = ClusterConf::load(&actual_path, self.profile.as_deref(), self.config_format)
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to load configuration from {}: {}", actual_path, e)
+                })?;
+
+            match &self.profile {
+                Some(profile) => println!(
+                    "✓ Loaded new configuration from: {} (profile: {})",
+                    actual_path, profile
+                ),
+                None => println!("✓ Loaded new configuration from: {}", actual_path),
+            }
             println!("  Note: Dynamic parameters (master addresses, journal addresses) will be regenerated from cluster state");
 
             conf
@@ -482,7 +781,7 @@ impl UpdateCommand {
                 .map_err(|_| anyhow::anyhow!(
                     "Configuration file is required. Please specify --config-file or set CURVINE_CONF_FILE environment variable"
                 ))?;
-            ClusterConf::from(&env_path)?
+            ClusterConf::load(&env_path, self.profile.as_deref(), self.config_format)?
         };
 
         // Check if master_replicas is being updated (not supported)
@@ -492,176 +791,43 @@ impl UpdateCommand {
             );
         }
 
-        // Parse dynamic configurations
-        let dynamic_configs = if !self.properties.is_empty() {
-            Some(
-                parse_dynamic_configs(&self.properties)
-                    .map_err(|e| anyhow::anyhow!("Failed to parse dynamic configs: {}", e))?,
-            )
-        } else {
-            None
-        };
-
-        // Note: Dynamic configs are now applied directly to KubernetesConfig
-        // via crate::domain::config::dynamic::apply_to_kube_config() later in this function.
-        let cmd = self;
-
-        // Get Kubernetes configuration from ClientConf.kubernetes or use command line arguments
-        let kube_conf = cluster_conf.client.kubernetes.as_ref();
-
-        // Resolve cluster_id: command line > config file > error
-        let cluster_id = cmd
-            .cluster_id
-            .clone()
-            .or_else(|| kube_conf.and_then(|k| k.cluster_id.clone()))
-            .ok_or_else(|| anyhow::anyhow!("cluster_id is required (use --cluster-id)"))?;
-
-        // Resolve namespace: command line > config file > default
-        let namespace = if !cmd.namespace.is_empty() && cmd.namespace != "default" {
-            cmd.namespace.clone()
-        } else {
-            kube_conf
-                .map(|k| k.namespace.clone())
-                .unwrap_or_else(|| cmd.namespace.clone())
-        };
-
-        // Resolve service type: only use if explicitly provided
-        let service_type_str = cmd
-            .service_type
-            .clone()
-            .or_else(|| kube_conf.map(|k| k.service.service_type.clone()));
-        let service_type = if let Some(st) = service_type_str {
-            st.parse::<ServiceType>()
-                .map_err(|e| anyhow::anyhow!("Invalid service type: {}", e))?
-        } else {
-            ServiceType::ClusterIP
-        };
-
-        let master_replicas = kube_conf.map(|k| k.master.replicas).unwrap_or(1) as i32;
-
-        // Priority: --image > config file
-        let master_image = cmd
-            .image
-            .clone()
-            .or_else(|| kube_conf.map(|k| k.master.image.clone()));
-
-        let master_pod_template = cmd
-            .master_pod_template
-            .clone()
-            .or_else(|| kube_conf.and_then(|k| k.master.pod_template.clone()));
-
-        let master_node_selector = kube_conf.and_then(|k| k.master.node_selector.clone());
-
-        // Resolve worker config: only update if explicitly provided
-        let worker_replicas = cmd
-            .worker_replicas
-            .or_else(|| kube_conf.map(|k| k.worker.replicas));
-
-        // Priority: --image > config file
-        let worker_image = cmd
-            .image
-            .clone()
-            .or_else(|| kube_conf.map(|k| k.worker.image.clone()));
-
-        let worker_pod_template = cmd
-            .worker_pod_template
-            .clone()
-            .or_else(|| kube_conf.and_then(|k| k.worker.pod_template.clone()));
-
-        let worker_node_selector = kube_conf.and_then(|k| k.worker.node_selector.clone());
-
-        // Note: storage_class and storage_size are not updatable
-        // They only affect new PVCs, and existing PVCs cannot be modified in K8s
-        // Use the existing storage config from cluster
-        let storage_config = kube_conf.and_then(|k| {
-            k.storage.as_ref().map(|s| StorageConfig {
-                storage_class: s.storage_class.clone(),
-                master_storage_class: s.master_storage_class.clone(),
-                worker_storage_class: s.worker_storage_class.clone(),
-                master_size: s.master_size.clone(),
-                worker_size: s.worker_size.clone(),
-            })
-        });
-
-        // Resolve image pull policy: only update if explicitly provided
-        let image_pull_policy = cmd
-            .image_pull_policy
-            .clone()
-            .or_else(|| kube_conf.map(|k| k.image_pull_policy.clone()));
-
-        // Build Kubernetes configuration with optional fields
-        let mut kube_config = KubernetesConfig {
-            cluster_id,
-            namespace: namespace.clone(),
-            master: MasterConfig {
-                replicas: master_replicas as u32,
-                image: master_image.unwrap_or_else(|| "docker.io/curvine:latest".to_string()),
-                resources: None,
-                node_selector: master_node_selector,
-                affinity: None,
-                pod_template: master_pod_template,
-                graceful_shutdown: kube_conf
-                    .map(|k| k.master.graceful_shutdown)
-                    .unwrap_or(true),
-                labels: HashMap::new(),
-                annotations: HashMap::new(),
-                tolerations: Vec::new(),
-                service_account: None,
-                env_vars: HashMap::new(),
-                dns_policy: None,
-                priority_class: None,
-            },
-            worker: WorkerConfig {
-                replicas: worker_replicas.unwrap_or(3),
-                image: worker_image.unwrap_or_else(|| "docker.io/curvine:latest".to_string()),
-                resources: None,
-                node_selector: worker_node_selector,
-                anti_affinity: false,
-                pod_template: worker_pod_template,
-                storage_class: storage_config.as_ref().map(|s| s.storage_class.clone()),
-                graceful_shutdown: kube_conf
-                    .map(|k| k.worker.graceful_shutdown)
-                    .unwrap_or(true),
-                host_network: kube_conf.map(|k| k.worker.host_network).unwrap_or(false),
-                init_container: kube_conf.map(|k| k.worker.init_container).unwrap_or(false),
-                host_path_storage: None,
-                labels: HashMap::new(),
-                annotations: HashMap::new(),
-                tolerations: Vec::new(),
-                service_account: None,
-                env_vars: HashMap::new(),
-                dns_policy: None,
-                priority_class: None,
-            },
-            service: ServiceConfig {
-                service_type,
-                annotations: kube_conf
-                    .map(|k| k.service.annotations.clone())
-                    .unwrap_or_default(),
-                session_affinity: kube_conf.and_then(|k| k.service.session_affinity.clone()),
-                external_ips: kube_conf
-                    .map(|k| k.service.external_ips.clone())
-                    .unwrap_or_default(),
-                load_balancer_source_ranges: Vec::new(),
-            },
-            storage: storage_config,
-            image_pull_policy: image_pull_policy.unwrap_or_else(|| "IfNotPresent".to_string()),
-            image_pull_secrets: kube_conf
-                .map(|k| k.image_pull_secrets.clone())
-                .unwrap_or_default(),
-            cluster_domain: "cluster.local".to_string(),
-        };
+        let properties = parse_dynamic_configs(&self.properties)
+            .map_err(|e| anyhow::anyhow!("Failed to parse dynamic configs: {}", e))?;
+        validate_properties(&properties, "update", &file_kube_config(&cluster_conf)?)?;
+
+        // Storage is fixed after deploy and only comes from the config file
+        let mut resolver = layered_resolver(&cluster_conf, &properties)?;
+
+        let worker_replicas = self.worker_replicas.map(|n| n.to_string());
+        for (path, value) in [
+            ("cluster_id", self.cluster_id.as_deref()),
+            ("namespace", self.namespace.as_deref()),
+            ("master.image", self.image.as_deref()),
+            ("worker.image", self.image.as_deref()),
+            ("worker.replicas", worker_replicas.as_deref()),
+            ("master.pod_template", self.master_pod_template.as_deref()),
+            ("worker.pod_template", self.worker_pod_template.as_deref()),
+            ("service.service_type", self.service_type.as_deref()),
+            ("image_pull_policy", self.image_pull_policy.as_deref()),
+            ("curvine_version", self.curvine_version.as_deref()),
+        ] {
+            resolver.flag(path, value)?;
+        }
 
-        // Apply advanced dynamic configurations directly to kube_config
-        if let Some(ref configs) = dynamic_configs {
-            crate::domain::config::dynamic::apply_to_kube_config(configs, &mut kube_config);
+        let kube_config = resolver.finish().config;
+        if kube_config.cluster_id.is_empty() {
+            anyhow::bail!("cluster_id is required (use --cluster-id)");
         }
+        let mut cluster_conf = cluster_conf;
+        apply_version_defaults(&mut cluster_conf, &kube_config)?;
+        check_version_compatibility(&cluster_conf, &kube_config)?;
+        let namespace = kube_config.namespace.clone();
 
         // Create cluster descriptor with kubeconfig options
         let descriptor = CurvineClusterDescriptor::new_with_config(
             namespace,
-            cmd.kubeconfig.clone(),
-            cmd.context.clone(),
+            self.kubeconfig.clone(),
+            self.context.clone(),
         )
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create cluster descriptor: {}", e))?;
@@ -701,11 +867,23 @@ impl StatusCommand {
         .await
         .map_err(|e| anyhow::anyhow!("Failed to create cluster descriptor: {}", e))?;
 
+        if self.watch {
+            if !matches!(self.output, OutputFormat::Table | OutputFormat::Wide) {
+                anyhow::bail!("--watch only supports table output");
+            }
+            return watch_cluster(&descriptor, cluster_id).await;
+        }
+
         let status = descriptor
             .get_cluster_status(cluster_id)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to get cluster status: {}", e))?;
 
+        if let Some(document) = render_structured(self.output, "ClusterStatus", &status)? {
+            println!("{}", document);
+            return Ok(());
+        }
+
         // Extract status information
         let master_name = status.master.as_ref().map(|m| m.name.as_str());
         let master_ready = status.master.as_ref().map(|m| m.ready_replicas).unwrap_or(0);
@@ -740,6 +918,11 @@ impl StatusCommand {
         );
 
         println!("{}", output);
+        print!("{}", renderer.render_pod_details(&status));
+
+        if self.output == OutputFormat::Wide {
+            println!("{}", renderer.render_pods_wide(&status.pods));
+        }
 
         Ok(())
     }
@@ -770,48 +953,87 @@ impl DeleteCommand {
     }
 }
 
+type ClusterPods = (ClusterInfo, Vec<PodStatus>);
+
 impl ListCommand {
     pub async fn execute(&self) -> anyhow::Result<()> {
-        use crate::cli::display::{table::ClusterInfo, TableRenderer};
+        use crate::cli::display::{table, TableRenderer};
 
-        let namespaces: Vec<String> = if let Some(ref ns) = self.namespace {
-            vec![ns.clone()]
+        let targets: Vec<Option<String>> = if self.contexts.is_empty() {
+            vec![self.context.clone()]
         } else {
-            vec!["default".to_string(), "curvine".to_string()]
+            self.contexts.iter().cloned().map(Some).collect()
         };
 
+        let results = futures::future::join_all(
+            targets
+                .iter()
+                .map(|context| self.list_in_context(context.clone())),
+        )
+        .await;
+
         let mut all_clusters = Vec::new();
+        let mut all_pods = Vec::new();
+        let mut failures = Vec::new();
+        for (context, result) in targets.iter().zip(results) {
+            match result {
+                Ok((clusters, pods)) => {
+                    all_clusters.extend(clusters);
+                    all_pods.extend(pods);
+                }
+                Err(e) => failures.push(format!(
+                    "context '{}': {}",
+                    context.as_deref().unwrap_or("current"),
+                    e
+                )),
+            }
+        }
 
-        for namespace in &namespaces {
-            let descriptor = match CurvineClusterDescriptor::new(namespace.clone()).await {
-                Ok(d) => d,
-                Err(_) => continue,
-            };
+        for failure in &failures {
+            eprintln!("⚠️  Failed to list clusters in {}", failure);
+        }
+        if failures.len() == targets.len() {
+            anyhow::bail!("Failed to list clusters: {}", failures.join("; "));
+        }
 
-            match descriptor.list_clusters().await {
-                Ok(clusters) => all_clusters.extend(clusters),
-                Err(_) => continue,
-            }
+        if let Some(document) = render_structured(
+            self.output,
+            "ClusterList",
+            &ItemList {
+                items: &all_clusters,
+            },
+        )? {
+            println!("{}", document);
+            return Ok(());
         }
 
         if all_clusters.is_empty() {
-            println!(
-                "No Curvine clusters found in namespace(s): {}",
-                namespaces.join(", ")
-            );
+            if self.all_namespaces {
+                println!("No Curvine clusters found in any namespace");
+            } else {
+                println!(
+                    "No Curvine clusters found in namespace {}",
+                    self.namespace
+                        .as_deref()
+                        .unwrap_or("of the current context")
+                );
+            }
             return Ok(());
         }
 
         // Convert to ClusterInfo for rendering
-        let cluster_infos: Vec<ClusterInfo> = all_clusters
+        let cluster_infos: Vec<table::ClusterInfo> = all_clusters
             .iter()
-            .map(|c| ClusterInfo {
+            .map(|c| table::ClusterInfo {
                 cluster_id: c.cluster_id.clone(),
                 namespace: c.namespace.clone(),
+                context: c.context.clone(),
                 master_ready: c.master_ready,
                 master_replicas: c.master_replicas,
                 worker_ready: c.worker_ready,
                 worker_replicas: c.worker_replicas,
+                version: c.version.clone(),
+                health: c.health,
             })
             .collect();
 
@@ -820,10 +1042,894 @@ impl ListCommand {
         let output = renderer.render_clusters_list(&cluster_infos);
         println!("{}", output);
 
+        for (cluster, pods) in &all_pods {
+            match &cluster.context {
+                Some(context) => println!(
+                    "Pods of {}/{}/{}:",
+                    context, cluster.namespace, cluster.cluster_id
+                ),
+                None => println!("Pods of {}/{}:", cluster.namespace, cluster.cluster_id),
+            }
+            println!("{}", renderer.render_pods_wide(pods));
+        }
+
+        Ok(())
+    }
+
+    /// List clusters (and, for `-o wide`, their pods) in one kubeconfig context
+    async fn list_in_context(
+        &self,
+        context: Option<String>,
+    ) -> anyhow::Result<(Vec<ClusterInfo>, Vec<ClusterPods>)> {
+        let namespace = match &self.namespace {
+            Some(ns) => ns.clone(),
+            None => {
+                CurvineKubeClientImpl::default_namespace(self.kubeconfig.clone(), context.clone())
+                    .await?
+            }
+        };
+        let descriptor = CurvineClusterDescriptor::new_with_config(
+            namespace.clone(),
+            self.kubeconfig.clone(),
+            context.clone(),
+        )
+        .await?;
+
+        let mut clusters = if self.all_namespaces {
+            descriptor.list_clusters_all_namespaces().await?
+        } else {
+            descriptor.list_clusters().await?
+        };
+        if !self.contexts.is_empty() {
+            for cluster in &mut clusters {
+                cluster.context = context.clone();
+            }
+        }
+
+        let mut pods = Vec::new();
+        if self.output == OutputFormat::Wide {
+            let mut descriptors = HashMap::new();
+            descriptors.insert(namespace, descriptor);
+            for cluster in &clusters {
+                if !descriptors.contains_key(&cluster.namespace) {
+                    let descriptor = CurvineClusterDescriptor::new_with_config(
+                        cluster.namespace.clone(),
+                        self.kubeconfig.clone(),
+                        context.clone(),
+                    )
+                    .await?;
+                    descriptors.insert(cluster.namespace.clone(), descriptor);
+                }
+                let cluster_pods = descriptors[&cluster.namespace]
+                    .list_cluster_pods(&cluster.cluster_id)
+                    .await?;
+                pods.push((cluster.clone(), cluster_pods));
+            }
+        }
+
+        Ok((clusters, pods))
+    }
+}
+
+/// Line prefix colors, assigned to pods in order
+const LOG_PREFIX_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Magenta,
+    Color::Blue,
+    Color::BrightRed,
+];
+
+impl LogsCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        let cluster_id = self
+            .cluster_id
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("cluster_id is required (use -c/--cluster-id)"))?;
+
+        let descriptor = CurvineClusterDescriptor::new_with_config(
+            self.namespace.clone(),
+            self.kubeconfig.clone(),
+            self.context.clone(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create cluster descriptor: {}", e))?;
+
+        let pods = descriptor
+            .list_cluster_pods(cluster_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to list pods: {}", e))?;
+        let pods = select_log_pods(&pods, self.component, self.pod);
+        if pods.is_empty() {
+            anyhow::bail!("No pods of cluster {} match the selection", cluster_id);
+        }
+
+        if let Some(dir) = &self.dir {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let api: Api<Pod> = Api::namespaced(descriptor.kube_client(), &self.namespace);
+        let results = futures::future::join_all(
+            pods.iter()
+                .enumerate()
+                .map(|(index, pod)| self.stream_pod_logs(&api, pod, index)),
+        )
+        .await;
+
+        let failures: Vec<String> = results
+            .into_iter()
+            .filter_map(|r| r.err().map(|e| e.to_string()))
+            .collect();
+        for failure in &failures {
+            eprintln!("⚠️  {}", failure);
+        }
+        if failures.len() == pods.len() {
+            anyhow::bail!("Failed to fetch logs from any pod");
+        }
+
+        if let Some(dir) = &self.dir {
+            println!(
+                "Wrote logs of {} pod(s) to {}",
+                pods.len() - failures.len(),
+                dir.display()
+            );
+        }
+        Ok(())
+    }
+
+    async fn stream_pod_logs(
+        &self,
+        api: &Api<Pod>,
+        pod: &PodStatus,
+        index: usize,
+    ) -> anyhow::Result<()> {
+        let container = match pod.component.as_deref() {
+            Some(COMPONENT_MASTER) => Some(CONTAINER_NAME_MASTER.to_string()),
+            Some(COMPONENT_WORKER) => Some(CONTAINER_NAME_WORKER.to_string()),
+            _ => None,
+        };
+        let params = LogParams {
+            container,
+            follow: self.follow,
+            previous: self.previous,
+            since_seconds: self.since,
+            ..Default::default()
+        };
+
+        let mut lines = api
+            .log_stream(&pod.name, &params)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch logs of {}: {}", pod.name, e))?
+            .lines();
+
+        match &self.dir {
+            Some(dir) => {
+                let path = dir.join(format!("{}.log", pod.name));
+                let mut file = tokio::fs::File::create(&path).await?;
+                while let Some(line) = lines.try_next().await? {
+                    file.write_all(line.as_bytes()).await?;
+                    file.write_all(b"\n").await?;
+                }
+                file.flush().await?;
+            }
+            None => {
+                let color = LOG_PREFIX_COLORS[index % LOG_PREFIX_COLORS.len()];
+                let prefix = format!("[{}]", pod.name).color(color);
+                while let Some(line) = lines.try_next().await? {
+                    println!("{} {}", prefix, line);
+                }
+            }
+        }
         Ok(())
     }
 }
 
+/// Pods matching the `logs` component and ordinal filters
+pub fn select_log_pods(
+    pods: &[PodStatus],
+    component: Option<LogComponent>,
+    ordinal: Option<u32>,
+) -> Vec<PodStatus> {
+    pods.iter()
+        .filter(|pod| component.is_none_or(|c| pod.component.as_deref() == Some(c.as_str())))
+        .filter(|pod| {
+            ordinal.is_none_or(|n| pod.name.rsplit('-').next() == Some(n.to_string().as_str()))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Parse a relative duration such as `45s`, `10m`, `2h` or `1d` into seconds
+pub fn parse_duration_secs(value: &str) -> Result<i64, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let number: i64 = number
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}': expected e.g. 30s, 10m, 2h", value))?;
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => {
+            return Err(format!(
+                "Invalid duration unit '{}': expected s, m, h or d",
+                unit
+            ))
+        }
+    };
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("Duration '{}' is too large", value))
+}
+
+impl CvCommand {
+    /// Run cv in a master pod and return its exit code
+    pub async fn execute(&self) -> anyhow::Result<i32> {
+        let cluster_id = self
+            .cluster_id
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("cluster_id is required (use -c/--cluster-id)"))?;
+
+        let descriptor = CurvineClusterDescriptor::new_with_config(
+            self.namespace.clone(),
+            self.kubeconfig.clone(),
+            self.context.clone(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create cluster descriptor: {}", e))?;
+
+        let pod = match &self.pod {
+            Some(pod) => pod.clone(),
+            None => descriptor
+                .select_exec_master(cluster_id)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to select master pod: {}", e))?,
+        };
+
+        let mut command = vec![CURVINE_CV_BIN.to_string()];
+        command.extend(self.args.iter().cloned());
+        let request = ExecRequest::new(pod, Some(CONTAINER_NAME_MASTER.to_string()), command)
+            .with_stdin(self.stdin)
+            .with_terminal_tty();
+
+        exec_attached(&descriptor.kube_client(), &self.namespace, &request)
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))
+    }
+}
+
+impl DoctorCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        let (cluster_conf, kube_config) = self.args.resolve_configs()?;
+        let descriptor = CurvineClusterDescriptor::new_with_config(
+            kube_config.namespace.clone(),
+            self.kubeconfig.clone(),
+            self.context.clone(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create cluster descriptor: {}", e))?;
+
+        let report = descriptor.preflight(&cluster_conf, &kube_config).await;
+        match render_structured(self.output, "DoctorReport", &report)? {
+            Some(document) => println!("{}", document),
+            None => println!("{}", TableRenderer::new().render_doctor_report(&report)),
+        }
+
+        if report.has_failures() {
+            anyhow::bail!(
+                "{} preflight check(s) failed",
+                report.count(CheckStatus::Fail)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// `--config-file`, falling back to `CURVINE_CONF_FILE`
+fn config_file_or_env(config_file: &Option<String>) -> anyhow::Result<String> {
+    match config_file {
+        Some(path) => Ok(path.clone()),
+        None => std::env::var("CURVINE_CONF_FILE")
+            .map_err(|_| anyhow::anyhow!("--config-file is required (or set CURVINE_CONF_FILE)")),
+    }
+}
+
+impl InitCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        let interactive = !self.non_interactive && std::io::stdin().is_terminal();
+        if self.output != "-" && !self.force && std::path::Path::new(&self.output).exists() {
+            anyhow::bail!(
+                "{} already exists; use --force to overwrite it or -o to write elsewhere",
+                self.output
+            );
+        }
+
+        let namespace = match &self.namespace {
+            Some(namespace) => namespace.clone(),
+            None if interactive => prompt("Namespace", "default")?,
+            None => "default".to_string(),
+        };
+        let facts = if self.discover {
+            self.discover_facts(&namespace).await
+        } else {
+            ClusterFacts::default()
+        };
+
+        let preset = match self.preset {
+            Some(preset) => preset,
+            None if interactive => {
+                let names: Vec<&str> = Preset::ALL.iter().map(Preset::as_str).collect();
+                prompt(&format!("Preset ({})", names.join(", ")), "dev")?.parse()?
+            }
+            None => Preset::default(),
+        };
+        let cluster_id = match &self.cluster_id {
+            Some(cluster_id) => cluster_id.clone(),
+            None if interactive => prompt("Cluster ID", "curvine")?,
+            None => "curvine".to_string(),
+        };
+        let storage_class = match &self.storage_class {
+            Some(class) => Some(class.clone()),
+            None if interactive && !facts.storage_classes.is_empty() => {
+                let default = facts
+                    .default_storage_class
+                    .clone()
+                    .unwrap_or_else(|| facts.storage_classes[0].clone());
+                Some(prompt(
+                    &format!("StorageClass ({})", facts.storage_classes.join(", ")),
+                    &default,
+                )?)
+            }
+            None => None,
+        };
+
+        let options = ScaffoldOptions {
+            preset,
+            cluster_id,
+            namespace,
+            image: self
+                .image
+                .clone()
+                .unwrap_or_else(|| KubernetesConfig::default().master.image),
+            storage_class,
+        };
+        let content = scaffold::render(&options, &facts);
+
+        // The scaffold must deploy as written
+        let cluster_conf: ClusterConf = toml::from_str(&content)?;
+        let mut resolver = ConfigResolver::new();
+        if let Some(kube_conf) = &cluster_conf.client.kubernetes {
+            resolver.config_file(kube_conf)?;
+        }
+        resolver.finish().config.validate()?;
+
+        if self.output == "-" {
+            print!("{}", content);
+            return Ok(());
+        }
+        std::fs::write(&self.output, &content)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", self.output, e))?;
+        println!("✓ Wrote {} (preset: {})", self.output, preset.as_str());
+        println!(
+            "  Next: curvine-kube validate --config-file {0} && curvine-kube deploy --config-file {0}",
+            self.output
+        );
+        Ok(())
+    }
+
+    /// StorageClasses and nodes of the target cluster; empty when it cannot be reached
+    async fn discover_facts(&self, namespace: &str) -> ClusterFacts {
+        let discovered = async {
+            let descriptor = CurvineClusterDescriptor::new_with_config(
+                namespace.to_string(),
+                self.kubeconfig.clone(),
+                self.context.clone(),
+            )
+            .await?;
+            let client = descriptor.kube_client();
+            let storage_classes = Api::<StorageClass>::all(client.clone())
+                .list(&Default::default())
+                .await?;
+            let nodes = Api::<Node>::all(client).list(&Default::default()).await?;
+            anyhow::Ok(ClusterFacts::from_objects(
+                &storage_classes.items,
+                &nodes.items,
+            ))
+        };
+        match discovered.await {
+            Ok(facts) => facts,
+            Err(e) => {
+                eprintln!("⚠️  Cannot query the cluster, using preset defaults: {}", e);
+                ClusterFacts::default()
+            }
+        }
+    }
+}
+
+impl ValidateCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        let file = config_file_or_env(&self.args.config_file)?;
+        let format = ConfigFormat::resolve(self.args.config_format, &file);
+        let source = std::fs::read_to_string(&file)
+            .map_err(|e| anyhow::anyhow!("Failed to read config file {}: {}", file, e))?;
+        let overlay = match &self.args.profile {
+            Some(profile) => {
+                let path = overlay::profile_path(std::path::Path::new(&file), profile)
+                    .display()
+                    .to_string();
+                let source = std::fs::read_to_string(&path).map_err(|e| {
+                    anyhow::anyhow!("Failed to read profile '{}' {}: {}", profile, path, e)
+                })?;
+                Some((path, source))
+            }
+            None => None,
+        };
+
+        let parsed =
+            lint::parse_cluster_conf_as(&file, &source, format).and_then(|conf| match &overlay {
+                Some((path, source)) => lint::parse_cluster_conf_as(path, source, format),
+                None => Ok(conf),
+            });
+        let mut report = match parsed {
+            Err(diagnostic) => lint::ValidationReport {
+                diagnostics: vec![diagnostic],
+            },
+            Ok(_) => {
+                let properties = parse_dynamic_configs(&self.args.properties)
+                    .map_err(|e| anyhow::anyhow!("Failed to parse dynamic configs: {}", e))?;
+                // Load errors are reported by `resolve_configs` below
+                let base =
+                    ClusterConf::load(&file, self.args.profile.as_deref(), self.args.config_format)
+                        .and_then(|conf| file_kube_config(&conf))
+                        .unwrap_or_default();
+                let property_report = lint::lint_properties(&properties, "deploy", &base);
+                if property_report.has_errors() {
+                    return self.report(&file, property_report);
+                }
+                let args = DeployArgs {
+                    config_file: Some(file.clone()),
+                    ..self.args.clone()
+                };
+                match args.resolve_configs() {
+                    Ok((cluster_conf, kube_config)) => {
+                        lint::lint(&file, &source, &properties, &cluster_conf, &kube_config)
+                    }
+                    Err(e) => lint::ValidationReport {
+                        diagnostics: vec![lint::Diagnostic {
+                            severity: lint::Severity::Error,
+                            file: file.clone(),
+                            line: None,
+                            key: None,
+                            message: e.to_string(),
+                        }],
+                    },
+                }
+            }
+        };
+        if let Some((path, source)) = &overlay {
+            report.relocate(path, source);
+        }
+
+        self.report(&file, report)
+    }
+
+    fn report(&self, file: &str, report: lint::ValidationReport) -> anyhow::Result<()> {
+        match render_structured(self.output, "ValidationReport", &report)? {
+            Some(document) => println!("{}", document),
+            None => {
+                for diagnostic in &report.diagnostics {
+                    println!("{}", diagnostic);
+                }
+                println!(
+                    "{}: {} error(s), {} warning(s)",
+                    file,
+                    report.count(lint::Severity::Error),
+                    report.count(lint::Severity::Warning)
+                );
+            }
+        }
+
+        if report.has_errors() {
+            anyhow::bail!("{} is invalid", file);
+        }
+        Ok(())
+    }
+}
+
+impl DiagnoseCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        let cluster_id = self
+            .cluster_id
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("cluster_id is required (use -c/--cluster-id)"))?;
+
+        let descriptor = CurvineClusterDescriptor::new_with_config(
+            self.namespace.clone(),
+            self.kubeconfig.clone(),
+            self.context.clone(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create cluster descriptor: {}", e))?;
+
+        println!("🔍 Collecting diagnostics for cluster {}...", cluster_id);
+        let bundle = collect_bundle(&descriptor, cluster_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to collect diagnostics: {}", e))?;
+
+        let root = format!(
+            "{}-diagnose-{}",
+            cluster_id,
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        );
+        let out = self
+            .out
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("{}.tar.gz", root)));
+        let file = std::fs::File::create(&out)
+            .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", out.display(), e))?;
+        bundle.write_tar_gz(&root, std::io::BufWriter::new(file))?;
+
+        if let Some(summary) = bundle.get("summary.txt") {
+            print!("\n{}", String::from_utf8_lossy(summary));
+        }
+        println!("\n✅ Support bundle written to {}", out.display());
+        Ok(())
+    }
+}
+
+impl PortForwardCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        let cluster_id = self
+            .cluster_id
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("cluster_id is required (use -c/--cluster-id)"))?;
+
+        let descriptor = CurvineClusterDescriptor::new_with_config(
+            self.namespace.clone(),
+            self.kubeconfig.clone(),
+            self.context.clone(),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create cluster descriptor: {}", e))?;
+
+        let component = self.component.as_str();
+        let container = match self.component {
+            LogComponent::Master => CONTAINER_NAME_MASTER,
+            LogComponent::Worker => CONTAINER_NAME_WORKER,
+        };
+        let pod = descriptor
+            .select_ready_pod(&cluster_id, component)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to select {} pod: {}", component, e))?;
+        let mappings = resolve_port_mappings(&pod, container, &self.ports)?;
+        let pod_name = pod.metadata.name.unwrap_or_default();
+
+        let forwarder = Arc::new(PortForwarder {
+            descriptor,
+            cluster_id,
+            component,
+            target: tokio::sync::RwLock::new(pod_name.clone()),
+        });
+
+        for mapping in mappings {
+            let listener = tokio::net::TcpListener::bind((self.address.as_str(), mapping.local))
+                .await
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to listen on {}:{}: {}",
+                        self.address,
+                        mapping.local,
+                        e
+                    )
+                })?;
+            println!(
+                "Forwarding {}:{} -> {}:{} ({})",
+                self.address, mapping.local, pod_name, mapping.remote, mapping.name
+            );
+
+            let forwarder = forwarder.clone();
+            tokio::spawn(async move {
+                while let Ok((local, _)) = listener.accept().await {
+                    let forwarder = forwarder.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = forwarder.forward(local, mapping.remote).await {
+                            eprintln!("{}", e.to_string().red());
+                        }
+                    });
+                }
+            });
+        }
+
+        println!("{}", "Press Ctrl+C to stop forwarding".bright_black());
+        tokio::signal::ctrl_c().await?;
+        Ok(())
+    }
+}
+
+/// Shared state of the `port-forward` listeners
+struct PortForwarder {
+    descriptor: CurvineClusterDescriptor,
+    cluster_id: String,
+    component: &'static str,
+    /// Pod currently forwarded to; replaced when it becomes unavailable
+    target: tokio::sync::RwLock<String>,
+}
+
+impl PortForwarder {
+    /// Forward one local connection, re-selecting the target pod while it is
+    /// unavailable (e.g. restarting)
+    async fn forward(&self, local: tokio::net::TcpStream, port: u16) -> anyhow::Result<()> {
+        let client = self.descriptor.kube_client();
+        let namespace = self.descriptor.namespace();
+
+        for attempt in 0..=PORT_FORWARD_RETRIES {
+            let pod = self.target.read().await.clone();
+            match portforward::connect(&client, namespace, &pod, port).await {
+                Ok(stream) => return Ok(portforward::forward(stream, local, &pod, port).await?),
+                Err(e) if attempt == PORT_FORWARD_RETRIES => return Err(e.into()),
+                Err(e) => {
+                    eprintln!("{} unavailable ({}), reconnecting...", pod, e);
+                    tokio::time::sleep(std::time::Duration::from_secs(PORT_FORWARD_RETRY_DELAY))
+                        .await;
+                }
+            }
+
+            if let Ok(selected) = self
+                .descriptor
+                .select_ready_pod(&self.cluster_id, self.component)
+                .await
+            {
+                let name = selected.metadata.name.unwrap_or_default();
+                let mut target = self.target.write().await;
+                if *target != name {
+                    println!("Forwarding to {} now", name);
+                    *target = name;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RbacCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        match &self.action {
+            RbacAction::Print { name } => {
+                let role = build_cli_cluster_role(name);
+                print!("{}", serde_yaml::to_string(&role)?);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ConfigCommand {
+    pub async fn execute(&self) -> anyhow::Result<()> {
+        match &self.action {
+            ConfigAction::Keys { command, output } => {
+                let keys: Vec<_> = PROPERTIES
+                    .iter()
+                    .filter(|p| command.as_deref().is_none_or(|c| p.commands.contains(&c)))
+                    .collect();
+                match render_structured(*output, "PropertyKeys", &ItemList { items: &keys })? {
+                    Some(document) => println!("{}", document),
+                    None => println!("{}", TableRenderer::new().render_property_keys(&keys)),
+                }
+            }
+            ConfigAction::Render {
+                config_file,
+                profile,
+                config_format,
+                output,
+            } => {
+                let file = config_file_or_env(config_file)?;
+                let format = ConfigFormat::resolve(*config_format, &file);
+                // `${file:...}` references usually hold secrets
+                let merged = overlay::load_redacted(&file, profile.as_deref(), format)?;
+                // Fail on documents that would not load as a ClusterConf
+                let _: ClusterConf = toml::Value::Table(merged.clone()).try_into()?;
+                match render_structured(*output, "ClusterConfig", &merged)? {
+                    Some(document) => println!("{}", document),
+                    None => print!("{}", toml::to_string_pretty(&merged)?),
+                }
+            }
+            ConfigAction::Convert {
+                config_file,
+                config_format,
+                to,
+            } => {
+                let file = config_file_or_env(config_file)?;
+                let content = std::fs::read_to_string(&file)
+                    .map_err(|e| anyhow::anyhow!("Failed to read config file {}: {}", file, e))?;
+                let document: toml::Table = ConfigFormat::resolve(*config_format, &file)
+                    .parse(&content)
+                    .map_err(|e| anyhow::anyhow!("{}: {}", file, e))?;
+                print!("{}", to.render(&document)?);
+            }
+            ConfigAction::Resolve { args, output } => {
+                let (_, resolved) = args.resolve()?;
+                let entries = resolved.entries();
+                match render_structured(*output, "ResolvedConfig", &ItemList { items: &entries })? {
+                    Some(document) => println!("{}", document),
+                    None => println!("{}", TableRenderer::new().render_resolved_config(&entries)),
+                }
+            }
+            ConfigAction::Schema => {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&schema::cluster_conf_schema())?
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+enum WatchUpdate {
+    Pod(Box<watcher::Event<Pod>>),
+    StatefulSet(Box<watcher::Event<StatefulSet>>),
+    Event(Box<watcher::Event<Event>>),
+}
+
+/// Drive the `status --watch` dashboard until Ctrl+C
+async fn watch_cluster(
+    descriptor: &CurvineClusterDescriptor,
+    cluster_id: &str,
+) -> anyhow::Result<()> {
+    let client = descriptor.kube_client();
+    let namespace = descriptor.namespace();
+    let selector = format!("{}={}", LABEL_APP, cluster_id);
+
+    let pods = watcher(
+        Api::<Pod>::namespaced(client.clone(), namespace),
+        watcher::Config::default().labels(&selector),
+    )
+    .default_backoff()
+    .map(|e| e.map(|ev| WatchUpdate::Pod(Box::new(ev))));
+    let statefulsets = watcher(
+        Api::<StatefulSet>::namespaced(client.clone(), namespace),
+        watcher::Config::default().labels(&selector),
+    )
+    .default_backoff()
+    .map(|e| e.map(|ev| WatchUpdate::StatefulSet(Box::new(ev))));
+    let events = watcher(
+        Api::<Event>::namespaced(client, namespace),
+        watcher::Config::default().fields("type=Warning"),
+    )
+    .default_backoff()
+    .map(|e| e.map(|ev| WatchUpdate::Event(Box::new(ev))));
+    let mut updates =
+        futures::stream::select_all(vec![pods.boxed(), statefulsets.boxed(), events.boxed()]);
+
+    let mut dashboard = Dashboard::new(cluster_id.to_string(), namespace.to_string());
+    let mut tick = tokio::time::interval(std::time::Duration::from_secs(1));
+
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tick.tick() => {}
+            update = updates.next() => {
+                let now = chrono::Utc::now();
+                match update {
+                    None => return Ok(()),
+                    Some(Err(e)) => dashboard.push_warning(format!("watch error: {}", e)),
+                    Some(Ok(WatchUpdate::Pod(event))) => match *event {
+                        watcher::Event::Apply(pod) | watcher::Event::InitApply(pod) => {
+                            dashboard.apply_pod(&pod, now)
+                        }
+                        watcher::Event::Delete(pod) => dashboard.delete_pod(&pod, now),
+                        watcher::Event::InitDone => dashboard.pods_synced(),
+                        watcher::Event::Init => {}
+                    },
+                    Some(Ok(WatchUpdate::StatefulSet(event))) => match *event {
+                        watcher::Event::Apply(ss) | watcher::Event::InitApply(ss) => {
+                            dashboard.apply_statefulset(&ss)
+                        }
+                        watcher::Event::Delete(ss) => dashboard.delete_statefulset(&ss),
+                        watcher::Event::Init | watcher::Event::InitDone => {}
+                    },
+                    Some(Ok(WatchUpdate::Event(event))) => match *event {
+                        watcher::Event::Apply(ev) | watcher::Event::InitApply(ev) => {
+                            dashboard.apply_event(&ev)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+
+        print!("{}{}", CLEAR_SCREEN, dashboard.render(chrono::Utc::now()));
+        std::io::stdout().flush()?;
+    }
+}
+
+/// `[client.kubernetes]` of the configuration file alone, which `-D`
+/// properties are checked against
+fn file_kube_config(cluster_conf: &ClusterConf) -> anyhow::Result<KubernetesConfig> {
+    let mut resolver = ConfigResolver::new();
+    if let Some(kube_conf) = &cluster_conf.client.kubernetes {
+        resolver.config_file(kube_conf)?;
+    }
+    Ok(resolver.finish().config)
+}
+
+/// Resolver with the config file, environment and `-D` layers applied;
+/// the caller adds its flags
+fn layered_resolver(
+    cluster_conf: &ClusterConf,
+    properties: &HashMap<String, String>,
+) -> anyhow::Result<ConfigResolver> {
+    let unmodeled = cluster_conf.unmodeled_keys();
+    if !unmodeled.is_empty() {
+        eprintln!(
+            "⚠️  Passing through configuration keys not modeled by curvine-kube: {}",
+            unmodeled.join(", ")
+        );
+    }
+
+    let mut resolver = ConfigResolver::new();
+    if let Some(kube_conf) = &cluster_conf.client.kubernetes {
+        for key in resolver.config_file(kube_conf)? {
+            eprintln!("⚠️  Ignoring unknown configuration key {}", key);
+        }
+    }
+    resolver.environment(|name| std::env::var(name).ok())?;
+    if !properties.is_empty() {
+        resolver.properties(properties)?;
+    }
+    Ok(resolver)
+}
+
+/// Apply the defaults of the image's Curvine version to the values the
+/// configuration leaves at the modeled default
+fn apply_version_defaults(
+    cluster_conf: &mut ClusterConf,
+    kube_config: &KubernetesConfig,
+) -> anyhow::Result<()> {
+    let applied = compat::apply_schema_defaults(cluster_conf, kube_config, compat::SCHEMAS)?;
+    if !applied.is_empty() {
+        eprintln!(
+            "ℹ️  Applied Curvine {} defaults: {}",
+            compat::find_schema(kube_config, compat::SCHEMAS)?
+                .map(|schema| schema.series)
+                .unwrap_or_default(),
+            applied.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Refuse images whose Curvine version expects other config keys or container
+/// args than the builders produce; warn when the version is unknown
+fn check_version_compatibility(
+    cluster_conf: &ClusterConf,
+    kube_config: &KubernetesConfig,
+) -> anyhow::Result<()> {
+    for warning in compat::check_compatibility(cluster_conf, kube_config, compat::SCHEMAS)? {
+        eprintln!("⚠️  {}", warning);
+    }
+    Ok(())
+}
+
+/// Ask `question` on stderr; an empty answer keeps `default`
+fn prompt(question: &str, default: &str) -> anyhow::Result<String> {
+    eprint!("{} [{}]: ", question, default);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(match answer.is_empty() {
+        true => default.to_string(),
+        false => answer.to_string(),
+    })
+}
+
 /// Parse dynamic configuration properties from -D key=value format
 fn parse_dynamic_configs(configs: &[String]) -> Result<HashMap<String, String>, String> {
     let mut map = HashMap::new();
@@ -0,0 +1,405 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Curvine versions supported by the config structs and builders
+//!
+//! The version of an image comes from `curvine_version` or else its tag.
+//! Reading the version from the image config labels would need registry
+//! access and is out of scope; pass the label value as `curvine_version`.
+//! Each supported release series has a `ConfigSchema` listing what differs
+//! from the modeled config: container args, keys it does not accept and other
+//! defaults. The 0.2 series is the modeled one, so its schema lists no
+//! differences; a new series adds its own entry to `SCHEMAS`.
+
+use crate::domain::cluster::descriptor::image_version;
+use crate::domain::cluster::doctor::{CheckStatus, DoctorReport};
+use crate::domain::config::curvine::ClusterConf;
+use crate::domain::config::kubernetes::{KubernetesConfig, KubernetesConfigBuilder};
+use crate::infrastructure::constants::{
+    COMPONENT_MASTER, COMPONENT_WORKER, CONTAINER_NAME_MASTER, CONTAINER_NAME_WORKER,
+};
+use crate::infrastructure::kubernetes::resources::{MasterBuilder, WorkerBuilder};
+use crate::shared::error::KubeError;
+use k8s_openapi::api::apps::v1::StatefulSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CurvineVersion {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl CurvineVersion {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl fmt::Display for CurvineVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl std::str::FromStr for CurvineVersion {
+    type Err = KubeError;
+
+    /// `1.2.3`, `v1.2`, `1.2.3-rc1`, `v1.2.3-ubuntu22.04`; missing parts are 0
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || KubeError::ConfigError(format!("Invalid Curvine version: {}", s));
+        let version = s.strip_prefix(['v', 'V']).unwrap_or(s);
+        let version = version.split(['-', '+']).next().unwrap_or(version);
+
+        let parts = version
+            .split('.')
+            .map(|part| part.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        match parts[..] {
+            [major] => Ok(Self::new(major, 0, 0)),
+            [major, minor] => Ok(Self::new(major, minor, 0)),
+            [major, minor, patch] => Ok(Self::new(major, minor, patch)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// What a Curvine release series expects, relative to the modeled config
+#[derive(Debug, Clone)]
+pub struct ConfigSchema {
+    pub series: &'static str,
+    /// First version of the series
+    pub min: CurvineVersion,
+    /// First version no longer covered
+    pub until: Option<CurvineVersion>,
+    /// Args of the master and worker containers
+    pub master_args: &'static [&'static str],
+    pub worker_args: &'static [&'static str],
+    /// Config keys (dotted, tables included) the series does not accept
+    pub unsupported_keys: &'static [&'static str],
+    /// Defaults of the series that differ from the modeled ones, as TOML values
+    pub defaults: &'static [(&'static str, &'static str)],
+}
+
+impl ConfigSchema {
+    pub fn covers(&self, version: CurvineVersion) -> bool {
+        version >= self.min && self.until.is_none_or(|until| version < until)
+    }
+}
+
+/// Name of the `check_versions` checks (suffixed with the component when the
+/// master and worker images differ)
+pub const VERSION_CHECK: &str = "Curvine version";
+
+/// Supported release series; `ClusterConf` tracks the 0.2 series
+pub const SCHEMAS: &[ConfigSchema] = &[ConfigSchema {
+    series: "0.2",
+    min: CurvineVersion::new(0, 2, 0),
+    until: Some(CurvineVersion::new(0, 3, 0)),
+    master_args: &[COMPONENT_MASTER],
+    worker_args: &[COMPONENT_WORKER],
+    unsupported_keys: &[],
+    defaults: &[],
+}];
+
+/// Version of `image`, from `curvine_version` if set, else from the tag
+/// (`None` for `latest`, digests and other non-version tags)
+pub fn detect_version(
+    image: &str,
+    curvine_version: Option<&str>,
+) -> Result<Option<CurvineVersion>, KubeError> {
+    match curvine_version {
+        Some(version) => version.parse().map(Some),
+        None => Ok(image_version(image).parse().ok()),
+    }
+}
+
+/// Schema of the master image's version (the Curvine config is shared by
+/// masters and workers)
+pub fn find_schema<'a>(
+    kube_config: &KubernetesConfig,
+    schemas: &'a [ConfigSchema],
+) -> Result<Option<&'a ConfigSchema>, KubeError> {
+    let version = detect_version(
+        &kube_config.master.image,
+        kube_config.curvine_version.as_deref(),
+    )?;
+    Ok(version.and_then(|version| schemas.iter().find(|schema| schema.covers(version))))
+}
+
+/// Apply the defaults of the image's schema to the values `cluster_conf`
+/// leaves at the modeled default. Returns the keys that changed.
+pub fn apply_schema_defaults(
+    cluster_conf: &mut ClusterConf,
+    kube_config: &KubernetesConfig,
+    schemas: &[ConfigSchema],
+) -> Result<Vec<String>, KubeError> {
+    let Some(schema) = find_schema(kube_config, schemas)? else {
+        return Ok(Vec::new());
+    };
+    if schema.defaults.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let to_value = |conf: &ClusterConf| {
+        toml::Value::try_from(conf).map_err(|e| KubeError::ConfigError(e.to_string()))
+    };
+    let modeled = to_value(&ClusterConf::default())?;
+    let mut document = to_value(cluster_conf)?;
+
+    let mut changed = Vec::new();
+    for (key, default) in schema.defaults {
+        if lookup(&document, key) != lookup(&modeled, key) {
+            continue;
+        }
+        let value = toml::from_str::<toml::Table>(&format!("value = {}", default))
+            .ok()
+            .and_then(|mut table| table.remove("value"))
+            .ok_or_else(|| {
+                KubeError::ConfigError(format!(
+                    "Invalid default for {} in Curvine {} schema: {}",
+                    key, schema.series, default
+                ))
+            })?;
+        insert(&mut document, key, value);
+        changed.push(key.to_string());
+    }
+
+    *cluster_conf = document
+        .try_into()
+        .map_err(|e: toml::de::Error| KubeError::ConfigError(e.to_string()))?;
+    Ok(changed)
+}
+
+/// Compare the images' Curvine versions with the supported schemas and refuse
+/// the first failing one; returns the warnings
+pub fn check_compatibility(
+    cluster_conf: &ClusterConf,
+    kube_config: &KubernetesConfig,
+    schemas: &[ConfigSchema],
+) -> Result<Vec<String>, KubeError> {
+    let mut report = DoctorReport::default();
+    check_versions(&mut report, cluster_conf, kube_config, schemas);
+
+    let mut warnings = Vec::new();
+    for check in report.checks {
+        match check.status {
+            CheckStatus::Fail => {
+                return Err(KubeError::ConfigError(format!(
+                    "{}: {}",
+                    check.name, check.message
+                )))
+            }
+            CheckStatus::Warn => warnings.push(format!("{}: {}", check.name, check.message)),
+            CheckStatus::Pass => {}
+        }
+    }
+    Ok(warnings)
+}
+
+/// Compare the images' Curvine versions with the supported schemas: container
+/// args or config keys the schema does not expect fail, unknown versions warn
+pub fn check_versions(
+    report: &mut DoctorReport,
+    cluster_conf: &ClusterConf,
+    kube_config: &KubernetesConfig,
+    schemas: &[ConfigSchema],
+) {
+    let components = [
+        ("master", &kube_config.master.image),
+        ("worker", &kube_config.worker.image),
+    ];
+    let same_image = kube_config.master.image == kube_config.worker.image;
+
+    for (component, image) in components {
+        if same_image && component == "worker" {
+            break;
+        }
+        let name = if same_image {
+            VERSION_CHECK.to_string()
+        } else {
+            format!("{} ({})", VERSION_CHECK, component)
+        };
+        let components: &[&str] = if same_image {
+            &["master", "worker"]
+        } else {
+            std::slice::from_ref(&component)
+        };
+
+        let version = match detect_version(image, kube_config.curvine_version.as_deref()) {
+            Ok(Some(version)) => version,
+            Ok(None) => {
+                report.push(
+                    name,
+                    CheckStatus::Warn,
+                    format!(
+                        "Cannot tell the Curvine version of {} from its tag; set --curvine-version",
+                        image
+                    ),
+                );
+                continue;
+            }
+            Err(e) => {
+                report.push(name, CheckStatus::Fail, e.to_string());
+                continue;
+            }
+        };
+        let Some(schema) = schemas.iter().find(|schema| schema.covers(version)) else {
+            report.push(
+                name,
+                CheckStatus::Warn,
+                format!(
+                    "Curvine {} ({}) is not a supported version (supported: {}); \
+                     config keys and container args are not checked",
+                    version,
+                    image,
+                    supported_series(schemas)
+                ),
+            );
+            continue;
+        };
+
+        match mismatches(schema, components, cluster_conf, kube_config) {
+            Ok(problems) if problems.is_empty() => report.push(
+                name,
+                CheckStatus::Pass,
+                format!("Curvine {} ({} schema)", version, schema.series),
+            ),
+            Ok(problems) => report.push(
+                name,
+                CheckStatus::Fail,
+                format!(
+                    "Curvine {} ({} schema): {}",
+                    version,
+                    schema.series,
+                    problems.join("; ")
+                ),
+            ),
+            Err(e) => report.push(
+                name,
+                CheckStatus::Warn,
+                format!("Cannot build the resources to compare: {}", e),
+            ),
+        }
+    }
+}
+
+fn supported_series(schemas: &[ConfigSchema]) -> String {
+    schemas
+        .iter()
+        .map(|schema| format!("{}.x", schema.series))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Differences between what the builders produce for `components` and what
+/// `schema` expects
+fn mismatches(
+    schema: &ConfigSchema,
+    components: &[&str],
+    cluster_conf: &ClusterConf,
+    kube_config: &KubernetesConfig,
+) -> Result<Vec<String>, KubeError> {
+    let mut problems = Vec::new();
+
+    for component in components {
+        let (statefulset, container, expected) = match *component {
+            "master" => (
+                MasterBuilder::new(
+                    kube_config.cluster_id.clone(),
+                    kube_config.namespace.clone(),
+                    kube_config.clone(),
+                    cluster_conf.clone(),
+                    false,
+                )
+                .build()?,
+                CONTAINER_NAME_MASTER,
+                schema.master_args,
+            ),
+            _ => (
+                WorkerBuilder::new(
+                    kube_config.cluster_id.clone(),
+                    kube_config.namespace.clone(),
+                    kube_config.clone(),
+                    cluster_conf.clone(),
+                )
+                .build()?,
+                CONTAINER_NAME_WORKER,
+                schema.worker_args,
+            ),
+        };
+        let args = container_args(&statefulset, container);
+        if args != expected {
+            problems.push(format!(
+                "{} container args {:?} (expected {:?})",
+                component, args, expected
+            ));
+        }
+    }
+
+    let rendered = KubernetesConfigBuilder::new(cluster_conf.clone(), kube_config.clone())
+        .build_cluster_side_config()?;
+    let document: toml::Value = toml::from_str(&rendered)
+        .map_err(|e| KubeError::ConfigError(format!("Invalid cluster config: {}", e)))?;
+    let unsupported: Vec<&str> = schema
+        .unsupported_keys
+        .iter()
+        .copied()
+        .filter(|key| lookup(&document, key).is_some())
+        .collect();
+    if !unsupported.is_empty() {
+        problems.push(format!(
+            "unsupported config keys {}",
+            unsupported.join(", ")
+        ));
+    }
+
+    Ok(problems)
+}
+
+fn container_args(statefulset: &StatefulSet, container: &str) -> Vec<String> {
+    statefulset
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.template.spec.as_ref())
+        .and_then(|pod| pod.containers.iter().find(|c| c.name == container))
+        .and_then(|c| c.args.clone())
+        .unwrap_or_default()
+}
+
+fn lookup<'a>(value: &'a toml::Value, key: &str) -> Option<&'a toml::Value> {
+    key.split('.')
+        .try_fold(value, |value, part| value.as_table()?.get(part))
+}
+
+fn insert(value: &mut toml::Value, key: &str, new: toml::Value) {
+    let mut current = value;
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        let Some(table) = current.as_table_mut() else {
+            return;
+        };
+        if parts.peek().is_none() {
+            table.insert(part.to_string(), new);
+            return;
+        }
+        current = table
+            .entry(part)
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    }
+}
@@ -12,14 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::domain::cluster::doctor::{Doctor, DoctorReport};
 use crate::domain::cluster::validator::KubernetesValidator;
-use crate::domain::config::kubernetes::KubernetesConfig;
+use crate::domain::config::kubernetes::{KubernetesConfig, SecretConfig, TlsConfig, TlsMode};
 use crate::domain::config::ClusterConf;
+use crate::infrastructure::constants::{
+    COMPONENT_MASTER, CONFIG_FILE_NAME, LABEL_APP, LABEL_COMPONENT, POD_WARNING_EVENTS_LIMIT,
+};
 use crate::infrastructure::kubernetes::client::{CurvineKubeClient, CurvineKubeClientImpl};
 use crate::infrastructure::kubernetes::resources::{
-    ConfigMapBuilder, HeadlessServiceBuilder, MasterBuilder, ServiceBuilder, WorkerBuilder,
+    secret_key_values, ConfigMapBuilder, HeadlessServiceBuilder, MasterBuilder,
+    NetworkPolicyBuilder, RbacBuilder, SecretBuilder, ServiceBuilder, TlsBuilder, WorkerBuilder,
 };
 use crate::shared::error::KubeError;
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::{ConfigMap, Event, Pod};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 use tokio::time::sleep;
 
@@ -51,6 +60,17 @@ impl CurvineClusterDescriptor {
         })
     }
 
+    /// Run the `doctor` preflight checks for a deployment of this configuration
+    pub async fn preflight(
+        &self,
+        cluster_conf: &ClusterConf,
+        kube_config: &KubernetesConfig,
+    ) -> DoctorReport {
+        Doctor::new(self.client.get_client(), self.namespace.clone())
+            .run(cluster_conf, kube_config)
+            .await
+    }
+
     pub async fn deploy_cluster(
         &self,
         cluster_conf: &ClusterConf,
@@ -192,7 +212,16 @@ impl CurvineClusterDescriptor {
             kube_config.cluster_id.clone(),
             kube_config.namespace.clone(),
             actual_master_replicas,
-        );
+        )
+        .with_cluster_domain(kube_config.cluster_domain.clone())
+        .with_secret_keys(
+            kube_config
+                .secrets
+                .as_ref()
+                .map(|s| s.keys.clone())
+                .unwrap_or_default(),
+        )
+        .with_tls(kube_config.tls.clone());
         let configmap = configmap_builder.build()?;
 
         self.client.apply_configmap(&configmap).await?;
@@ -208,6 +237,96 @@ impl CurvineClusterDescriptor {
             .uid
             .ok_or_else(|| KubeError::ValidationError("ConfigMap UID not found".to_string()))?;
 
+        // Pods mount the complete config from this Secret, so apply before
+        // StatefulSets; the ConfigMap holds it without the secret keys
+        let config_secret_name = SecretConfig::config_secret_name(&kube_config.cluster_id);
+        match &kube_config.secrets {
+            Some(secrets) => {
+                let values = match &secrets.existing_secret {
+                    Some(name) => {
+                        secret_key_values(&self.client.get_secret(name).await?, &secrets.keys)?
+                    }
+                    None => configmap_builder.secret_values()?,
+                };
+                let secret = SecretBuilder::new(
+                    kube_config.cluster_id.clone(),
+                    kube_config.namespace.clone(),
+                    config_secret_name,
+                    BTreeMap::from([(
+                        CONFIG_FILE_NAME.to_string(),
+                        configmap_builder.build_pod_config(&values)?,
+                    )]),
+                )
+                .build_with_owner(Some(configmap_uid.clone()))?;
+                self.client.apply_secret(&secret).await?;
+                println!(
+                    "✓ Config Secret applied ({} secret keys)",
+                    secrets.keys.len()
+                );
+            }
+            None if is_update_mode => {
+                self.client.delete_secret(&config_secret_name).await?;
+            }
+            None => {}
+        }
+
+        // The TLS Secret is mounted by every pod
+        let tls_secret_name = TlsConfig::secret_name(&kube_config.cluster_id);
+        match kube_config.tls.as_ref().map(|t| t.mode) {
+            Some(TlsMode::CertManager) => {
+                let certificate = TlsBuilder::new(
+                    kube_config.cluster_id.clone(),
+                    kube_config.namespace.clone(),
+                    kube_config.clone(),
+                )
+                .build_certificate(Some(configmap_uid.clone()))?;
+                self.client.apply_certificate(&certificate).await?;
+                println!("✓ cert-manager Certificate applied");
+            }
+            Some(TlsMode::SelfSigned) => {
+                // Keep existing certificates so updates don't rotate the CA
+                if self.client.get_secret(&tls_secret_name).await.is_ok() {
+                    println!("✓ TLS Secret exists, keeping certificates");
+                } else {
+                    let secret = TlsBuilder::new(
+                        kube_config.cluster_id.clone(),
+                        kube_config.namespace.clone(),
+                        kube_config.clone(),
+                    )
+                    .build_self_signed_secret(Some(configmap_uid.clone()))?;
+                    self.client.apply_secret(&secret).await?;
+                    println!("✓ Self-signed TLS Secret applied");
+                }
+            }
+            None if is_update_mode => {
+                self.client.delete_certificate(&tls_secret_name).await?;
+                self.client.delete_secret(&tls_secret_name).await?;
+            }
+            None => {}
+        }
+
+        // ServiceAccounts must exist before the StatefulSets create pods
+        let rbac_builder = RbacBuilder::new(
+            kube_config.cluster_id.clone(),
+            kube_config.namespace.clone(),
+            kube_config.clone(),
+        );
+        let service_accounts = rbac_builder.build_service_accounts(Some(configmap_uid.clone()))?;
+        for account in &service_accounts {
+            self.client.apply_service_account(account).await?;
+        }
+        if !service_accounts.is_empty() {
+            println!("✓ ServiceAccounts applied");
+        }
+
+        if let Some((role, binding)) =
+            rbac_builder.build_pod_discovery(Some(configmap_uid.clone()))?
+        {
+            self.client.apply_role(&role).await?;
+            self.client.apply_role_binding(&binding).await?;
+            println!("✓ Role and RoleBinding applied");
+        }
+
         let master_builder = MasterBuilder::new(
             kube_config.cluster_id.clone(),
             kube_config.namespace.clone(),
@@ -255,6 +374,38 @@ impl CurvineClusterDescriptor {
         self.client.apply_service(&service).await?;
         println!("✓ Service applied");
 
+        if let Some(network_policy_config) = &kube_config.network_policy {
+            let network_policy_builder = NetworkPolicyBuilder::new(
+                kube_config.cluster_id.clone(),
+                kube_config.namespace.clone(),
+                network_policy_config.clone(),
+            );
+            for policy in network_policy_builder.build_with_owner(Some(configmap_uid.clone()))? {
+                self.client.apply_network_policy(&policy).await?;
+            }
+            println!("✓ NetworkPolicies applied");
+        } else if is_update_mode {
+            // Policies may have been enabled by an earlier deploy; drop them
+            self.client
+                .delete_network_policy(&format!("{}-master", kube_config.cluster_id))
+                .await?;
+            self.client
+                .delete_network_policy(&format!("{}-worker", kube_config.cluster_id))
+                .await?;
+        }
+
+        if is_update_mode {
+            // RBAC objects may have been generated by an earlier deploy; drop
+            // them once the StatefulSets no longer reference them
+            if let Some(name) = rbac_builder.stale_pod_discovery()? {
+                self.client.delete_role_binding(&name).await?;
+                self.client.delete_role(&name).await?;
+            }
+            for name in rbac_builder.stale_service_accounts()? {
+                self.client.delete_service_account(&name).await?;
+            }
+        }
+
         if is_first_deployment {
             println!("\nWaiting for cluster to be ready...");
             self.wait_for_cluster_ready(&kube_config.cluster_id).await?;
@@ -400,10 +551,12 @@ impl CurvineClusterDescriptor {
     pub async fn get_cluster_status(&self, cluster_id: &str) -> Result<ClusterStatus, KubeError> {
         let mut status = ClusterStatus {
             cluster_id: cluster_id.to_string(),
+            namespace: self.namespace.clone(),
             master: None,
             worker: None,
             service: None,
             configmap: None,
+            pods: Vec::new(),
         };
 
         match self
@@ -479,9 +632,105 @@ impl CurvineClusterDescriptor {
             Err(e) => return Err(e),
         }
 
+        for pod in self.get_cluster_pods(cluster_id).await? {
+            status.pods.push(self.pod_details(&pod).await);
+        }
+
         Ok(status)
     }
 
+    /// List master and worker pods of a cluster, sorted by name
+    pub async fn list_cluster_pods(&self, cluster_id: &str) -> Result<Vec<PodStatus>, KubeError> {
+        Ok(self
+            .get_cluster_pods(cluster_id)
+            .await?
+            .iter()
+            .map(PodStatus::from_pod)
+            .collect())
+    }
+
+    async fn get_cluster_pods(&self, cluster_id: &str) -> Result<Vec<Pod>, KubeError> {
+        let mut labels = HashMap::new();
+        labels.insert(LABEL_APP.to_string(), cluster_id.to_string());
+
+        let mut pods = self.client.get_pods_with_labels(&labels).await?;
+        pods.sort_by(|a, b| a.metadata.name.cmp(&b.metadata.name));
+        Ok(pods)
+    }
+
+    /// Pod status plus PVC binding state and recent Warning events. These
+    /// lookups are best-effort: failures (e.g. no `events/list` permission)
+    /// are recorded in `unavailable` instead of failing the whole status.
+    async fn pod_details(&self, pod: &Pod) -> PodStatus {
+        let mut status = PodStatus::from_pod(pod);
+
+        let claims = pod
+            .spec
+            .as_ref()
+            .and_then(|s| s.volumes.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|v| v.persistent_volume_claim.as_ref())
+            .map(|pvc| pvc.claim_name.clone());
+        for name in claims {
+            let phase = match self.client.get_pvc(&name).await {
+                Ok(pvc) => pvc.status.and_then(|s| s.phase),
+                Err(KubeError::NotFound { .. }) => Some("Missing".to_string()),
+                Err(e) => {
+                    status
+                        .unavailable
+                        .push(format!("PVC {} not checked: {}", name, e));
+                    None
+                }
+            };
+            status.pvcs.push(PvcStatus { name, phase });
+        }
+
+        match self.client.list_pod_warning_events(&status.name).await {
+            Ok(mut events) => {
+                events.sort_by_key(|e| std::cmp::Reverse(event_time(e)));
+                status.warnings = events
+                    .iter()
+                    .take(POD_WARNING_EVENTS_LIMIT)
+                    .map(PodEvent::from_event)
+                    .collect();
+            }
+            Err(e) => status
+                .unavailable
+                .push(format!("Warning events not listed: {}", e)),
+        }
+
+        status
+    }
+
+    /// Pick the master pod to exec into: the first ready master. The Raft
+    /// leader is not preferred, as Curvine does not report it (see `--pod`).
+    pub async fn select_exec_master(&self, cluster_id: &str) -> Result<String, KubeError> {
+        let pod = self.select_ready_pod(cluster_id, COMPONENT_MASTER).await?;
+        Ok(pod.metadata.name.unwrap_or_default())
+    }
+
+    /// First ready pod of a component.
+    pub async fn select_ready_pod(
+        &self,
+        cluster_id: &str,
+        component: &str,
+    ) -> Result<Pod, KubeError> {
+        self.get_cluster_pods(cluster_id)
+            .await?
+            .into_iter()
+            .find(|p| {
+                let status = PodStatus::from_pod(p);
+                status.component.as_deref() == Some(component) && status.ready
+            })
+            .ok_or_else(|| {
+                KubeError::KubeError(format!(
+                    "No ready {} pod found for cluster '{}' in namespace '{}'",
+                    component, cluster_id, self.namespace
+                ))
+            })
+    }
+
     pub async fn delete_cluster(
         &self,
         cluster_id: &str,
@@ -496,102 +745,287 @@ impl CurvineClusterDescriptor {
         &self.namespace
     }
 
+    pub fn kube_client(&self) -> kube::Client {
+        self.client.get_client()
+    }
+
     /// List all Curvine clusters in the namespace by querying ConfigMaps with the curvine label
     pub async fn list_clusters(&self) -> Result<Vec<ClusterInfo>, KubeError> {
-        let label_selector = "type=curvine-native-kubernetes";
-        let configmaps = self.client.list_configmaps(label_selector).await?;
-
-        let mut clusters = Vec::new();
-        for cm in configmaps {
-            if let Some(name) = cm.metadata.name {
-                if let Some(cluster_id) = name.strip_suffix("-config") {
-                    let cluster_id = cluster_id.to_string();
-
-                    let master_ss = self
-                        .client
-                        .get_statefulset(&format!("{}-master", cluster_id))
-                        .await
-                        .ok();
-
-                    let worker_ss = self
-                        .client
-                        .get_statefulset(&format!("{}-worker", cluster_id))
-                        .await
-                        .ok();
-
-                    let master_replicas = master_ss
-                        .as_ref()
-                        .and_then(|ss| ss.spec.as_ref())
-                        .and_then(|spec| spec.replicas)
-                        .unwrap_or(0);
-
-                    let master_ready = master_ss
-                        .as_ref()
-                        .and_then(|ss| ss.status.as_ref())
-                        .and_then(|status| status.ready_replicas)
-                        .unwrap_or(0);
-
-                    let worker_replicas = worker_ss
-                        .as_ref()
-                        .and_then(|ss| ss.spec.as_ref())
-                        .and_then(|spec| spec.replicas)
-                        .unwrap_or(0);
-
-                    let worker_ready = worker_ss
-                        .as_ref()
-                        .and_then(|ss| ss.status.as_ref())
-                        .and_then(|status| status.ready_replicas)
-                        .unwrap_or(0);
-
-                    clusters.push(ClusterInfo {
-                        cluster_id,
-                        namespace: self.namespace.clone(),
-                        master_replicas: master_replicas as u32,
-                        master_ready: master_ready as u32,
-                        worker_replicas: worker_replicas as u32,
-                        worker_ready: worker_ready as u32,
-                    });
-                }
-            }
-        }
+        let configmaps = self.client.list_configmaps(CLUSTER_LABEL_SELECTOR).await?;
+        let statefulsets = self
+            .client
+            .list_statefulsets(CLUSTER_LABEL_SELECTOR)
+            .await?;
+        Ok(summarize_clusters(&configmaps, &statefulsets))
+    }
+
+    /// List Curvine clusters in every namespace with two cluster-scoped list calls
+    pub async fn list_clusters_all_namespaces(&self) -> Result<Vec<ClusterInfo>, KubeError> {
+        let configmaps = self
+            .client
+            .list_configmaps_all_namespaces(CLUSTER_LABEL_SELECTOR)
+            .await?;
+        let statefulsets = self
+            .client
+            .list_statefulsets_all_namespaces(CLUSTER_LABEL_SELECTOR)
+            .await?;
+        Ok(summarize_clusters(&configmaps, &statefulsets))
+    }
+}
+
+const CLUSTER_LABEL_SELECTOR: &str = "type=curvine-native-kubernetes";
+
+/// Join cluster ConfigMaps (`<id>-config`) with their master and worker
+/// StatefulSets by namespace and name; results are sorted by namespace and id.
+pub fn summarize_clusters(
+    configmaps: &[ConfigMap],
+    statefulsets: &[StatefulSet],
+) -> Vec<ClusterInfo> {
+    let statefulsets: HashMap<(String, String), &StatefulSet> = statefulsets
+        .iter()
+        .filter_map(|ss| {
+            Some((
+                (ss.metadata.namespace.clone()?, ss.metadata.name.clone()?),
+                ss,
+            ))
+        })
+        .collect();
+
+    let mut clusters: Vec<ClusterInfo> = configmaps
+        .iter()
+        .filter_map(|cm| {
+            let namespace = cm.metadata.namespace.clone().unwrap_or_default();
+            let cluster_id = cm.metadata.name.as_deref()?.strip_suffix("-config")?;
+            let master = statefulsets.get(&(namespace.clone(), format!("{}-master", cluster_id)));
+            let worker = statefulsets.get(&(namespace.clone(), format!("{}-worker", cluster_id)));
+
+            let (master_replicas, master_ready) = replica_counts(master.copied());
+            let (worker_replicas, worker_ready) = replica_counts(worker.copied());
+            let image = master
+                .or(worker)
+                .and_then(|ss| ss.spec.as_ref())
+                .and_then(|spec| spec.template.spec.as_ref())
+                .and_then(|spec| spec.containers.first())
+                .and_then(|c| c.image.clone());
+
+            Some(ClusterInfo {
+                cluster_id: cluster_id.to_string(),
+                namespace,
+                context: None,
+                master_replicas,
+                master_ready,
+                worker_replicas,
+                worker_ready,
+                version: image.as_deref().map(image_version),
+                image,
+                health: ClusterHealth::from_replicas(
+                    master_ready,
+                    master_replicas,
+                    worker_ready,
+                    worker_replicas,
+                ),
+            })
+        })
+        .collect();
+
+    clusters.sort_by(|a, b| (&a.namespace, &a.cluster_id).cmp(&(&b.namespace, &b.cluster_id)));
+    clusters
+}
+
+fn replica_counts(statefulset: Option<&StatefulSet>) -> (u32, u32) {
+    let Some(ss) = statefulset else {
+        return (0, 0);
+    };
+    let replicas = ss.spec.as_ref().and_then(|s| s.replicas).unwrap_or(0);
+    let ready = ss
+        .status
+        .as_ref()
+        .and_then(|s| s.ready_replicas)
+        .unwrap_or(0);
+    (replicas as u32, ready as u32)
+}
+
+/// Version part of an image reference: the tag, a shortened digest, or
+/// `latest` when neither is given
+pub fn image_version(image: &str) -> String {
+    if let Some((_, digest)) = image.split_once('@') {
+        let hex = digest.split_once(':').map(|(_, h)| h).unwrap_or(digest);
+        return format!("@{}", &hex[..hex.len().min(12)]);
+    }
 
-        Ok(clusters)
+    let name = image.rsplit('/').next().unwrap_or(image);
+    match name.split_once(':') {
+        Some((_, tag)) => tag.to_string(),
+        None => "latest".to_string(),
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ClusterInfo {
     pub cluster_id: String,
     pub namespace: String,
+    /// Kubeconfig context, set when listing across `--contexts`
+    pub context: Option<String>,
     pub master_replicas: u32,
     pub master_ready: u32,
     pub worker_replicas: u32,
     pub worker_ready: u32,
+    /// Master container image (worker image when no master exists)
+    pub image: Option<String>,
+    pub version: Option<String>,
+    pub health: ClusterHealth,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ClusterHealth {
+    Healthy,
+    Degraded,
+    Failed,
+    Unknown,
+}
+
+impl ClusterHealth {
+    pub fn from_replicas(
+        master_ready: u32,
+        master_replicas: u32,
+        worker_ready: u32,
+        worker_replicas: u32,
+    ) -> Self {
+        if master_replicas + worker_replicas == 0 {
+            Self::Unknown
+        } else if master_ready == master_replicas && worker_ready == worker_replicas {
+            Self::Healthy
+        } else if master_ready > 0 || worker_ready > 0 {
+            Self::Degraded
+        } else {
+            Self::Failed
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Healthy => "Healthy",
+            Self::Degraded => "Degraded",
+            Self::Failed => "Failed",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ClusterStatus {
     pub cluster_id: String,
+    pub namespace: String,
     pub master: Option<StatefulSetStatus>,
     pub worker: Option<StatefulSetStatus>,
     pub service: Option<ServiceStatus>,
     pub configmap: Option<ConfigMapStatus>,
+    pub pods: Vec<PodStatus>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct StatefulSetStatus {
     pub name: String,
     pub replicas: u32,
     pub ready_replicas: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ServiceStatus {
     pub name: String,
     pub cluster_ip: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ConfigMapStatus {
     pub name: String,
 }
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PodStatus {
+    pub name: String,
+    /// `master` or `worker`
+    pub component: Option<String>,
+    pub phase: Option<String>,
+    pub ready: bool,
+    pub restarts: u32,
+    pub node: Option<String>,
+    pub pod_ip: Option<String>,
+    /// Creation time in RFC 3339
+    pub created_at: Option<String>,
+    pub pvcs: Vec<PvcStatus>,
+    /// Most recent Warning events, newest first
+    pub warnings: Vec<PodEvent>,
+    /// Details that could not be looked up
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub unavailable: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PvcStatus {
+    pub name: String,
+    /// `Bound`, `Pending`, `Lost`, or `Missing` when the claim does not exist
+    pub phase: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PodEvent {
+    pub reason: Option<String>,
+    pub message: Option<String>,
+    pub count: u32,
+    /// Last occurrence in RFC 3339
+    pub last_seen: Option<String>,
+}
+
+impl PodEvent {
+    pub fn from_event(event: &Event) -> Self {
+        Self {
+            reason: event.reason.clone(),
+            message: event.message.clone(),
+            count: event.count.unwrap_or(1).max(0) as u32,
+            last_seen: event_time(event).map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+fn event_time(event: &Event) -> Option<chrono::DateTime<chrono::Utc>> {
+    event
+        .last_timestamp
+        .as_ref()
+        .map(|t| t.0)
+        .or_else(|| event.event_time.as_ref().map(|t| t.0))
+        .or_else(|| event.metadata.creation_timestamp.as_ref().map(|t| t.0))
+}
+
+impl PodStatus {
+    pub fn from_pod(pod: &Pod) -> Self {
+        let status = pod.status.as_ref();
+        let container_statuses = status.and_then(|s| s.container_statuses.as_deref());
+
+        Self {
+            name: pod.metadata.name.clone().unwrap_or_default(),
+            component: pod
+                .metadata
+                .labels
+                .as_ref()
+                .and_then(|l| l.get(LABEL_COMPONENT).cloned()),
+            phase: status.and_then(|s| s.phase.clone()),
+            ready: container_statuses
+                .map(|cs| !cs.is_empty() && cs.iter().all(|c| c.ready))
+                .unwrap_or(false),
+            restarts: container_statuses
+                .map(|cs| cs.iter().map(|c| c.restart_count.max(0) as u32).sum())
+                .unwrap_or(0),
+            node: pod.spec.as_ref().and_then(|s| s.node_name.clone()),
+            pod_ip: status.and_then(|s| s.pod_ip.clone()),
+            created_at: pod
+                .metadata
+                .creation_timestamp
+                .as_ref()
+                .map(|t| t.0.to_rfc3339()),
+            pvcs: Vec::new(),
+            warnings: Vec::new(),
+            unavailable: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,610 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support bundles for bug reports (`diagnose`)
+//!
+//! Gathers a cluster's Kubernetes objects, events, pod logs and scheduling
+//! context into a single tar.gz archive with a summary of detected problems.
+//! Secrets are never collected, and values stored under sensitive-looking
+//! keys (passwords, tokens, access keys, ...) are redacted, in pod logs too.
+
+use crate::domain::cluster::descriptor::{ClusterStatus, CurvineClusterDescriptor};
+use crate::infrastructure::constants::{DIAGNOSE_LOG_TAIL_LINES, LABEL_APP};
+use crate::shared::error::KubeError;
+use chrono::Utc;
+use k8s_openapi::api::apps::v1::StatefulSet;
+use k8s_openapi::api::core::v1::{Event, Node, PersistentVolumeClaim, Pod, Service};
+use k8s_openapi::api::storage::v1::StorageClass;
+use kube::api::{Api, ListParams, LogParams};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+/// Replacement for redacted values
+pub const REDACTED: &str = "<redacted>";
+
+/// Key fragments marking a value as sensitive (matched case-insensitively,
+/// with `-` and `.` treated as `_`)
+const SENSITIVE_KEY_PATTERNS: &[&str] = &[
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "credential",
+    "access_key",
+    "private_key",
+    "api_key",
+    "authorization",
+];
+
+/// HTTP authentication schemes that precede the credentials in a value
+const AUTH_SCHEMES: &[&str] = &["basic", "bearer", "digest", "token"];
+
+/// Container waiting reasons reported as problems
+const PROBLEM_WAITING_REASONS: &[&str] = &[
+    "CrashLoopBackOff",
+    "ImagePullBackOff",
+    "ErrImagePull",
+    "CreateContainerConfigError",
+    "CreateContainerError",
+    "InvalidImageName",
+];
+
+/// Whether a key names a sensitive value. References to secrets
+/// (`secretName`, `secretKeyRef`, ...) are not sensitive themselves.
+pub fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_lowercase().replace(['-', '.'], "_");
+    if key.ends_with("name") || key.ends_with("ref") {
+        return false;
+    }
+    SENSITIVE_KEY_PATTERNS.iter().any(|p| key.contains(p))
+}
+
+/// Redact every `key=value` / `key: value` pair with a sensitive key, both
+/// in config files (the value runs to the end of the line) and within log
+/// lines (`level=info password=...`, `Authorization: Bearer ...`)
+pub fn redact_text(text: &str) -> String {
+    let mut redacted: Vec<String> = text.lines().map(redact_line).collect();
+    if text.ends_with('\n') {
+        redacted.push(String::new());
+    }
+    redacted.join("\n")
+}
+
+fn redact_line(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut copied = 0;
+    let mut search = 0;
+
+    while let Some(offset) = line[search..].find(['=', ':']) {
+        let separator = search + offset;
+        let value_start = separator
+            + 1
+            + (line[separator + 1..].len() - line[separator + 1..].trim_start().len());
+        let (key, key_start) = pair_key(&line[..separator]);
+        // `key: value` / `key = value` starting a line is a config entry whose
+        // value may contain blanks
+        let config_entry =
+            value_start > separator + 1 && matches!(line[..key_start].trim(), "" | "-");
+        let quoted = line[value_start..].starts_with(['"', '\'']);
+        let value_end = if config_entry && !quoted {
+            line.trim_end().len().max(value_start)
+        } else {
+            value_end(line, value_start)
+        };
+
+        if is_sensitive_key(key) && value_end > value_start {
+            output.push_str(&line[copied..value_start]);
+            if quoted || config_entry {
+                let quote = if quoted {
+                    &line[value_start..value_start + 1]
+                } else {
+                    "\""
+                };
+                output.push_str(&format!("{}{}{}", quote, REDACTED, quote));
+            } else {
+                output.push_str(REDACTED);
+            }
+            copied = value_end;
+        }
+        search = value_end.max(separator + 1);
+    }
+
+    output.push_str(&line[copied..]);
+    output
+}
+
+/// Key in front of a separator (`"key"` or a run of key characters before
+/// optional blanks) and the byte index where it starts
+fn pair_key(before: &str) -> (&str, usize) {
+    let trimmed = before.trim_end();
+    if let Some(quoted) = trimmed.strip_suffix('"') {
+        if let Some(start) = quoted.rfind('"') {
+            return (&quoted[start + 1..], start);
+        }
+    }
+    let start = trimmed
+        .rfind(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+        .map_or(0, |i| i + 1);
+    (&trimmed[start..], start)
+}
+
+/// End of the value starting at `start`: a quoted string, an authentication
+/// scheme with its credentials, or a token up to a blank or delimiter
+fn value_end(line: &str, start: usize) -> usize {
+    let rest = &line[start..];
+    if let Some(quote) = rest.chars().next().filter(|c| matches!(c, '"' | '\'')) {
+        let mut escaped = false;
+        for (i, c) in rest.char_indices().skip(1) {
+            match c {
+                '\\' if !escaped => escaped = true,
+                c if c == quote && !escaped => return start + i + 1,
+                _ => escaped = false,
+            }
+        }
+        return line.len();
+    }
+
+    let token = |from: usize| {
+        line[from..]
+            .find(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '&' | '}' | ']'))
+            .map_or(line.len(), |i| from + i)
+    };
+    let end = token(start);
+    let scheme = line[start..end].to_lowercase();
+    if AUTH_SCHEMES.contains(&scheme.as_str()) && line[end..].starts_with(' ') {
+        let credentials = end + 1;
+        if token(credentials) > credentials {
+            return token(credentials);
+        }
+    }
+    end
+}
+
+/// Keep the events whose involved object is one of `objects` (`(kind, name)`),
+/// so that clusters sharing a name prefix are not mixed up
+pub fn filter_cluster_events(events: Vec<Event>, objects: &BTreeSet<(&str, &str)>) -> Vec<Event> {
+    events
+        .into_iter()
+        .filter(|e| {
+            let object = &e.involved_object;
+            match (object.kind.as_deref(), object.name.as_deref()) {
+                (Some(kind), Some(name)) => objects.contains(&(kind, name)),
+                _ => false,
+            }
+        })
+        .collect()
+}
+
+/// Redact sensitive values in a serialized Kubernetes object: string fields
+/// with sensitive keys, `{name, value}` pairs such as env vars with a
+/// sensitive name, and sensitive lines of embedded multi-line config files.
+pub fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            let sensitive_pair = map
+                .get("name")
+                .and_then(Value::as_str)
+                .is_some_and(is_sensitive_key);
+            for (key, field) in map.iter_mut() {
+                match field {
+                    Value::String(s) if is_sensitive_key(key) => *s = REDACTED.to_string(),
+                    Value::String(s) if key == "value" && sensitive_pair => {
+                        *s = REDACTED.to_string()
+                    }
+                    Value::String(s) if s.contains('\n') => *s = redact_text(s),
+                    _ => redact_value(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// Files of a support bundle, keyed by path inside the archive
+#[derive(Debug, Default)]
+pub struct SupportBundle {
+    files: BTreeMap<String, Vec<u8>>,
+}
+
+impl SupportBundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_text(&mut self, path: impl Into<String>, content: impl Into<String>) {
+        self.files.insert(path.into(), content.into().into_bytes());
+    }
+
+    /// Add an object as YAML with `managedFields` dropped and secrets redacted
+    pub fn add_yaml<T: Serialize>(
+        &mut self,
+        path: impl Into<String>,
+        object: &T,
+    ) -> Result<(), KubeError> {
+        let mut value = serde_json::to_value(object)?;
+        if let Some(metadata) = value.get_mut("metadata").and_then(Value::as_object_mut) {
+            metadata.remove("managedFields");
+        }
+        redact_value(&mut value);
+        self.add_text(path, serde_yaml::to_string(&value)?);
+        Ok(())
+    }
+
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    pub fn get(&self, path: &str) -> Option<&[u8]> {
+        self.files.get(path).map(Vec::as_slice)
+    }
+
+    /// Write the bundle as a gzip-compressed tar archive with every file under `root/`
+    pub fn write_tar_gz<W: Write>(&self, root: &str, writer: W) -> Result<(), KubeError> {
+        let encoder = flate2::write::GzEncoder::new(writer, flate2::Compression::default());
+        let mut archive = tar::Builder::new(encoder);
+        let mtime = Utc::now().timestamp().max(0) as u64;
+
+        for (path, content) in &self.files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime);
+            header.set_cksum();
+            archive.append_data(
+                &mut header,
+                format!("{}/{}", root, path),
+                content.as_slice(),
+            )?;
+        }
+
+        archive.into_inner()?.finish()?;
+        Ok(())
+    }
+}
+
+/// Problems visible in the collected objects, most severe first.
+///
+/// StatefulSet checks are skipped when the cluster status could not be read.
+pub fn detect_problems(
+    status: Option<&ClusterStatus>,
+    pods: &[Pod],
+    pvcs: &[PersistentVolumeClaim],
+    events: &[Event],
+) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if let Some(status) = status {
+        for statefulset in [&status.master, &status.worker].into_iter().flatten() {
+            if statefulset.ready_replicas < statefulset.replicas {
+                problems.push(format!(
+                    "StatefulSet {} has {}/{} ready replicas",
+                    statefulset.name, statefulset.ready_replicas, statefulset.replicas
+                ));
+            }
+        }
+        if status.master.is_none() {
+            problems.push(format!(
+                "Master StatefulSet of {} not found",
+                status.cluster_id
+            ));
+        }
+    }
+
+    for pod in pods {
+        let name = pod.metadata.name.as_deref().unwrap_or_default();
+        let Some(pod_status) = &pod.status else {
+            continue;
+        };
+
+        if pod_status.phase.as_deref() == Some("Pending") {
+            let unschedulable = pod_status
+                .conditions
+                .iter()
+                .flatten()
+                .find(|c| c.type_ == "PodScheduled" && c.status == "False");
+            match unschedulable {
+                Some(condition) => problems.push(format!(
+                    "Pod {} cannot be scheduled: {}",
+                    name,
+                    condition.message.as_deref().unwrap_or("unknown reason")
+                )),
+                None => problems.push(format!("Pod {} is Pending", name)),
+            }
+        }
+
+        for container in pod_status.container_statuses.iter().flatten() {
+            let waiting = container
+                .state
+                .as_ref()
+                .and_then(|s| s.waiting.as_ref())
+                .filter(|w| {
+                    PROBLEM_WAITING_REASONS.contains(&w.reason.as_deref().unwrap_or_default())
+                });
+            if let Some(waiting) = waiting {
+                problems.push(format!(
+                    "Pod {} container {} is waiting: {}{}",
+                    name,
+                    container.name,
+                    waiting.reason.as_deref().unwrap_or_default(),
+                    waiting
+                        .message
+                        .as_deref()
+                        .map(|m| format!(" ({})", m.trim()))
+                        .unwrap_or_default()
+                ));
+            }
+
+            let last_terminated = container
+                .last_state
+                .as_ref()
+                .and_then(|s| s.terminated.as_ref());
+            if let Some(terminated) = last_terminated {
+                if terminated.reason.as_deref() == Some("OOMKilled") {
+                    problems.push(format!(
+                        "Pod {} container {} was OOMKilled",
+                        name, container.name
+                    ));
+                }
+            }
+            if container.restart_count > 0 {
+                problems.push(format!(
+                    "Pod {} container {} restarted {} time(s)",
+                    name, container.name, container.restart_count
+                ));
+            }
+        }
+    }
+
+    for pvc in pvcs {
+        let phase = pvc.status.as_ref().and_then(|s| s.phase.as_deref());
+        if phase != Some("Bound") {
+            problems.push(format!(
+                "PVC {} is {}",
+                pvc.metadata.name.as_deref().unwrap_or_default(),
+                phase.unwrap_or("Unknown")
+            ));
+        }
+    }
+
+    let mut warnings: BTreeMap<&str, i32> = BTreeMap::new();
+    for event in events
+        .iter()
+        .filter(|e| e.type_.as_deref() == Some("Warning"))
+    {
+        *warnings
+            .entry(event.reason.as_deref().unwrap_or("Unknown"))
+            .or_default() += event.count.unwrap_or(1);
+    }
+    if !warnings.is_empty() {
+        let reasons: Vec<String> = warnings
+            .iter()
+            .map(|(reason, count)| format!("{} ({})", reason, count))
+            .collect();
+        problems.push(format!("Warning events: {}", reasons.join(", ")));
+    }
+
+    problems
+}
+
+/// Collect the support bundle of a cluster.
+///
+/// Failures to collect individual items (e.g. forbidden node access) are
+/// recorded in `errors.txt` instead of aborting the bundle.
+pub async fn collect_bundle(
+    descriptor: &CurvineClusterDescriptor,
+    cluster_id: &str,
+) -> Result<SupportBundle, KubeError> {
+    let client = descriptor.kube_client();
+    let namespace = descriptor.namespace();
+    let selector = ListParams::default().labels(&format!("{}={}", LABEL_APP, cluster_id));
+    let mut bundle = SupportBundle::new();
+    let mut errors = Vec::new();
+
+    let status = match descriptor.get_cluster_status(cluster_id).await {
+        Ok(status) => {
+            bundle.add_yaml("status.yaml", &status)?;
+            Some(status)
+        }
+        Err(e) => {
+            errors.push(format!("Cluster status: {}", e));
+            None
+        }
+    };
+
+    let configmaps: Api<k8s_openapi::api::core::v1::ConfigMap> =
+        Api::namespaced(client.clone(), namespace);
+    match configmaps.get_opt(&format!("{}-config", cluster_id)).await {
+        Ok(Some(configmap)) => bundle.add_yaml("configmap.yaml", &configmap)?,
+        Ok(None) => errors.push(format!("ConfigMap {}-config not found", cluster_id)),
+        Err(e) => errors.push(format!("ConfigMap: {}", e)),
+    }
+
+    let statefulset_api: Api<StatefulSet> = Api::namespaced(client.clone(), namespace);
+    let statefulsets = match statefulset_api.list(&selector).await {
+        Ok(list) => list.items,
+        Err(e) => {
+            errors.push(format!("StatefulSets: {}", e));
+            Vec::new()
+        }
+    };
+    for statefulset in &statefulsets {
+        let name = statefulset.metadata.name.as_deref().unwrap_or_default();
+        bundle.add_yaml(format!("statefulsets/{}.yaml", name), statefulset)?;
+    }
+
+    let services: Api<Service> = Api::namespaced(client.clone(), namespace);
+    match services.list(&selector).await {
+        Ok(list) => {
+            for service in &list.items {
+                let name = service.metadata.name.as_deref().unwrap_or_default();
+                bundle.add_yaml(format!("services/{}.yaml", name), service)?;
+            }
+        }
+        Err(e) => errors.push(format!("Services: {}", e)),
+    }
+
+    let pvc_api: Api<PersistentVolumeClaim> = Api::namespaced(client.clone(), namespace);
+    let pvcs = match pvc_api.list(&selector).await {
+        Ok(list) => list.items,
+        Err(e) => {
+            errors.push(format!("PVCs: {}", e));
+            Vec::new()
+        }
+    };
+    for pvc in &pvcs {
+        let name = pvc.metadata.name.as_deref().unwrap_or_default();
+        bundle.add_yaml(format!("pvcs/{}.yaml", name), pvc)?;
+    }
+
+    let pod_api: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    let pods = match pod_api.list(&selector).await {
+        Ok(list) => list.items,
+        Err(e) => {
+            errors.push(format!("Pods: {}", e));
+            Vec::new()
+        }
+    };
+    for pod in &pods {
+        let name = pod.metadata.name.as_deref().unwrap_or_default();
+        bundle.add_yaml(format!("pods/{}.yaml", name), pod)?;
+
+        let containers = pod.spec.iter().flat_map(|s| s.containers.iter());
+        for container in containers {
+            for previous in [false, true] {
+                let params = LogParams {
+                    container: Some(container.name.clone()),
+                    previous,
+                    tail_lines: Some(DIAGNOSE_LOG_TAIL_LINES),
+                    ..Default::default()
+                };
+                let suffix = if previous { ".previous" } else { "" };
+                match pod_api.logs(name, &params).await {
+                    Ok(log) => bundle.add_text(
+                        format!("logs/{}/{}{}.log", name, container.name, suffix),
+                        redact_text(&log),
+                    ),
+                    // A container that never restarted has no previous log
+                    Err(_) if previous => {}
+                    Err(e) => errors.push(format!("Logs of {}/{}: {}", name, container.name, e)),
+                }
+            }
+        }
+    }
+
+    let event_api: Api<Event> = Api::namespaced(client.clone(), namespace);
+    let objects: BTreeSet<(&str, &str)> = statefulsets
+        .iter()
+        .map(|s| ("StatefulSet", s.metadata.name.as_deref()))
+        .chain(pods.iter().map(|p| ("Pod", p.metadata.name.as_deref())))
+        .chain(
+            pvcs.iter()
+                .map(|p| ("PersistentVolumeClaim", p.metadata.name.as_deref())),
+        )
+        .filter_map(|(kind, name)| Some((kind, name?)))
+        .collect();
+    let mut events: Vec<Event> = match event_api.list(&ListParams::default()).await {
+        Ok(list) => filter_cluster_events(list.items, &objects),
+        Err(e) => {
+            errors.push(format!("Events: {}", e));
+            Vec::new()
+        }
+    };
+    events.sort_by_key(|e| {
+        e.last_timestamp
+            .as_ref()
+            .map(|t| t.0)
+            .or_else(|| e.event_time.as_ref().map(|t| t.0))
+    });
+    bundle.add_yaml("events.yaml", &events)?;
+
+    let storage_classes: Api<StorageClass> = Api::all(client.clone());
+    match storage_classes.list(&ListParams::default()).await {
+        Ok(list) => {
+            for storage_class in &list.items {
+                let name = storage_class.metadata.name.as_deref().unwrap_or_default();
+                bundle.add_yaml(format!("storageclasses/{}.yaml", name), storage_class)?;
+            }
+        }
+        Err(e) => errors.push(format!("StorageClasses: {}", e)),
+    }
+
+    let node_api: Api<Node> = Api::all(client);
+    let nodes: BTreeSet<&str> = pods
+        .iter()
+        .filter_map(|p| p.spec.as_ref().and_then(|s| s.node_name.as_deref()))
+        .collect();
+    for node_name in nodes {
+        match node_api.get(node_name).await {
+            Ok(mut node) => {
+                // The image list is long and irrelevant for scheduling problems
+                if let Some(status) = node.status.as_mut() {
+                    status.images = None;
+                }
+                bundle.add_yaml(format!("nodes/{}.yaml", node_name), &node)?;
+            }
+            Err(e) => errors.push(format!("Node {}: {}", node_name, e)),
+        }
+    }
+
+    let problems = detect_problems(status.as_ref(), &pods, &pvcs, &events);
+    bundle.add_text(
+        "summary.txt",
+        render_summary(cluster_id, namespace, &problems, &errors),
+    );
+    if !errors.is_empty() {
+        bundle.add_text("errors.txt", errors.join("\n") + "\n");
+    }
+
+    Ok(bundle)
+}
+
+/// Human readable summary placed at the root of the bundle
+pub fn render_summary(
+    cluster_id: &str,
+    namespace: &str,
+    problems: &[String],
+    errors: &[String],
+) -> String {
+    let mut summary = format!(
+        "Curvine support bundle\n\
+         Cluster:   {}\n\
+         Namespace: {}\n\
+         Generated: {}\n\
+         Tool:      curvine-kube {}\n\n",
+        cluster_id,
+        namespace,
+        Utc::now().format("%Y-%m-%d %H:%M:%S UTC"),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    if problems.is_empty() {
+        summary.push_str("No problems detected\n");
+    } else {
+        summary.push_str(&format!("Detected problems ({}):\n", problems.len()));
+        for problem in problems {
+            summary.push_str(&format!("  - {}\n", problem));
+        }
+    }
+
+    if !errors.is_empty() {
+        summary.push_str(&format!(
+            "\n{} item(s) could not be collected, see errors.txt\n",
+            errors.len()
+        ));
+    }
+    summary
+}
@@ -0,0 +1,919 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Preflight checks (`doctor`, and automatically before `deploy`)
+//!
+//! Each check reports pass, warn or fail. Checks that cannot be evaluated
+//! (e.g. because listing nodes is forbidden) warn instead of failing.
+
+use crate::domain::cluster::compat;
+use crate::domain::config::curvine::{ClusterConf, StorageType, WorkerDataDir};
+use crate::domain::config::kubernetes::{AntiAffinityMode, KubernetesConfig, TlsMode};
+use crate::infrastructure::constants::{DEFAULT_STORAGE_SIZE, MIN_KUBERNETES_VERSION};
+use crate::shared::error::KubeError;
+use k8s_openapi::api::authorization::v1::{
+    ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+};
+use k8s_openapi::api::core::v1::{
+    LimitRange, Namespace, Node, ResourceQuota, ResourceRequirements, Secret, Taint, Toleration,
+};
+use kube::api::{Api, ListParams, PostParams};
+use kube::Client;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Worker data volume size when `storage.worker_size` is not set
+const DEFAULT_WORKER_STORAGE_SIZE: &str = "20Gi";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    pub fn push(
+        &mut self,
+        name: impl Into<String>,
+        status: CheckStatus,
+        message: impl Into<String>,
+    ) {
+        self.checks.push(CheckResult {
+            name: name.into(),
+            status,
+            message: message.into(),
+        });
+    }
+
+    pub fn has_failures(&self) -> bool {
+        self.count(CheckStatus::Fail) > 0
+    }
+
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+}
+
+/// An API permission needed to deploy a cluster
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permission {
+    pub group: &'static str,
+    pub resource: &'static str,
+    pub verb: &'static str,
+    pub namespaced: bool,
+}
+
+impl Permission {
+    fn describe(&self) -> String {
+        if self.group.is_empty() {
+            format!("{} {}", self.verb, self.resource)
+        } else {
+            format!("{} {}.{}", self.verb, self.resource, self.group)
+        }
+    }
+}
+
+/// Permissions `deploy` needs for the features enabled in `kube_config`
+pub fn required_permissions(kube_config: &KubernetesConfig) -> Vec<Permission> {
+    let mut resources: Vec<(&'static str, &'static str, bool)> = vec![
+        ("apps", "statefulsets", true),
+        ("", "configmaps", true),
+        ("", "services", true),
+    ];
+    if kube_config.network_policy.is_some() {
+        resources.push(("networking.k8s.io", "networkpolicies", true));
+    }
+    if let Some(rbac) = &kube_config.rbac {
+        if rbac.create_service_accounts {
+            resources.push(("", "serviceaccounts", true));
+        }
+        if rbac.pod_discovery {
+            resources.push(("rbac.authorization.k8s.io", "roles", true));
+            resources.push(("rbac.authorization.k8s.io", "rolebindings", true));
+        }
+    }
+    let tls_mode = kube_config.tls.as_ref().map(|t| t.mode);
+    if kube_config.secrets.is_some() || tls_mode == Some(TlsMode::SelfSigned) {
+        resources.push(("", "secrets", true));
+    }
+    if tls_mode == Some(TlsMode::CertManager) {
+        resources.push(("cert-manager.io", "certificates", true));
+    }
+
+    let mut permissions: Vec<Permission> = resources
+        .into_iter()
+        .flat_map(|(group, resource, namespaced)| {
+            ["get", "create", "patch"].map(|verb| Permission {
+                group,
+                resource,
+                verb,
+                namespaced,
+            })
+        })
+        .collect();
+    permissions.push(Permission {
+        group: "",
+        resource: "pods",
+        verb: "list",
+        namespaced: true,
+    });
+    permissions.push(Permission {
+        group: "storage.k8s.io",
+        resource: "storageclasses",
+        verb: "get",
+        namespaced: false,
+    });
+    permissions
+}
+
+/// Parse a Kubernetes quantity (`500m`, `2Gi`, `1.5G`, `1e3`) into a number
+/// of base units (cores, bytes, objects)
+pub fn parse_quantity(quantity: &str) -> Option<f64> {
+    let quantity = quantity.trim();
+    let split = quantity
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || c == '+' || c == '-'))
+        .unwrap_or(quantity.len());
+    let (number, suffix) = quantity.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier = match suffix {
+        "" => 1.0,
+        "m" => 1e-3,
+        "k" => 1e3,
+        "M" => 1e6,
+        "G" => 1e9,
+        "T" => 1e12,
+        "P" => 1e15,
+        "E" => 1e18,
+        "Ki" => 1024.0,
+        "Mi" => 1024f64.powi(2),
+        "Gi" => 1024f64.powi(3),
+        "Ti" => 1024f64.powi(4),
+        "Pi" => 1024f64.powi(5),
+        "Ei" => 1024f64.powi(6),
+        exponent => 10f64.powi(exponent.strip_prefix(['e', 'E'])?.parse().ok()?),
+    };
+    Some(number * multiplier)
+}
+
+fn format_amount(resource: &str, amount: f64) -> String {
+    let resource = resource.rsplit('.').next().unwrap_or(resource);
+    if resource == "cpu" {
+        return format!("{}", (amount * 1000.0).round() / 1000.0);
+    }
+    if resource == "memory" || resource == "storage" || resource.starts_with("hugepages-") {
+        return format!("{:.1}Gi", amount / 1024f64.powi(3));
+    }
+    format!("{}", amount)
+}
+
+fn resource_amount(
+    resources: Option<&ResourceRequirements>,
+    limits: bool,
+    resource: &str,
+) -> Option<f64> {
+    let resources = resources?;
+    let values = if limits {
+        resources.limits.as_ref()
+    } else {
+        resources.requests.as_ref()
+    };
+    parse_quantity(&values?.get(resource)?.0)
+}
+
+/// Total MEM tier capacity of one worker in bytes
+pub fn mem_tier_bytes(cluster_conf: &ClusterConf) -> u64 {
+    cluster_conf
+        .worker
+        .data_dir
+        .iter()
+        .filter_map(|d| WorkerDataDir::parse_data_dir(d).ok())
+        .filter(|d| d.storage_type == StorageType::Mem)
+        .map(|d| d.capacity)
+        .sum()
+}
+
+/// Resources a deployment asks for, keyed like ResourceQuota entries
+/// (`requests.cpu`, `limits.memory`, `requests.storage`, `pods`, ...)
+pub fn requested_resources(
+    cluster_conf: &ClusterConf,
+    kube_config: &KubernetesConfig,
+) -> BTreeMap<String, f64> {
+    let mut requested = BTreeMap::new();
+    let components = [
+        (
+            kube_config.master.resources.as_ref(),
+            kube_config.master.replicas,
+        ),
+        (
+            kube_config.worker.resources.as_ref(),
+            kube_config.worker.replicas,
+        ),
+    ];
+    for (resources, replicas) in components {
+        let Some(resources) = resources else {
+            continue;
+        };
+        for (prefix, values) in [
+            ("requests", resources.requests.as_ref()),
+            ("limits", resources.limits.as_ref()),
+        ] {
+            for (name, quantity) in values.into_iter().flatten() {
+                if let Some(amount) = parse_quantity(&quantity.0) {
+                    *requested.entry(format!("{}.{}", prefix, name)).or_default() +=
+                        amount * replicas as f64;
+                }
+            }
+        }
+    }
+
+    let storage = kube_config.storage.as_ref();
+    let master_size = storage
+        .and_then(|s| s.master_size.as_deref())
+        .unwrap_or(DEFAULT_STORAGE_SIZE);
+    let worker_size = storage
+        .and_then(|s| s.worker_size.as_deref())
+        .unwrap_or(DEFAULT_WORKER_STORAGE_SIZE);
+    let disk_dirs = cluster_conf
+        .worker
+        .data_dir
+        .iter()
+        .filter_map(|d| WorkerDataDir::parse_data_dir(d).ok())
+        .filter(|d| d.storage_type != StorageType::Mem)
+        .count() as f64;
+
+    // Masters get a meta and a journal volume each
+    let master_pvcs = 2.0 * kube_config.master.replicas as f64;
+    let worker_pvcs = disk_dirs * kube_config.worker.replicas as f64;
+    requested.insert(
+        "persistentvolumeclaims".to_string(),
+        master_pvcs + worker_pvcs,
+    );
+    requested.insert(
+        "requests.storage".to_string(),
+        master_pvcs * parse_quantity(master_size).unwrap_or(0.0)
+            + worker_pvcs * parse_quantity(worker_size).unwrap_or(0.0),
+    );
+    requested.insert(
+        "pods".to_string(),
+        (kube_config.master.replicas + kube_config.worker.replicas) as f64,
+    );
+    requested
+}
+
+/// Quota entries the request would exceed, given the quota's current usage
+pub fn quota_violations(quota: &ResourceQuota, requested: &BTreeMap<String, f64>) -> Vec<String> {
+    let name = quota.metadata.name.as_deref().unwrap_or_default();
+    let status = quota.status.as_ref();
+    let hard = status
+        .and_then(|s| s.hard.as_ref())
+        .or_else(|| quota.spec.as_ref().and_then(|s| s.hard.as_ref()));
+    let used = status.and_then(|s| s.used.as_ref());
+
+    let mut violations = Vec::new();
+    for (key, limit) in hard.into_iter().flatten() {
+        // `cpu`, `memory` and `hugepages-*` are shorthands for requests
+        let canonical = if key.contains('.') || key == "pods" || key == "persistentvolumeclaims" {
+            key.clone()
+        } else {
+            format!("requests.{}", key)
+        };
+        let (Some(wanted), Some(limit)) = (requested.get(&canonical), parse_quantity(&limit.0))
+        else {
+            continue;
+        };
+        let in_use = used
+            .and_then(|u| u.get(key))
+            .and_then(|q| parse_quantity(&q.0))
+            .unwrap_or(0.0);
+
+        if *wanted > limit - in_use {
+            violations.push(format!(
+                "{} in ResourceQuota {}: need {}, {} of {} left",
+                key,
+                name,
+                format_amount(key, *wanted),
+                format_amount(key, (limit - in_use).max(0.0)),
+                format_amount(key, limit)
+            ));
+        }
+    }
+    violations
+}
+
+/// Containers of the deployment outside a LimitRange's container min/max
+pub fn limit_range_violations(
+    limit_range: &LimitRange,
+    containers: &[(&str, Option<&ResourceRequirements>)],
+) -> Vec<String> {
+    let name = limit_range.metadata.name.as_deref().unwrap_or_default();
+    let mut violations = Vec::new();
+
+    let items = limit_range.spec.as_ref().map(|s| s.limits.as_slice());
+    for item in items
+        .unwrap_or_default()
+        .iter()
+        .filter(|i| i.type_ == "Container")
+    {
+        for (component, resources) in containers {
+            for (resource, max) in item.max.iter().flatten() {
+                let amount = resource_amount(*resources, true, resource)
+                    .or_else(|| resource_amount(*resources, false, resource));
+                if let (Some(amount), Some(bound)) = (amount, parse_quantity(&max.0)) {
+                    if amount > bound {
+                        violations.push(format!(
+                            "{} {} {} exceeds LimitRange {} max {}",
+                            component,
+                            resource,
+                            format_amount(resource, amount),
+                            name,
+                            max.0
+                        ));
+                    }
+                }
+            }
+            for (resource, min) in item.min.iter().flatten() {
+                let amount = resource_amount(*resources, false, resource);
+                if let (Some(amount), Some(bound)) = (amount, parse_quantity(&min.0)) {
+                    if amount < bound {
+                        violations.push(format!(
+                            "{} {} {} is below LimitRange {} min {}",
+                            component,
+                            resource,
+                            format_amount(resource, amount),
+                            name,
+                            min.0
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Whether a toleration tolerates a taint
+pub fn tolerates(toleration: &Toleration, taint: &Taint) -> bool {
+    if toleration
+        .effect
+        .as_deref()
+        .is_some_and(|e| !e.is_empty() && e != taint.effect)
+    {
+        return false;
+    }
+    match toleration.operator.as_deref() {
+        Some("Exists") => toleration
+            .key
+            .as_deref()
+            .is_none_or(|k| k.is_empty() || k == taint.key),
+        _ => {
+            toleration.key.as_deref() == Some(taint.key.as_str())
+                && toleration.value.as_deref().unwrap_or_default()
+                    == taint.value.as_deref().unwrap_or_default()
+        }
+    }
+}
+
+/// Nodes a pod with this node selector and these tolerations can be scheduled on
+pub fn schedulable_nodes<'a>(
+    nodes: &'a [Node],
+    node_selector: Option<&HashMap<String, String>>,
+    tolerations: &[Toleration],
+) -> Vec<&'a Node> {
+    nodes
+        .iter()
+        .filter(|node| {
+            let spec = node.spec.as_ref();
+            if spec.and_then(|s| s.unschedulable).unwrap_or(false) {
+                return false;
+            }
+
+            let labels = node.metadata.labels.as_ref();
+            let selected = node_selector
+                .into_iter()
+                .flatten()
+                .all(|(key, value)| labels.and_then(|l| l.get(key)) == Some(value));
+
+            let tolerated = spec
+                .and_then(|s| s.taints.as_ref())
+                .into_iter()
+                .flatten()
+                .filter(|t| t.effect == "NoSchedule" || t.effect == "NoExecute")
+                .all(|taint| tolerations.iter().any(|t| tolerates(t, taint)));
+
+            selected && tolerated
+        })
+        .collect()
+}
+
+fn allocatable(node: &Node, resource: &str) -> f64 {
+    node.status
+        .as_ref()
+        .and_then(|s| s.allocatable.as_ref())
+        .and_then(|a| a.get(resource))
+        .and_then(|q| parse_quantity(&q.0))
+        .unwrap_or(0.0)
+}
+
+/// Runs the preflight checks against the cluster
+pub struct Doctor {
+    client: Client,
+    namespace: String,
+}
+
+impl Doctor {
+    pub fn new(client: Client, namespace: String) -> Self {
+        Self { client, namespace }
+    }
+
+    pub async fn run(
+        &self,
+        cluster_conf: &ClusterConf,
+        kube_config: &KubernetesConfig,
+    ) -> DoctorReport {
+        let mut report = DoctorReport::default();
+
+        compat::check_versions(&mut report, cluster_conf, kube_config, compat::SCHEMAS);
+        if !self.check_api_server(&mut report).await {
+            return report;
+        }
+        self.check_namespace(&mut report).await;
+        self.check_permissions(&mut report, kube_config).await;
+        self.check_quotas(&mut report, cluster_conf, kube_config)
+            .await;
+
+        let nodes: Api<Node> = Api::all(self.client.clone());
+        match nodes.list(&ListParams::default()).await {
+            Ok(list) => {
+                Self::check_placement(&mut report, &list.items, kube_config);
+                Self::check_node_capacity(&mut report, &list.items, cluster_conf, kube_config);
+            }
+            Err(e) => report.push(
+                "Nodes",
+                CheckStatus::Warn,
+                format!(
+                    "Cannot list nodes, placement and capacity not checked: {}",
+                    e
+                ),
+            ),
+        }
+
+        self.check_image_pull_secrets(&mut report, kube_config)
+            .await;
+        report
+    }
+
+    async fn check_api_server(&self, report: &mut DoctorReport) -> bool {
+        let info = match self.client.apiserver_version().await {
+            Ok(info) => info,
+            Err(e) => {
+                report.push(
+                    "API server",
+                    CheckStatus::Fail,
+                    format!("Cannot reach the API server: {}", e),
+                );
+                return false;
+            }
+        };
+
+        let number = |s: &str| {
+            s.trim_end_matches(|c: char| !c.is_ascii_digit())
+                .parse::<u32>()
+                .ok()
+        };
+        let version = number(&info.major).zip(number(&info.minor));
+        let (min_major, min_minor) = MIN_KUBERNETES_VERSION;
+        match version {
+            Some(version) if version >= MIN_KUBERNETES_VERSION => report.push(
+                "API server",
+                CheckStatus::Pass,
+                format!("Kubernetes {}", info.git_version),
+            ),
+            Some(_) => report.push(
+                "API server",
+                CheckStatus::Fail,
+                format!(
+                    "Kubernetes {} is older than the minimum supported {}.{}",
+                    info.git_version, min_major, min_minor
+                ),
+            ),
+            None => report.push(
+                "API server",
+                CheckStatus::Warn,
+                format!("Cannot parse server version {}", info.git_version),
+            ),
+        }
+        true
+    }
+
+    async fn check_namespace(&self, report: &mut DoctorReport) {
+        let api: Api<Namespace> = Api::all(self.client.clone());
+        match api.get_opt(&self.namespace).await {
+            Ok(Some(_)) => report.push(
+                "Namespace",
+                CheckStatus::Pass,
+                format!("Namespace {} exists", self.namespace),
+            ),
+            Ok(None) => report.push(
+                "Namespace",
+                CheckStatus::Fail,
+                format!(
+                    "Namespace {} does not exist (kubectl create namespace {})",
+                    self.namespace, self.namespace
+                ),
+            ),
+            Err(e) => report.push(
+                "Namespace",
+                CheckStatus::Warn,
+                format!("Cannot verify namespace {}: {}", self.namespace, e),
+            ),
+        }
+    }
+
+    async fn check_permissions(&self, report: &mut DoctorReport, kube_config: &KubernetesConfig) {
+        let permissions = required_permissions(kube_config);
+        let reviews = futures::future::join_all(
+            permissions
+                .iter()
+                .map(|permission| self.access_allowed(permission)),
+        )
+        .await;
+
+        let mut denied = Vec::new();
+        for (permission, allowed) in permissions.iter().zip(reviews) {
+            match allowed {
+                Ok(true) => {}
+                Ok(false) => denied.push(permission.describe()),
+                Err(e) => {
+                    report.push(
+                        "RBAC permissions",
+                        CheckStatus::Warn,
+                        format!("Cannot run SelfSubjectAccessReview: {}", e),
+                    );
+                    return;
+                }
+            }
+        }
+
+        if denied.is_empty() {
+            report.push(
+                "RBAC permissions",
+                CheckStatus::Pass,
+                format!("All {} required permissions granted", permissions.len()),
+            );
+        } else {
+            report.push(
+                "RBAC permissions",
+                CheckStatus::Fail,
+                format!(
+                    "Denied: {} (see `curvine-kube rbac print`)",
+                    denied.join(", ")
+                ),
+            );
+        }
+    }
+
+    async fn access_allowed(&self, permission: &Permission) -> Result<bool, KubeError> {
+        let review = SelfSubjectAccessReview {
+            spec: SelfSubjectAccessReviewSpec {
+                resource_attributes: Some(ResourceAttributes {
+                    group: Some(permission.group.to_string()),
+                    resource: Some(permission.resource.to_string()),
+                    verb: Some(permission.verb.to_string()),
+                    namespace: permission.namespaced.then(|| self.namespace.clone()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let api: Api<SelfSubjectAccessReview> = Api::all(self.client.clone());
+        let review = api.create(&PostParams::default(), &review).await?;
+        Ok(review.status.is_some_and(|s| s.allowed))
+    }
+
+    async fn check_quotas(
+        &self,
+        report: &mut DoctorReport,
+        cluster_conf: &ClusterConf,
+        kube_config: &KubernetesConfig,
+    ) {
+        let quotas: Api<ResourceQuota> = Api::namespaced(self.client.clone(), &self.namespace);
+        match quotas.list(&ListParams::default()).await {
+            Ok(list) if list.items.is_empty() => report.push(
+                "ResourceQuota",
+                CheckStatus::Pass,
+                "No ResourceQuota in namespace",
+            ),
+            Ok(list) => {
+                let requested = requested_resources(cluster_conf, kube_config);
+                let violations: Vec<String> = list
+                    .items
+                    .iter()
+                    .flat_map(|q| quota_violations(q, &requested))
+                    .collect();
+                if violations.is_empty() {
+                    report.push(
+                        "ResourceQuota",
+                        CheckStatus::Pass,
+                        format!("{} quota(s) leave enough headroom", list.items.len()),
+                    );
+                } else {
+                    report.push("ResourceQuota", CheckStatus::Fail, violations.join("; "));
+                }
+            }
+            Err(e) => report.push(
+                "ResourceQuota",
+                CheckStatus::Warn,
+                format!("Cannot list ResourceQuotas: {}", e),
+            ),
+        }
+
+        let limit_ranges: Api<LimitRange> = Api::namespaced(self.client.clone(), &self.namespace);
+        match limit_ranges.list(&ListParams::default()).await {
+            Ok(list) if list.items.is_empty() => report.push(
+                "LimitRange",
+                CheckStatus::Pass,
+                "No LimitRange in namespace",
+            ),
+            Ok(list) => {
+                let containers = [
+                    ("master", kube_config.master.resources.as_ref()),
+                    ("worker", kube_config.worker.resources.as_ref()),
+                ];
+                let violations: Vec<String> = list
+                    .items
+                    .iter()
+                    .flat_map(|l| limit_range_violations(l, &containers))
+                    .collect();
+                if violations.is_empty() {
+                    report.push(
+                        "LimitRange",
+                        CheckStatus::Pass,
+                        "Requested resources are within LimitRange bounds",
+                    );
+                } else {
+                    report.push("LimitRange", CheckStatus::Fail, violations.join("; "));
+                }
+            }
+            Err(e) => report.push(
+                "LimitRange",
+                CheckStatus::Warn,
+                format!("Cannot list LimitRanges: {}", e),
+            ),
+        }
+    }
+
+    fn check_placement(report: &mut DoctorReport, nodes: &[Node], kube_config: &KubernetesConfig) {
+        let master_spread = kube_config
+            .master
+            .placement
+            .as_ref()
+            .is_some_and(|p| p.anti_affinity == AntiAffinityMode::Required);
+        let worker_spread = kube_config.worker.anti_affinity
+            || kube_config
+                .worker
+                .placement
+                .as_ref()
+                .is_some_and(|p| p.anti_affinity == AntiAffinityMode::Required);
+
+        let components = [
+            (
+                "master",
+                kube_config.master.node_selector.as_ref(),
+                &kube_config.master.tolerations,
+                kube_config.master.replicas,
+                master_spread,
+            ),
+            (
+                "worker",
+                kube_config.worker.node_selector.as_ref(),
+                &kube_config.worker.tolerations,
+                kube_config.worker.replicas,
+                worker_spread,
+            ),
+        ];
+        for (component, selector, tolerations, replicas, spread) in components {
+            let name = format!("Node placement ({})", component);
+            let matching = schedulable_nodes(nodes, selector, tolerations).len();
+            if matching == 0 {
+                report.push(
+                    name,
+                    CheckStatus::Fail,
+                    "No schedulable node matches the node selector and tolerations",
+                );
+            } else if spread && (matching as u32) < replicas {
+                report.push(
+                    name,
+                    CheckStatus::Warn,
+                    format!(
+                        "Only {} matching node(s) for {} {}s with anti-affinity",
+                        matching, replicas, component
+                    ),
+                );
+            } else {
+                report.push(
+                    name,
+                    CheckStatus::Pass,
+                    format!("{} matching node(s)", matching),
+                );
+            }
+        }
+    }
+
+    fn check_node_capacity(
+        report: &mut DoctorReport,
+        nodes: &[Node],
+        cluster_conf: &ClusterConf,
+        kube_config: &KubernetesConfig,
+    ) {
+        let worker = &kube_config.worker;
+        let worker_nodes =
+            schedulable_nodes(nodes, worker.node_selector.as_ref(), &worker.tolerations);
+
+        let mem_tier = mem_tier_bytes(cluster_conf) as f64;
+        if mem_tier > 0.0 {
+            let name = "MEM tier capacity";
+            let request =
+                resource_amount(worker.resources.as_ref(), false, "memory").unwrap_or(0.0);
+            let needed = request.max(mem_tier);
+            let fitting = worker_nodes
+                .iter()
+                .filter(|n| allocatable(n, "memory") >= needed)
+                .count();
+            let limit = resource_amount(worker.resources.as_ref(), true, "memory");
+
+            if fitting == 0 {
+                report.push(
+                    name,
+                    CheckStatus::Fail,
+                    format!(
+                        "No worker node has {} allocatable memory for the MEM tier",
+                        format_amount("memory", needed)
+                    ),
+                );
+            } else if limit.is_some_and(|l| l < mem_tier) {
+                report.push(
+                    name,
+                    CheckStatus::Warn,
+                    format!(
+                        "Worker memory limit {} is below the MEM tier size {}; tmpfs usage \
+                         counts against the limit and may OOM-kill workers",
+                        format_amount("memory", limit.unwrap_or_default()),
+                        format_amount("memory", mem_tier)
+                    ),
+                );
+            } else if (fitting as u32) < worker.replicas {
+                report.push(
+                    name,
+                    CheckStatus::Warn,
+                    format!(
+                        "Only {} node(s) have {} allocatable memory for {} workers",
+                        fitting,
+                        format_amount("memory", needed),
+                        worker.replicas
+                    ),
+                );
+            } else {
+                report.push(
+                    name,
+                    CheckStatus::Pass,
+                    format!(
+                        "{} node(s) fit the {} MEM tier",
+                        fitting,
+                        format_amount("memory", mem_tier)
+                    ),
+                );
+            }
+        }
+
+        let components = [
+            (
+                "master",
+                kube_config.master.resources.as_ref(),
+                schedulable_nodes(
+                    nodes,
+                    kube_config.master.node_selector.as_ref(),
+                    &kube_config.master.tolerations,
+                ),
+            ),
+            ("worker", worker.resources.as_ref(), worker_nodes),
+        ];
+        for (component, resources, candidates) in components {
+            let hugepages = resources
+                .and_then(|r| r.limits.as_ref().or(r.requests.as_ref()))
+                .into_iter()
+                .flatten()
+                .filter(|(name, _)| name.starts_with("hugepages-"));
+            for (resource, quantity) in hugepages {
+                let name = format!("Hugepages ({} {})", component, resource);
+                let wanted = parse_quantity(&quantity.0).unwrap_or(0.0);
+                let fitting = candidates
+                    .iter()
+                    .filter(|n| allocatable(n, resource) >= wanted)
+                    .count();
+                if fitting == 0 {
+                    report.push(
+                        name,
+                        CheckStatus::Fail,
+                        format!(
+                            "No matching node has {} {} allocatable",
+                            quantity.0, resource
+                        ),
+                    );
+                } else {
+                    report.push(
+                        name,
+                        CheckStatus::Pass,
+                        format!(
+                            "{} node(s) have {} {} allocatable",
+                            fitting, quantity.0, resource
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    async fn check_image_pull_secrets(
+        &self,
+        report: &mut DoctorReport,
+        kube_config: &KubernetesConfig,
+    ) {
+        if kube_config.image_pull_secrets.is_empty() {
+            report.push("Image pull secrets", CheckStatus::Pass, "None configured");
+            return;
+        }
+
+        let api: Api<Secret> = Api::namespaced(self.client.clone(), &self.namespace);
+        let mut missing = Vec::new();
+        for name in &kube_config.image_pull_secrets {
+            match api.get_opt(name).await {
+                Ok(Some(_)) => {}
+                Ok(None) => missing.push(name.clone()),
+                Err(e) => {
+                    report.push(
+                        "Image pull secrets",
+                        CheckStatus::Warn,
+                        format!("Cannot verify Secret {}: {}", name, e),
+                    );
+                    return;
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            report.push(
+                "Image pull secrets",
+                CheckStatus::Pass,
+                format!("{} secret(s) present", kube_config.image_pull_secrets.len()),
+            );
+        } else {
+            report.push(
+                "Image pull secrets",
+                CheckStatus::Fail,
+                format!(
+                    "Missing in namespace {}: {}",
+                    self.namespace,
+                    missing.join(", ")
+                ),
+            );
+        }
+    }
+}
@@ -0,0 +1,608 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Offline configuration checks (`validate`)
+//!
+//! Everything here runs without cluster access. Diagnostics point at the
+//! TOML key (and line) they concern, or at the `-D` property that set it.
+
+use crate::domain::cluster::doctor::parse_quantity;
+use crate::domain::cluster::validator::KubernetesValidator;
+use crate::domain::config::curvine::{parse_size_string, ClusterConf, WorkerDataDir};
+use crate::domain::config::format::ConfigFormat;
+use crate::domain::config::kubernetes::KubernetesConfig;
+use crate::domain::config::paths::apply_conf_overrides;
+use crate::domain::config::properties::check_property;
+use crate::infrastructure::constants::{
+    MASTER_JOURNAL_PORT, MASTER_RPC_PORT, MASTER_WEB_PORT, WORKER_RPC_PORT, WORKER_WEB_PORT,
+};
+use crate::infrastructure::kubernetes::resources::{MasterBuilder, WorkerBuilder};
+use crate::shared::error::KubeError;
+use crate::shared::interpolate::interpolate;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Pseudo file name for diagnostics about `-D` properties
+pub const COMMAND_LINE: &str = "<command line>";
+
+/// TOML keys that `-D` properties override, and the properties overriding them
+const DYNAMIC_OVERRIDES: &[(&str, &[&str])] = &[
+    (
+        "client.kubernetes.master.replicas",
+        &["kubernetes.master.replicas"],
+    ),
+    (
+        "client.kubernetes.worker.replicas",
+        &["kubernetes.worker.replicas"],
+    ),
+    (
+        "client.kubernetes.image_pull_policy",
+        &["kubernetes.image.pull-policy"],
+    ),
+    (
+        "client.kubernetes.storage.master_size",
+        &["kubernetes.storage.master-size", "kubernetes.storage.size"],
+    ),
+    (
+        "client.kubernetes.storage.worker_size",
+        &["kubernetes.storage.worker-size", "kubernetes.storage.size"],
+    ),
+    (
+        "client.kubernetes.master.pod_template",
+        &["kubernetes.master.pod-template"],
+    ),
+    (
+        "client.kubernetes.worker.pod_template",
+        &["kubernetes.worker.pod-template"],
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One finding, located by file, line (when known) and key
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    /// `file:line: error[key]: message`, like compiler output
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.file)?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match &self.key {
+            Some(key) => write!(f, ": {}[{}]: {}", severity, key, self.message),
+            None => write!(f, ": {}: {}", severity, self.message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+
+    /// Point diagnostics at `file` for the keys it sets, e.g. a profile
+    /// overlay merged over the configuration file
+    pub fn relocate(&mut self, file: &str, source: &str) {
+        for diagnostic in &mut self.diagnostics {
+            if diagnostic.file == COMMAND_LINE {
+                continue;
+            }
+            let Some(line) = diagnostic
+                .key
+                .as_deref()
+                .and_then(|key| locate(source, key, None))
+            else {
+                continue;
+            };
+            diagnostic.file = file.to_string();
+            diagnostic.line = Some(line);
+        }
+    }
+}
+
+/// Interpolate and parse a configuration file in the format of its extension,
+/// see `parse_cluster_conf_as`
+pub fn parse_cluster_conf(file: &str, source: &str) -> Result<ClusterConf, Diagnostic> {
+    parse_cluster_conf_as(file, source, ConfigFormat::from_path(file))
+}
+
+/// Interpolate and parse a configuration file, reporting unresolved `${...}`
+/// references and syntax and type errors with their line
+pub fn parse_cluster_conf_as(
+    file: &str,
+    source: &str,
+    format: ConfigFormat,
+) -> Result<ClusterConf, Diagnostic> {
+    let error = |line: Option<usize>, message: String| Diagnostic {
+        severity: Severity::Error,
+        file: file.to_string(),
+        line,
+        key: None,
+        message,
+    };
+
+    let interpolated = interpolate(source, format.syntax()).map_err(|e| {
+        error(
+            None,
+            match e {
+                KubeError::ConfigError(message) => message,
+                other => other.to_string(),
+            },
+        )
+    })?;
+    match format {
+        ConfigFormat::Toml => toml::from_str(&interpolated).map_err(|e: toml::de::Error| {
+            error(
+                e.span()
+                    .map(|span| line_of_offset(&interpolated, span.start)),
+                e.message().to_string(),
+            )
+        }),
+        ConfigFormat::Yaml => serde_yaml::from_str(&interpolated)
+            .map_err(|e| error(e.location().map(|l| l.line()), e.to_string())),
+        ConfigFormat::Json => serde_json::from_str(&interpolated)
+            .map_err(|e| error(Some(e.line()).filter(|l| *l > 0), e.to_string())),
+    }
+}
+
+fn line_of_offset(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())].matches('\n').count() + 1
+}
+
+/// Line of `key` (dotted, e.g. `client.kubernetes.master.replicas`) in a TOML
+/// document; an `[index]` suffix selects an array element by its `value`.
+pub fn locate(source: &str, key: &str, value: Option<&str>) -> Option<usize> {
+    let key = key.split('[').next().unwrap_or(key);
+    let mut table = String::new();
+    let mut found = None;
+
+    for (index, line) in source.lines().enumerate() {
+        let trimmed = line.trim();
+        if found.is_none() {
+            if trimmed.starts_with('[') {
+                table = trimmed
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .split('.')
+                    .map(|part| part.trim().trim_matches('"'))
+                    .collect::<Vec<_>>()
+                    .join(".");
+                if table == key {
+                    return Some(index + 1);
+                }
+                continue;
+            }
+            let Some((name, _)) = trimmed.split_once('=') else {
+                continue;
+            };
+            let name = name
+                .split('.')
+                .map(|part| part.trim().trim_matches('"'))
+                .collect::<Vec<_>>()
+                .join(".");
+            let full = if table.is_empty() {
+                name
+            } else {
+                format!("{}.{}", table, name)
+            };
+            if full == key {
+                found = Some(index + 1);
+            }
+        }
+
+        // Array elements may sit on the lines following the key
+        if let Some(line_number) = found {
+            match value {
+                None => return found,
+                Some(value) if line.contains(&format!("\"{}\"", value)) => return Some(index + 1),
+                Some(_) if index + 1 > line_number && trimmed.starts_with('[') => return found,
+                Some(_) => {}
+            }
+        }
+    }
+    found
+}
+
+/// Collects diagnostics, locating keys in the source or the `-D` properties
+struct Linter<'a> {
+    file: &'a str,
+    source: &'a str,
+    properties: &'a HashMap<String, String>,
+    report: ValidationReport,
+}
+
+impl Linter<'_> {
+    fn push(&mut self, severity: Severity, key: &str, value: Option<&str>, message: String) {
+        let property = DYNAMIC_OVERRIDES
+            .iter()
+            .find(|(toml_key, _)| *toml_key == key)
+            .and_then(|(_, properties)| {
+                properties
+                    .iter()
+                    .find(|p| self.properties.contains_key(**p))
+            });
+
+        let diagnostic = match property {
+            // Invalid values were already reported by `lint_properties`
+            Some(property)
+                if self
+                    .report
+                    .diagnostics
+                    .iter()
+                    .any(|d| d.key.as_deref() == Some(&format!("-D{}", property))) =>
+            {
+                return
+            }
+            Some(property) => Diagnostic {
+                severity,
+                file: COMMAND_LINE.to_string(),
+                line: None,
+                key: Some(format!("-D{}", property)),
+                message,
+            },
+            None => Diagnostic {
+                severity,
+                file: self.file.to_string(),
+                line: locate(self.source, key, value),
+                key: Some(key.to_string()),
+                message,
+            },
+        };
+        self.report.diagnostics.push(diagnostic);
+    }
+}
+
+/// Key named in a `KubernetesConfig::validate` error (`master.replicas must be ...`)
+fn validation_error_key(message: &str) -> String {
+    let subject = message.split(':').next().unwrap_or(message);
+    subject
+        .split_whitespace()
+        .find(|word| {
+            (word.contains('.') || word.contains('_'))
+                && word
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '.' || c == '_')
+        })
+        .map(|word| format!("client.kubernetes.{}", word))
+        .unwrap_or_else(|| "client.kubernetes".to_string())
+}
+
+/// Run every offline check on resolved configuration.
+///
+/// `file`/`source` are the configuration file the values were read from and
+/// `properties` the `-D` overrides applied on top of it.
+pub fn lint(
+    file: &str,
+    source: &str,
+    properties: &HashMap<String, String>,
+    cluster_conf: &ClusterConf,
+    kube_config: &KubernetesConfig,
+) -> ValidationReport {
+    let mut linter = Linter {
+        file,
+        source,
+        properties,
+        report: lint_properties(properties, "deploy", kube_config),
+    };
+
+    if let Err(e) = kube_config.validate() {
+        let message = match e {
+            KubeError::ConfigError(message) | KubeError::ValidationError(message) => message,
+            other => other.to_string(),
+        };
+        linter.push(
+            Severity::Error,
+            &validation_error_key(&message),
+            None,
+            message,
+        );
+    }
+
+    check_unknown_keys(&mut linter, cluster_conf, kube_config);
+    check_sizes(&mut linter, cluster_conf, kube_config);
+    check_ports(&mut linter, cluster_conf);
+    check_mounts(&mut linter, cluster_conf, kube_config);
+
+    linter.report
+}
+
+/// Check `-D` properties against the registry for `command`; `base` is the
+/// configuration they are applied to
+pub fn lint_properties(
+    properties: &HashMap<String, String>,
+    command: &str,
+    base: &KubernetesConfig,
+) -> ValidationReport {
+    let mut keys: Vec<&String> = properties.keys().collect();
+    keys.sort();
+
+    let diagnostics = keys
+        .into_iter()
+        .filter_map(|key| {
+            let problem = check_property(key, &properties[key], command, base).err()?;
+            Some(Diagnostic {
+                severity: Severity::Error,
+                file: COMMAND_LINE.to_string(),
+                line: None,
+                key: Some(format!("-D{}", key)),
+                message: problem,
+            })
+        })
+        .collect();
+    ValidationReport { diagnostics }
+}
+
+/// `[client.kubernetes]` keys that name no `KubernetesConfig` field are ignored
+fn check_unknown_keys(
+    linter: &mut Linter,
+    cluster_conf: &ClusterConf,
+    kube_config: &KubernetesConfig,
+) {
+    for key in cluster_conf.unmodeled_keys() {
+        linter.push(
+            Severity::Warning,
+            &key,
+            None,
+            "not modeled by curvine-kube, passed through to Curvine unchanged".to_string(),
+        );
+    }
+
+    let Some(kube_conf) = &cluster_conf.client.kubernetes else {
+        return;
+    };
+    let unknown = apply_conf_overrides(kube_conf, &mut kube_config.clone()).unwrap_or_default();
+    for key in unknown {
+        linter.push(
+            Severity::Warning,
+            &key,
+            None,
+            "unknown key, ignored".to_string(),
+        );
+    }
+}
+
+fn check_sizes(linter: &mut Linter, cluster_conf: &ClusterConf, kube_config: &KubernetesConfig) {
+    if cluster_conf.client.block_size <= 0 {
+        if let Err(e) = parse_size_string(&cluster_conf.client.block_size_str) {
+            linter.push(
+                Severity::Error,
+                "client.block_size_str",
+                None,
+                format!("Invalid size: {}", e),
+            );
+        }
+    }
+
+    let mut data_dirs_valid = true;
+    for (index, data_dir) in cluster_conf.worker.data_dir.iter().enumerate() {
+        let key = format!("worker.data_dir[{}]", index);
+        if let Err(e) = WorkerDataDir::parse_data_dir(data_dir) {
+            data_dirs_valid = false;
+            linter.push(
+                Severity::Error,
+                &key,
+                Some(data_dir),
+                format!("Invalid data_dir '{}': {}", data_dir, e),
+            );
+        } else if let Some(tier) = unknown_tier(data_dir) {
+            linter.push(
+                Severity::Warning,
+                &key,
+                Some(data_dir),
+                format!("Unknown storage type '{}' is treated as DISK", tier),
+            );
+        }
+    }
+    if data_dirs_valid {
+        if let Err(e) = KubernetesValidator::validate_worker_mem_size(cluster_conf) {
+            let message = e.to_string();
+            let summary = message.lines().next().unwrap_or(&message).to_string();
+            linter.push(Severity::Error, "worker.data_dir", None, summary);
+        }
+    }
+
+    if let Some(storage) = &kube_config.storage {
+        for (key, size) in [
+            (
+                "client.kubernetes.storage.master_size",
+                &storage.master_size,
+            ),
+            (
+                "client.kubernetes.storage.worker_size",
+                &storage.worker_size,
+            ),
+        ] {
+            let Some(size) = size else {
+                continue;
+            };
+            if !parse_quantity(size).is_some_and(|amount| amount > 0.0) {
+                linter.push(
+                    Severity::Error,
+                    key,
+                    None,
+                    format!("'{}' is not a valid Kubernetes quantity (e.g. 100Gi)", size),
+                );
+            }
+        }
+    }
+}
+
+/// Storage type of a `[TYPE:SIZE]/path` entry that `StorageType` does not know
+fn unknown_tier(data_dir: &str) -> Option<&str> {
+    let prefix = data_dir.strip_prefix('[')?.split(']').next()?;
+    let tier = prefix.split(':').next()?;
+    let known = ["mem", "ssd", "hdd", "disk", "ufs"];
+    (!tier.is_empty()
+        && tier.chars().all(|c| c.is_alphabetic())
+        && !known.contains(&tier.to_lowercase().as_str()))
+    .then_some(tier)
+}
+
+/// Ports sharing a pod must differ, and should match the container ports the
+/// StatefulSets and Services expose
+fn check_ports(linter: &mut Linter, cluster_conf: &ClusterConf) {
+    let pods = [
+        vec![
+            (
+                "master.rpc_port",
+                cluster_conf.master.rpc_port,
+                MASTER_RPC_PORT,
+            ),
+            (
+                "master.web_port",
+                cluster_conf.master.web_port,
+                MASTER_WEB_PORT,
+            ),
+            (
+                "journal.rpc_port",
+                cluster_conf.journal.rpc_port,
+                MASTER_JOURNAL_PORT,
+            ),
+        ],
+        vec![
+            (
+                "worker.rpc_port",
+                cluster_conf.worker.rpc_port,
+                WORKER_RPC_PORT,
+            ),
+            (
+                "worker.web_port",
+                cluster_conf.worker.web_port,
+                WORKER_WEB_PORT,
+            ),
+        ],
+    ];
+
+    for ports in pods {
+        for (index, (key, port, container_port)) in ports.iter().enumerate() {
+            if let Some((other, _, _)) = ports[..index].iter().find(|(_, p, _)| p == port) {
+                linter.push(
+                    Severity::Error,
+                    key,
+                    None,
+                    format!("Port {} is already used by {} in the same pod", port, other),
+                );
+            } else if i32::from(*port) != *container_port {
+                linter.push(
+                    Severity::Warning,
+                    key,
+                    None,
+                    format!(
+                        "Port {} differs from container port {} exposed by the StatefulSet and Service",
+                        port, container_port
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Volume mounts must not share a path, and pod templates must mount the
+/// configured directories where the configuration expects them
+fn check_mounts(linter: &mut Linter, cluster_conf: &ClusterConf, kube_config: &KubernetesConfig) {
+    let master = MasterBuilder::new(
+        kube_config.cluster_id.clone(),
+        kube_config.namespace.clone(),
+        kube_config.clone(),
+        cluster_conf.clone(),
+        false,
+    );
+    let worker = WorkerBuilder::new(
+        kube_config.cluster_id.clone(),
+        kube_config.namespace.clone(),
+        kube_config.clone(),
+        cluster_conf.clone(),
+    );
+
+    let components = [
+        (
+            "master",
+            "journal.journal_dir",
+            master.build_volume_mounts_impl().ok(),
+            kube_config.master.pod_template.as_ref(),
+        ),
+        (
+            "worker",
+            "worker.data_dir",
+            worker.build_volume_mounts_impl().ok(),
+            kube_config.worker.pod_template.as_ref(),
+        ),
+    ];
+    for (component, key, mounts, template) in components {
+        for (index, mount) in mounts.iter().flatten().enumerate() {
+            let earlier = mounts.iter().flatten().take(index);
+            if let Some(other) = earlier
+                .into_iter()
+                .find(|m| m.mount_path == mount.mount_path)
+            {
+                linter.push(
+                    Severity::Error,
+                    key,
+                    None,
+                    format!(
+                        "{} volumes '{}' and '{}' are both mounted at {}",
+                        component, other.name, mount.name, mount.mount_path
+                    ),
+                );
+            }
+        }
+
+        if template.is_none() {
+            continue;
+        }
+        let built = if component == "master" {
+            master.build().map(|_| ())
+        } else {
+            worker.build().map(|_| ())
+        };
+        if let Err(e) = built {
+            linter.push(
+                Severity::Error,
+                &format!("client.kubernetes.{}.pod_template", component),
+                None,
+                e.to_string().trim().to_string(),
+            );
+        }
+    }
+}
@@ -14,7 +14,11 @@
 
 //! Cluster domain - Cluster lifecycle management
 
+pub mod compat;
 pub mod descriptor;
+pub mod diagnose;
+pub mod doctor;
+pub mod lint;
 pub mod validator;
 
 pub use descriptor::CurvineClusterDescriptor;
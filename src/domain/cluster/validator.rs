@@ -14,6 +14,7 @@
 
 use crate::domain::config::curvine::{parse_size_string, ClusterConf, StorageType, WorkerDataDir};
 use crate::domain::config::kubernetes::KubernetesConfig;
+use crate::infrastructure::constants::TOPOLOGY_KEY_ZONE;
 use crate::shared::error::KubeError;
 use kube::Client;
 
@@ -31,8 +32,9 @@ impl KubernetesValidator {
         cluster_conf: &ClusterConf,
         kube_config: &KubernetesConfig,
     ) -> Result<(), KubeError> {
-        self.validate_worker_mem_size(cluster_conf)?;
+        Self::validate_worker_mem_size(cluster_conf)?;
         self.validate_master_pvcs(kube_config).await?;
+        self.warn_master_zones(kube_config).await;
         if let Some(storage_config) = &kube_config.storage {
             if let Some(ref master_sc) = storage_config.master_storage_class {
                 if !master_sc.is_empty() {
@@ -75,6 +77,7 @@ impl KubernetesValidator {
         }
 
         self.validate_master_pvcs(kube_config).await?;
+        self.warn_master_zones(kube_config).await;
         if let Some(storage_config) = &kube_config.storage {
             self.validate_storage_class(&storage_config.storage_class)
                 .await?;
@@ -83,7 +86,8 @@ impl KubernetesValidator {
         Ok(())
     }
 
-    fn validate_worker_mem_size(&self, cluster_conf: &ClusterConf) -> Result<(), KubeError> {
+    /// MEM tiers must hold at least one block. Offline check, also run by `validate`.
+    pub fn validate_worker_mem_size(cluster_conf: &ClusterConf) -> Result<(), KubeError> {
         let block_size = if cluster_conf.client.block_size > 0 {
             cluster_conf.client.block_size as u64
         } else {
@@ -125,6 +129,56 @@ impl KubernetesValidator {
         Ok(())
     }
 
+    /// Print a warning when zone-aware masters outnumber the cluster's zones.
+    /// Never fails: listing nodes needs cluster-scoped read access.
+    async fn warn_master_zones(&self, kube_config: &KubernetesConfig) {
+        use k8s_openapi::api::core::v1::Node;
+        use kube::api::Api;
+
+        if !kube_config
+            .master
+            .placement
+            .as_ref()
+            .is_some_and(|p| p.is_zone_aware())
+        {
+            return;
+        }
+
+        let api: Api<Node> = Api::all(self.client.clone());
+        if let Ok(list) = api.list(&Default::default()).await {
+            let zones = Self::count_zones(&list.items);
+            if let Some(warning) = Self::zone_warning(zones, kube_config.master.replicas) {
+                println!("⚠️  {}", warning);
+            }
+        }
+    }
+
+    /// Number of distinct `topology.kubernetes.io/zone` values across nodes
+    pub fn count_zones(nodes: &[k8s_openapi::api::core::v1::Node]) -> usize {
+        nodes
+            .iter()
+            .filter_map(|n| n.metadata.labels.as_ref()?.get(TOPOLOGY_KEY_ZONE))
+            .collect::<std::collections::BTreeSet<_>>()
+            .len()
+    }
+
+    pub fn zone_warning(zones: usize, master_replicas: u32) -> Option<String> {
+        if zones == 0 {
+            return Some(format!(
+                "No nodes are labeled with {}; masters cannot be spread across zones",
+                TOPOLOGY_KEY_ZONE
+            ));
+        }
+        if (zones as u32) < master_replicas {
+            return Some(format!(
+                "Only {} zone(s) for {} masters; some masters will share a zone and a \
+                 single zone outage may cost Raft quorum",
+                zones, master_replicas
+            ));
+        }
+        None
+    }
+
     async fn validate_storage_class(&self, storage_class_name: &str) -> Result<(), KubeError> {
         use k8s_openapi::api::storage::v1::StorageClass;
         use kube::api::Api;
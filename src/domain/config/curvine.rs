@@ -15,8 +15,13 @@
 //! Type definitions for Curvine configuration
 //! This is a simplified version that keeps data structures but removes runtime dependencies
 
+use crate::domain::config::format::ConfigFormat;
+use crate::domain::config::kubernetes::{AntiAffinityMode, ServiceType, TlsMode};
+use crate::domain::config::{overlay, schema};
+use crate::shared::interpolate::interpolate;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::read_to_string;
 
 // ============================================================================
@@ -24,7 +29,7 @@ use std::fs::read_to_string;
 // ============================================================================
 
 /// Main cluster configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct ClusterConf {
     pub format_master: bool,
@@ -38,6 +43,13 @@ pub struct ClusterConf {
     pub fuse: FuseConf,
     pub s3_gateway: S3GatewayConf,
     pub job: JobConf,
+    /// Set by the Kubernetes deployment when TLS is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConf>,
+    /// Curvine options not modeled here, passed through to the cluster config
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub extra: BTreeMap<String, toml::Value>,
 }
 
 impl Default for ClusterConf {
@@ -54,20 +66,65 @@ impl Default for ClusterConf {
             fuse: FuseConf::default(),
             s3_gateway: S3GatewayConf::default(),
             job: JobConf::default(),
+            tls: None,
+            extra: BTreeMap::new(),
         }
     }
 }
 
 impl ClusterConf {
-    /// Load configuration from TOML file
+    /// Load configuration from a TOML, YAML or JSON file (by extension),
+    /// resolving `${...}` references
     pub fn from<T: AsRef<str>>(path: T) -> anyhow::Result<Self> {
-        let content = read_to_string(path.as_ref())
-            .map_err(|e| anyhow::anyhow!("Failed to read config file {}: {}", path.as_ref(), e))?;
+        Self::load(path, None, None)
+    }
+
+    /// Load configuration with the overlay of `profile` (if any) merged over it
+    pub fn from_profile<T: AsRef<str>>(path: T, profile: Option<&str>) -> anyhow::Result<Self> {
+        Self::load(path, profile, None)
+    }
+
+    /// Load configuration in `format` (by default from the extension) with the
+    /// overlay of `profile` (if any) merged over it, see `overlay`
+    pub fn load<T: AsRef<str>>(
+        path: T,
+        profile: Option<&str>,
+        format: Option<ConfigFormat>,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let format = ConfigFormat::resolve(format, path);
+
+        if let Some(profile) = profile {
+            let merged = overlay::load(path, Some(profile), format)?;
+            return toml::Value::Table(merged).try_into().map_err(|e| {
+                anyhow::anyhow!("Failed to parse {} with profile '{}': {}", path, profile, e)
+            });
+        }
 
-        let conf: Self =
-            toml::from_str(&content).map_err(|e| anyhow::anyhow!("Failed to parse TOML: {}", e))?;
+        let content = read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read config file {}: {}", path, e))?;
+        let content = interpolate(&content, format.syntax())
+            .map_err(|e| anyhow::anyhow!("Failed to interpolate {}: {}", path, e))?;
+        format.parse(&content)
+    }
 
-        Ok(conf)
+    /// Dotted names of the options kept in the `extra` maps, i.e. passed
+    /// through without being modeled
+    pub fn unmodeled_keys(&self) -> Vec<String> {
+        let sections = [
+            ("", &self.extra),
+            ("master.", &self.master.extra),
+            ("journal.", &self.journal.extra),
+            ("worker.", &self.worker.extra),
+            ("client.", &self.client.extra),
+            ("fuse.", &self.fuse.extra),
+            ("s3_gateway.", &self.s3_gateway.extra),
+            ("job.", &self.job.extra),
+        ];
+        sections
+            .into_iter()
+            .flat_map(|(prefix, extra)| extra.keys().map(move |key| format!("{}{}", prefix, key)))
+            .collect()
     }
 }
 
@@ -75,7 +132,7 @@ impl ClusterConf {
 // Network types (替代 orpc 类型)
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
 pub struct InetAddr {
     pub hostname: String,
     pub port: u16,
@@ -87,7 +144,7 @@ impl InetAddr {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RaftPeer {
     pub id: u64,
     pub hostname: String,
@@ -98,7 +155,7 @@ pub struct RaftPeer {
 // Storage types
 // ============================================================================
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
 pub enum StorageType {
     #[serde(rename = "mem")]
     Mem,
@@ -189,7 +246,7 @@ impl WorkerDataDir {
 // Master configuration
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct MasterConf {
     pub hostname: String,
@@ -227,6 +284,10 @@ pub struct MasterConf {
     pub ttl_checker_retry_attempts: u32,
     pub ttl_checker_interval: String,
     pub ttl_bucket_interval: String,
+    /// Curvine options not modeled here, passed through to the cluster config
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub extra: BTreeMap<String, toml::Value>,
 }
 
 impl Default for MasterConf {
@@ -267,6 +328,7 @@ impl Default for MasterConf {
             ttl_checker_retry_attempts: 3,
             ttl_checker_interval: "1h".to_string(),
             ttl_bucket_interval: "1h".to_string(),
+            extra: BTreeMap::new(),
         }
     }
 }
@@ -275,7 +337,7 @@ impl Default for MasterConf {
 // Journal configuration
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct JournalConf {
     pub enable: bool,
@@ -318,6 +380,10 @@ pub struct JournalConf {
     pub raft_max_committed_size_per_ready: u64,
     pub raft_retry_cache_size: u64,
     pub raft_retry_cache_ttl: String,
+    /// Curvine options not modeled here, passed through to the cluster config
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub extra: BTreeMap<String, toml::Value>,
 }
 
 impl Default for JournalConf {
@@ -361,6 +427,7 @@ impl Default for JournalConf {
             raft_max_committed_size_per_ready: 16 * 1024 * 1024,
             raft_retry_cache_size: 100000,
             raft_retry_cache_ttl: "10m".to_string(),
+            extra: BTreeMap::new(),
         }
     }
 }
@@ -369,7 +436,7 @@ impl Default for JournalConf {
 // Worker configuration
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct WorkerConf {
     pub hostname: String,
@@ -379,6 +446,8 @@ pub struct WorkerConf {
     pub worker_threads: usize,
     pub io_timeout: String,
     pub io_close_idle: bool,
+    /// Data directories as `[TYPE:CAPACITY]/path`, e.g. `[SSD:100GB]/data`
+    #[schemars(schema_with = "schema::data_dir")]
     pub data_dir: Vec<String>,
     pub tier_alias: HashMap<String, Vec<i32>>,
     pub enable_tiered_store: bool,
@@ -390,6 +459,10 @@ pub struct WorkerConf {
     pub heartbeat_interval: String,
     pub filesystem_check_interval: String,
     pub block_remover_interval: String,
+    /// Curvine options not modeled here, passed through to the cluster config
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub extra: BTreeMap<String, toml::Value>,
 }
 
 impl Default for WorkerConf {
@@ -413,6 +486,7 @@ impl Default for WorkerConf {
             heartbeat_interval: "1s".to_string(),
             filesystem_check_interval: "1m".to_string(),
             block_remover_interval: "1h".to_string(),
+            extra: BTreeMap::new(),
         }
     }
 }
@@ -422,7 +496,7 @@ impl Default for WorkerConf {
 // ============================================================================
 
 /// Client configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct ClientConf {
     pub hostname: String,
@@ -442,64 +516,201 @@ pub struct ClientConf {
     pub master_io_timeout_ms: u64,
     pub conn_size: usize,
     pub kubernetes: Option<KubernetesConf>,
+    /// Curvine options not modeled here, passed through to the cluster config
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub extra: BTreeMap<String, toml::Value>,
 }
 
 /// Kubernetes deployment configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct KubernetesConf {
+    /// Namespace of the cluster resources
     pub namespace: String,
+    /// Cluster ID, used as the prefix of resource names
     pub cluster_id: Option<String>,
     pub master: KubernetesMasterConf,
     pub worker: KubernetesWorkerConf,
     pub service: KubernetesServiceConf,
     pub storage: Option<KubernetesStorageConf>,
+    /// "Always", "IfNotPresent" or "Never"
     pub image_pull_policy: String,
+    /// Secrets used to pull the images
     pub image_pull_secrets: Vec<String>,
+    pub network_policy: Option<KubernetesNetworkPolicyConf>,
+    pub rbac: Option<KubernetesRbacConf>,
+    pub secrets: Option<KubernetesSecretsConf>,
+    pub tls: Option<KubernetesTlsConf>,
+    /// Any other `KubernetesConfig` field, applied by path (see `paths`)
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub overrides: BTreeMap<String, toml::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct KubernetesMasterConf {
+    /// Master replicas, odd for Raft
     pub replicas: u32,
     pub image: String,
+    /// Pod template YAML merged into the master pods
     pub pod_template: Option<String>,
     pub node_selector: Option<HashMap<String, String>>,
+    /// Stop the master through a preStop hook
     pub graceful_shutdown: bool,
+    pub placement: Option<KubernetesPlacementConf>,
+    /// Any other `KubernetesConfig` field, applied by path (see `paths`)
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub overrides: BTreeMap<String, toml::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct KubernetesWorkerConf {
     pub replicas: u32,
     pub image: String,
+    /// Pod template YAML merged into the worker pods
     pub pod_template: Option<String>,
     pub node_selector: Option<HashMap<String, String>>,
+    /// StorageClass of the worker data volumes
     pub storage_class: Option<String>,
+    /// Stop the worker through a preStop hook
     pub graceful_shutdown: bool,
+    /// Run workers in the host network namespace
     pub host_network: bool,
+    /// Wait for the master service in an init container
     pub init_container: bool,
+    pub placement: Option<KubernetesPlacementConf>,
+    /// Any other `KubernetesConfig` field, applied by path (see `paths`)
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub overrides: BTreeMap<String, toml::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Pod placement (`[client.kubernetes.master.placement]`, same for worker)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(default)]
+pub struct KubernetesPlacementConf {
+    /// "zone-aware" spreads pods across `topology.kubernetes.io/zone`
+    pub preset: Option<String>,
+    /// "none", "preferred" or "required"
+    #[schemars(with = "Option<AntiAffinityMode>")]
+    pub anti_affinity: Option<String>,
+    /// Topology key for anti-affinity, default "kubernetes.io/hostname"
+    pub anti_affinity_topology_key: Option<String>,
+    pub topology_spread: Vec<KubernetesTopologySpreadConf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct KubernetesTopologySpreadConf {
+    pub topology_key: String,
+    pub max_skew: i32,
+    /// "DoNotSchedule" or "ScheduleAnyway"
+    pub when_unsatisfiable: String,
+}
+
+impl Default for KubernetesTopologySpreadConf {
+    fn default() -> Self {
+        Self {
+            topology_key: "topology.kubernetes.io/zone".to_string(),
+            max_skew: 1,
+            when_unsatisfiable: "DoNotSchedule".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct KubernetesServiceConf {
+    #[schemars(with = "ServiceType")]
     pub service_type: String,
     pub annotations: HashMap<String, String>,
     pub session_affinity: Option<String>,
     pub external_ips: Vec<String>,
+    /// Any other `KubernetesConfig` field, applied by path (see `paths`)
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub overrides: BTreeMap<String, toml::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(default)]
 pub struct KubernetesStorageConf {
+    /// StorageClass of the master and worker PVCs
     pub storage_class: String,
     pub master_storage_class: Option<String>,
     pub worker_storage_class: Option<String>,
+    /// Master PVC size, e.g. "10Gi"
     pub master_size: Option<String>,
+    /// Worker PVC size, e.g. "100Gi"
     pub worker_size: Option<String>,
 }
 
+/// NetworkPolicy generation settings (`[client.kubernetes.network_policy]`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(default)]
+pub struct KubernetesNetworkPolicyConf {
+    pub enabled: bool,
+    /// Namespaces whose pods may reach master and worker RPC ports
+    pub client_namespaces: Vec<String>,
+    /// Pod labels identifying clients allowed to reach RPC ports
+    pub client_labels: HashMap<String, String>,
+    /// Namespace allowed to scrape the web ports (e.g. "monitoring")
+    pub monitoring_namespace: Option<String>,
+}
+
+/// RBAC generation settings (`[client.kubernetes.rbac]`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(default)]
+pub struct KubernetesRbacConf {
+    /// Create `<cluster-id>-master` and `<cluster-id>-worker` ServiceAccounts
+    pub create_service_accounts: bool,
+    /// Grant pods read access to pods/endpoints in their namespace
+    pub pod_discovery: bool,
+}
+
+/// Sensitive config keys (`[client.kubernetes.secrets]`)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(default)]
+pub struct KubernetesSecretsConf {
+    /// Dotted config keys moved out of the ConfigMap, e.g. "s3_gateway.secret_key"
+    pub keys: Vec<String>,
+    /// Read the values from an existing Secret (one entry per dotted key)
+    /// instead of the config file
+    pub existing_secret: Option<String>,
+}
+
+/// TLS for RPC and web endpoints (`[client.kubernetes.tls]`)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct KubernetesTlsConf {
+    pub enabled: bool,
+    /// "self-signed" (generated locally) or "cert-manager"
+    #[schemars(with = "TlsMode")]
+    pub mode: String,
+    /// cert-manager issuer name, required for mode = "cert-manager"
+    pub issuer: Option<String>,
+    /// "Issuer" or "ClusterIssuer"
+    pub issuer_kind: String,
+    /// Validity of generated certificates
+    pub validity_days: u32,
+}
+
+impl Default for KubernetesTlsConf {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: "self-signed".to_string(),
+            issuer: None,
+            issuer_kind: "Issuer".to_string(),
+            validity_days: 365,
+        }
+    }
+}
+
 impl Default for KubernetesConf {
     fn default() -> Self {
         Self {
@@ -511,6 +722,11 @@ impl Default for KubernetesConf {
             storage: None,
             image_pull_policy: "IfNotPresent".to_string(),
             image_pull_secrets: vec![],
+            network_policy: None,
+            rbac: None,
+            secrets: None,
+            tls: None,
+            overrides: BTreeMap::new(),
         }
     }
 }
@@ -523,6 +739,8 @@ impl Default for KubernetesMasterConf {
             pod_template: None,
             node_selector: None,
             graceful_shutdown: true,
+            placement: None,
+            overrides: BTreeMap::new(),
         }
     }
 }
@@ -538,6 +756,8 @@ impl Default for KubernetesWorkerConf {
             graceful_shutdown: true,
             host_network: false,
             init_container: false,
+            placement: None,
+            overrides: BTreeMap::new(),
         }
     }
 }
@@ -549,6 +769,7 @@ impl Default for KubernetesServiceConf {
             annotations: HashMap::new(),
             session_affinity: None,
             external_ips: vec![],
+            overrides: BTreeMap::new(),
         }
     }
 }
@@ -569,6 +790,7 @@ impl Default for ClientConf {
             master_io_timeout_ms: 60000,
             conn_size: 1,
             kubernetes: None,
+            extra: BTreeMap::new(),
         }
     }
 }
@@ -590,11 +812,15 @@ impl ClientConf {
 // FUSE configuration
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct FuseConf {
     pub mount_path: String,
     pub max_idle_threads: usize,
+    /// Curvine options not modeled here, passed through to the cluster config
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub extra: BTreeMap<String, toml::Value>,
 }
 
 impl Default for FuseConf {
@@ -602,6 +828,7 @@ impl Default for FuseConf {
         Self {
             mount_path: "/mnt/curvine".to_string(),
             max_idle_threads: 10,
+            extra: BTreeMap::new(),
         }
     }
 }
@@ -616,12 +843,16 @@ impl FuseConf {
 // Job configuration
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct JobConf {
     pub enabled: bool,
     pub temp_folder: String,
     pub batch_size: usize,
+    /// Curvine options not modeled here, passed through to the cluster config
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub extra: BTreeMap<String, toml::Value>,
 }
 
 impl Default for JobConf {
@@ -630,6 +861,7 @@ impl Default for JobConf {
             enabled: false,
             temp_folder: "/tmp/curvine/job".to_string(),
             batch_size: 1000,
+            extra: BTreeMap::new(),
         }
     }
 }
@@ -644,12 +876,20 @@ impl JobConf {
 // S3 Gateway configuration
 // ============================================================================
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct S3GatewayConf {
     pub hostname: String,
     pub port: u16,
     pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret_key: Option<String>,
+    /// Curvine options not modeled here, passed through to the cluster config
+    #[serde(flatten)]
+    #[schemars(skip)]
+    pub extra: BTreeMap<String, toml::Value>,
 }
 
 impl Default for S3GatewayConf {
@@ -658,10 +898,26 @@ impl Default for S3GatewayConf {
             hostname: "localhost".to_string(),
             port: 9002,
             enabled: false,
+            access_key: None,
+            secret_key: None,
+            extra: BTreeMap::new(),
         }
     }
 }
 
+// ============================================================================
+// TLS configuration
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(default)]
+pub struct TlsConf {
+    pub enabled: bool,
+    pub cert_file: String,
+    pub key_file: String,
+    pub ca_file: String,
+}
+
 // ============================================================================
 // Utility functions
 // ============================================================================
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::domain::config::paths;
+use crate::domain::config::properties::find_property;
+use crate::shared::error::KubeError;
 use crate::{KubernetesConfig, ServiceType, StorageConfig};
 use k8s_openapi::api::core::v1::ResourceRequirements;
 use std::collections::{BTreeMap, HashMap};
@@ -129,35 +132,7 @@ pub fn apply_to_kube_config(configs: &HashMap<String, String>, kube_config: &mut
         }
     }
 
-    if kube_config.master.resources.is_none() {
-        kube_config.master.resources = Some(ResourceRequirements {
-            requests: Some({
-                let mut map = BTreeMap::new();
-                map.insert(
-                    "cpu".to_string(),
-                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("1000m".to_string()),
-                );
-                map.insert(
-                    "memory".to_string(),
-                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("2Gi".to_string()),
-                );
-                map
-            }),
-            limits: Some({
-                let mut map = BTreeMap::new();
-                map.insert(
-                    "cpu".to_string(),
-                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("1000m".to_string()),
-                );
-                map.insert(
-                    "memory".to_string(),
-                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("2Gi".to_string()),
-                );
-                map
-            }),
-            ..Default::default()
-        });
-    }
+    fill_default_resources(kube_config);
 
     if let Some(cpu_str) = configs.get("kubernetes.master.cpu") {
         if let Ok(cpu_float) = cpu_str.parse::<f64>() {
@@ -187,36 +162,6 @@ pub fn apply_to_kube_config(configs: &HashMap<String, String>, kube_config: &mut
         }
     }
 
-    if kube_config.worker.resources.is_none() {
-        kube_config.worker.resources = Some(ResourceRequirements {
-            requests: Some({
-                let mut map = BTreeMap::new();
-                map.insert(
-                    "cpu".to_string(),
-                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("500m".to_string()),
-                );
-                map.insert(
-                    "memory".to_string(),
-                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("1Gi".to_string()),
-                );
-                map
-            }),
-            limits: Some({
-                let mut map = BTreeMap::new();
-                map.insert(
-                    "cpu".to_string(),
-                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("500m".to_string()),
-                );
-                map.insert(
-                    "memory".to_string(),
-                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("1Gi".to_string()),
-                );
-                map
-            }),
-            ..Default::default()
-        });
-    }
-
     if let Some(cpu_str) = configs.get("kubernetes.worker.cpu") {
         if let Ok(cpu_float) = cpu_str.parse::<f64>() {
             let cpu_milli = (cpu_float * 1000.0) as i32;
@@ -336,6 +281,91 @@ pub fn apply_to_kube_config(configs: &HashMap<String, String>, kube_config: &mut
     }
 }
 
+/// Default requests/limits for components without resources (master 1 CPU
+/// and 2Gi, worker 500m and 1Gi); applied whenever `-D` properties are given
+pub fn fill_default_resources(kube_config: &mut KubernetesConfig) {
+    if kube_config.master.resources.is_none() {
+        kube_config.master.resources = Some(ResourceRequirements {
+            requests: Some({
+                let mut map = BTreeMap::new();
+                map.insert(
+                    "cpu".to_string(),
+                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("1000m".to_string()),
+                );
+                map.insert(
+                    "memory".to_string(),
+                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("2Gi".to_string()),
+                );
+                map
+            }),
+            limits: Some({
+                let mut map = BTreeMap::new();
+                map.insert(
+                    "cpu".to_string(),
+                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("1000m".to_string()),
+                );
+                map.insert(
+                    "memory".to_string(),
+                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("2Gi".to_string()),
+                );
+                map
+            }),
+            ..Default::default()
+        });
+    }
+
+    if kube_config.worker.resources.is_none() {
+        kube_config.worker.resources = Some(ResourceRequirements {
+            requests: Some({
+                let mut map = BTreeMap::new();
+                map.insert(
+                    "cpu".to_string(),
+                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("500m".to_string()),
+                );
+                map.insert(
+                    "memory".to_string(),
+                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("1Gi".to_string()),
+                );
+                map
+            }),
+            limits: Some({
+                let mut map = BTreeMap::new();
+                map.insert(
+                    "cpu".to_string(),
+                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("500m".to_string()),
+                );
+                map.insert(
+                    "memory".to_string(),
+                    k8s_openapi::apimachinery::pkg::api::resource::Quantity("1Gi".to_string()),
+                );
+                map
+            }),
+            ..Default::default()
+        });
+    }
+}
+
+/// Apply `-Dkubernetes.<path>` keys without a registry entry by setting the
+/// `KubernetesConfig` field at `<path>` (see `paths::set_str`)
+pub fn apply_config_paths(
+    configs: &HashMap<String, String>,
+    kube_config: &mut KubernetesConfig,
+) -> Result<(), KubeError> {
+    let mut keys: Vec<&String> = configs
+        .keys()
+        .filter(|key| find_property(key).is_none())
+        .collect();
+    keys.sort();
+
+    for key in keys {
+        if let Some(path) = key.strip_prefix("kubernetes.") {
+            paths::set_str(kube_config, path, &configs[key])
+                .map_err(|e| KubeError::ConfigError(format!("-D{}: {}", key, e)))?;
+        }
+    }
+    Ok(())
+}
+
 fn parse_key_value_pairs(input: &str) -> HashMap<String, String> {
     let mut map = HashMap::new();
     for pair in input.split(',') {
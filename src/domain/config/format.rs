@@ -0,0 +1,104 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! File formats of cluster configuration files
+//!
+//! TOML, YAML and JSON files share the `ClusterConf` schema. Whatever the
+//! input format, the ConfigMap read by the Curvine processes is TOML.
+
+use crate::shared::error::KubeError;
+use crate::shared::interpolate::Syntax;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use toml::Table;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfigFormat {
+    #[default]
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl std::str::FromStr for ConfigFormat {
+    type Err = KubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "toml" => Ok(ConfigFormat::Toml),
+            "yaml" | "yml" => Ok(ConfigFormat::Yaml),
+            "json" => Ok(ConfigFormat::Json),
+            _ => Err(KubeError::ConfigError(format!(
+                "Invalid config format: {} (expected toml, yaml or json)",
+                s
+            ))),
+        }
+    }
+}
+
+impl ConfigFormat {
+    /// Format named by the extension of `path`; TOML when it has no known one
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let extension = path
+            .as_ref()
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+            Some("json") => ConfigFormat::Json,
+            _ => ConfigFormat::Toml,
+        }
+    }
+
+    /// `format` if given, else the format of `path`
+    pub fn resolve(format: Option<ConfigFormat>, path: impl AsRef<Path>) -> Self {
+        format.unwrap_or_else(|| Self::from_path(path))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigFormat::Toml => "TOML",
+            ConfigFormat::Yaml => "YAML",
+            ConfigFormat::Json => "JSON",
+        }
+    }
+
+    /// Syntax used to escape interpolated values
+    pub fn syntax(&self) -> Syntax {
+        match self {
+            ConfigFormat::Toml => Syntax::Toml,
+            ConfigFormat::Yaml => Syntax::Yaml,
+            ConfigFormat::Json => Syntax::Json,
+        }
+    }
+
+    /// Deserialize a document of this format
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> anyhow::Result<T> {
+        let parsed = match self {
+            ConfigFormat::Toml => toml::from_str(content).map_err(anyhow::Error::from),
+            ConfigFormat::Yaml => serde_yaml::from_str(content).map_err(anyhow::Error::from),
+            ConfigFormat::Json => serde_json::from_str(content).map_err(anyhow::Error::from),
+        };
+        parsed.map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", self.as_str(), e))
+    }
+
+    /// Render a configuration document in this format
+    pub fn render(&self, document: &Table) -> anyhow::Result<String> {
+        Ok(match self {
+            ConfigFormat::Toml => toml::to_string_pretty(document)?,
+            ConfigFormat::Yaml => serde_yaml::to_string(document)?,
+            ConfigFormat::Json => serde_json::to_string_pretty(document)? + "\n",
+        })
+    }
+}
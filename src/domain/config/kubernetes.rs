@@ -13,14 +13,21 @@
 // limitations under the License.
 
 use crate::domain::config::ClusterConf;
+use crate::infrastructure::constants::{
+    TLS_SECRET_SUFFIX, TOPOLOGY_KEY_HOSTNAME, TOPOLOGY_KEY_ZONE,
+};
 use crate::shared::error::KubeError;
 use k8s_openapi::api::core::v1::ResourceRequirements;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
 const CURVINE_HOME: &str = "/app/curvine";
+const DEFAULT_IMAGE: &str = "docker.io/curvine:latest";
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct KubernetesConfig {
     pub cluster_id: String,
     pub namespace: String,
@@ -31,9 +38,20 @@ pub struct KubernetesConfig {
     pub image_pull_policy: String,
     pub image_pull_secrets: Vec<String>,
     pub cluster_domain: String,
+    pub network_policy: Option<NetworkPolicyConfig>,
+    pub rbac: Option<RbacConfig>,
+    pub secrets: Option<SecretConfig>,
+    pub tls: Option<TlsConfig>,
+    /// Curvine version of the images; detected from the image tag when unset
+    pub curvine_version: Option<String>,
+    /// Expand `${...}` references in pod templates. Off by default because
+    /// shell variables in container commands use the same syntax; write
+    /// them as `$${VAR}` when enabling it
+    pub interpolate_pod_templates: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct MasterConfig {
     pub replicas: u32,
     pub image: String,
@@ -49,9 +67,11 @@ pub struct MasterConfig {
     pub env_vars: HashMap<String, String>,
     pub dns_policy: Option<String>,
     pub priority_class: Option<String>,
+    pub placement: Option<PlacementConfig>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct WorkerConfig {
     pub replicas: u32,
     pub image: String,
@@ -71,9 +91,81 @@ pub struct WorkerConfig {
     pub env_vars: HashMap<String, String>,
     pub dns_policy: Option<String>,
     pub priority_class: Option<String>,
+    pub placement: Option<PlacementConfig>,
 }
 
-#[derive(Debug, Clone)]
+impl Default for KubernetesConfig {
+    fn default() -> Self {
+        Self {
+            cluster_id: String::new(),
+            namespace: "default".to_string(),
+            master: MasterConfig::default(),
+            worker: WorkerConfig::default(),
+            service: ServiceConfig::default(),
+            storage: None,
+            image_pull_policy: "IfNotPresent".to_string(),
+            image_pull_secrets: Vec::new(),
+            cluster_domain: "cluster.local".to_string(),
+            network_policy: None,
+            rbac: None,
+            secrets: None,
+            tls: None,
+            curvine_version: None,
+            interpolate_pod_templates: false,
+        }
+    }
+}
+
+impl Default for MasterConfig {
+    fn default() -> Self {
+        Self {
+            replicas: 3,
+            image: DEFAULT_IMAGE.to_string(),
+            resources: None,
+            node_selector: None,
+            affinity: None,
+            pod_template: None,
+            graceful_shutdown: true,
+            labels: HashMap::new(),
+            annotations: HashMap::new(),
+            tolerations: Vec::new(),
+            service_account: None,
+            env_vars: HashMap::new(),
+            dns_policy: None,
+            priority_class: None,
+            placement: None,
+        }
+    }
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        Self {
+            replicas: 3,
+            image: DEFAULT_IMAGE.to_string(),
+            resources: None,
+            node_selector: None,
+            anti_affinity: false,
+            pod_template: None,
+            storage_class: None,
+            graceful_shutdown: true,
+            host_network: false,
+            init_container: false,
+            host_path_storage: None,
+            labels: HashMap::new(),
+            annotations: HashMap::new(),
+            tolerations: Vec::new(),
+            service_account: None,
+            env_vars: HashMap::new(),
+            dns_policy: None,
+            priority_class: None,
+            placement: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct ServiceConfig {
     pub service_type: ServiceType,
     pub annotations: HashMap<String, String>,
@@ -82,8 +174,9 @@ pub struct ServiceConfig {
     pub load_balancer_source_ranges: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
 pub enum ServiceType {
+    #[default]
     ClusterIP,
     NodePort,
     LoadBalancer,
@@ -115,7 +208,8 @@ impl std::str::FromStr for ServiceType {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
 pub struct StorageConfig {
     pub storage_class: String,
     pub master_storage_class: Option<String>,
@@ -124,6 +218,187 @@ pub struct StorageConfig {
     pub worker_size: Option<String>,
 }
 
+/// Ingress restrictions rendered as NetworkPolicies; `None` on
+/// `KubernetesConfig` means no policies are created.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct NetworkPolicyConfig {
+    pub client_namespaces: Vec<String>,
+    pub client_labels: HashMap<String, String>,
+    pub monitoring_namespace: Option<String>,
+}
+
+/// ServiceAccount/Role generation; `None` means pods run with whatever
+/// `service_account` names are configured, which must already exist.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RbacConfig {
+    pub create_service_accounts: bool,
+    pub pod_discovery: bool,
+}
+
+/// Config keys kept out of the ConfigMap. Pods mount the complete config from
+/// the `<cluster-id>-secrets` Secret instead; with `existing_secret` the values
+/// are read from that Secret (one entry per dotted key) at deploy time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SecretConfig {
+    pub keys: Vec<String>,
+    pub existing_secret: Option<String>,
+}
+
+impl SecretConfig {
+    /// Secret holding the complete cluster config: `<cluster-id>-secrets`
+    pub fn config_secret_name(cluster_id: &str) -> String {
+        format!("{}-secrets", cluster_id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AntiAffinityMode {
+    #[default]
+    None,
+    Preferred,
+    Required,
+}
+
+impl std::str::FromStr for AntiAffinityMode {
+    type Err = KubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(AntiAffinityMode::None),
+            "preferred" => Ok(AntiAffinityMode::Preferred),
+            "required" => Ok(AntiAffinityMode::Required),
+            _ => Err(KubeError::ConfigError(format!(
+                "Invalid anti_affinity: {} (expected none, preferred or required)",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TopologySpreadConfig {
+    pub topology_key: String,
+    pub max_skew: i32,
+    pub when_unsatisfiable: String,
+}
+
+/// Anti-affinity and topology spread between pods of one component.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PlacementConfig {
+    pub anti_affinity: AntiAffinityMode,
+    pub anti_affinity_topology_key: String,
+    pub topology_spread: Vec<TopologySpreadConfig>,
+}
+
+impl Default for PlacementConfig {
+    fn default() -> Self {
+        Self {
+            anti_affinity: AntiAffinityMode::None,
+            anti_affinity_topology_key: TOPOLOGY_KEY_HOSTNAME.to_string(),
+            topology_spread: Vec::new(),
+        }
+    }
+}
+
+impl PlacementConfig {
+    /// "zone-aware" preset: at most one pod of skew between zones and
+    /// preferably one pod per node. Explicit settings override the preset.
+    pub fn zone_aware() -> Self {
+        Self {
+            anti_affinity: AntiAffinityMode::Preferred,
+            anti_affinity_topology_key: TOPOLOGY_KEY_HOSTNAME.to_string(),
+            topology_spread: vec![TopologySpreadConfig {
+                topology_key: TOPOLOGY_KEY_ZONE.to_string(),
+                max_skew: 1,
+                when_unsatisfiable: "DoNotSchedule".to_string(),
+            }],
+        }
+    }
+
+    pub fn from_preset(preset: &str) -> Result<Self, KubeError> {
+        match preset {
+            "zone-aware" => Ok(Self::zone_aware()),
+            _ => Err(KubeError::ConfigError(format!(
+                "Invalid placement preset: {}",
+                preset
+            ))),
+        }
+    }
+
+    /// Whether pods are spread or separated by zone
+    pub fn is_zone_aware(&self) -> bool {
+        self.topology_spread
+            .iter()
+            .any(|t| t.topology_key == TOPOLOGY_KEY_ZONE)
+            || (self.anti_affinity != AntiAffinityMode::None
+                && self.anti_affinity_topology_key == TOPOLOGY_KEY_ZONE)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TlsMode {
+    /// CA and leaf certificate generated by the CLI into `<cluster-id>-tls`
+    SelfSigned,
+    /// cert-manager `Certificate` writing `<cluster-id>-tls`
+    CertManager,
+}
+
+impl TlsMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TlsMode::SelfSigned => "self-signed",
+            TlsMode::CertManager => "cert-manager",
+        }
+    }
+}
+
+impl std::str::FromStr for TlsMode {
+    type Err = KubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "self-signed" => Ok(TlsMode::SelfSigned),
+            "cert-manager" => Ok(TlsMode::CertManager),
+            _ => Err(KubeError::ConfigError(format!("Invalid TLS mode: {}", s))),
+        }
+    }
+}
+
+/// TLS certificates for RPC and web endpoints, mounted from the
+/// `<cluster-id>-tls` Secret at `TLS_MOUNT_PATH` (`tls.crt`, `tls.key`,
+/// `ca.crt`) and referenced from the `[tls]` section of the rendered config.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TlsConfig {
+    pub mode: TlsMode,
+    pub issuer: Option<String>,
+    pub issuer_kind: String,
+    pub validity_days: u32,
+}
+
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            mode: TlsMode::SelfSigned,
+            issuer: None,
+            issuer_kind: "Issuer".to_string(),
+            validity_days: 365,
+        }
+    }
+}
+
+impl TlsConfig {
+    pub fn secret_name(cluster_id: &str) -> String {
+        format!("{}{}", cluster_id, TLS_SECRET_SUFFIX)
+    }
+}
+
 pub struct KubernetesConfigBuilder {
     cluster_conf: ClusterConf,
     kube_config: KubernetesConfig,
@@ -141,18 +416,7 @@ impl KubernetesConfigBuilder {
         let mut cluster_side_conf = self.cluster_conf.clone();
 
         // Generate master DNS addresses
-        let master_addrs: Vec<_> = (0..self.kube_config.master.replicas)
-            .map(|i| {
-                format!(
-                    "{}-master-{}.{}-master.{}.svc.{}",
-                    self.kube_config.cluster_id,
-                    i,
-                    self.kube_config.cluster_id,
-                    self.kube_config.namespace,
-                    self.kube_config.cluster_domain
-                )
-            })
-            .collect();
+        let master_addrs = self.kube_config.master_hostnames();
 
         // Update journal.journal_addrs with RaftPeer (dynamically generated in k8s)
         use crate::domain::config::curvine::RaftPeer;
@@ -181,6 +445,19 @@ impl KubernetesConfigBuilder {
         cluster_side_conf.journal.journal_dir =
             Self::resolve_path(&self.cluster_conf.journal.journal_dir);
 
+        if self.kube_config.tls.is_some() {
+            use crate::domain::config::curvine::TlsConf;
+            use crate::infrastructure::constants::{
+                TLS_CA_KEY, TLS_CERT_KEY, TLS_MOUNT_PATH, TLS_PRIVATE_KEY,
+            };
+            cluster_side_conf.tls = Some(TlsConf {
+                enabled: true,
+                cert_file: format!("{}/{}", TLS_MOUNT_PATH, TLS_CERT_KEY),
+                key_file: format!("{}/{}", TLS_MOUNT_PATH, TLS_PRIVATE_KEY),
+                ca_file: format!("{}/{}", TLS_MOUNT_PATH, TLS_CA_KEY),
+            });
+        }
+
         let toml_str = toml::to_string(&cluster_side_conf)
             .map_err(|e| KubeError::ConfigError(e.to_string()))?;
 
@@ -197,6 +474,64 @@ impl KubernetesConfigBuilder {
 }
 
 impl KubernetesConfig {
+    /// ServiceAccount for master pods: the explicit name wins, otherwise the
+    /// generated `<cluster-id>-master` account when RBAC creation is enabled.
+    pub fn master_service_account(&self) -> Option<String> {
+        self.master
+            .service_account
+            .clone()
+            .or_else(|| self.generated_service_account("master"))
+    }
+
+    /// ServiceAccount for worker pods, resolved like `master_service_account`.
+    pub fn worker_service_account(&self) -> Option<String> {
+        self.worker
+            .service_account
+            .clone()
+            .or_else(|| self.generated_service_account("worker"))
+    }
+
+    /// Stable per-pod DNS names of the masters:
+    /// `<id>-master-N.<id>-master.<ns>.svc.<domain>`
+    pub fn master_hostnames(&self) -> Vec<String> {
+        (0..self.master.replicas)
+            .map(|i| {
+                format!(
+                    "{}-master-{}.{}-master.{}.svc.{}",
+                    self.cluster_id, i, self.cluster_id, self.namespace, self.cluster_domain
+                )
+            })
+            .collect()
+    }
+
+    /// DNS names covered by the TLS certificate.
+    ///
+    /// Wildcards keep the certificate valid when masters or workers are scaled.
+    pub fn tls_dns_names(&self) -> Vec<String> {
+        let mut names = self.master_hostnames();
+        for component in ["master", "worker"] {
+            names.push(format!(
+                "*.{}-{}.{}.svc.{}",
+                self.cluster_id, component, self.namespace, self.cluster_domain
+            ));
+        }
+        let service = format!("{}-master", self.cluster_id);
+        names.push(format!(
+            "{}.{}.svc.{}",
+            service, self.namespace, self.cluster_domain
+        ));
+        names.push(format!("{}.{}.svc", service, self.namespace));
+        names.push(service);
+        names
+    }
+
+    fn generated_service_account(&self, component: &str) -> Option<String> {
+        self.rbac
+            .as_ref()
+            .filter(|r| r.create_service_accounts)
+            .map(|_| format!("{}-{}", self.cluster_id, component))
+    }
+
     pub fn validate(&self) -> Result<(), KubeError> {
         if !is_valid_k8s_name(&self.cluster_id) {
             return Err(KubeError::ConfigError(format!(
@@ -238,6 +573,62 @@ impl KubernetesConfig {
             )));
         }
 
+        for (component, placement) in [
+            ("master", &self.master.placement),
+            ("worker", &self.worker.placement),
+        ] {
+            for spread in placement.iter().flat_map(|p| &p.topology_spread) {
+                if spread.max_skew < 1 {
+                    return Err(KubeError::ConfigError(format!(
+                        "{}.placement.topology_spread.max_skew must be >= 1",
+                        component
+                    )));
+                }
+                if !["DoNotSchedule", "ScheduleAnyway"]
+                    .contains(&spread.when_unsatisfiable.as_str())
+                {
+                    return Err(KubeError::ConfigError(format!(
+                        "Invalid {}.placement.topology_spread.when_unsatisfiable: {}",
+                        component, spread.when_unsatisfiable
+                    )));
+                }
+            }
+        }
+
+        if let Some(tls) = &self.tls {
+            if tls.mode == TlsMode::CertManager && tls.issuer.is_none() {
+                return Err(KubeError::ConfigError(
+                    "tls.issuer is required when tls.mode is cert-manager".to_string(),
+                ));
+            }
+            if !["Issuer", "ClusterIssuer"].contains(&tls.issuer_kind.as_str()) {
+                return Err(KubeError::ConfigError(format!(
+                    "Invalid tls.issuer_kind: {}",
+                    tls.issuer_kind
+                )));
+            }
+            if tls.validity_days == 0 {
+                return Err(KubeError::ConfigError(
+                    "tls.validity_days must be > 0".to_string(),
+                ));
+            }
+        }
+
+        if let Some(secrets) = &self.secrets {
+            for key in &secrets.keys {
+                if key.split('.').any(|part| part.is_empty())
+                    || !key
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+                {
+                    return Err(KubeError::ConfigError(format!(
+                        "Invalid secret key: {}",
+                        key
+                    )));
+                }
+            }
+        }
+
         Ok(())
     }
 }
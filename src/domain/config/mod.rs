@@ -16,21 +16,30 @@
 
 pub mod curvine;
 pub mod dynamic;
+pub mod format;
 pub mod kubernetes;
+pub mod overlay;
+pub mod paths;
+pub mod properties;
+pub mod resolver;
+pub mod scaffold;
+pub mod schema;
 
 // Re-export Curvine configuration types
 pub use self::curvine::{
     parse_size_string, ClientConf, ClusterConf, FuseConf, InetAddr, JobConf, JournalConf,
-    KubernetesConf, KubernetesMasterConf, KubernetesServiceConf, KubernetesStorageConf,
-    KubernetesWorkerConf, MasterConf, RaftPeer, S3GatewayConf, StorageType, WorkerConf,
-    WorkerDataDir,
+    KubernetesConf, KubernetesMasterConf, KubernetesNetworkPolicyConf, KubernetesPlacementConf,
+    KubernetesRbacConf, KubernetesSecretsConf, KubernetesServiceConf, KubernetesStorageConf,
+    KubernetesTlsConf, KubernetesTopologySpreadConf, KubernetesWorkerConf, MasterConf, RaftPeer,
+    S3GatewayConf, StorageType, TlsConf, WorkerConf, WorkerDataDir,
 };
 
 // Re-export Kubernetes configuration types
 pub use self::kubernetes::{
-    KubernetesConfig, KubernetesConfigBuilder, MasterConfig, ServiceConfig, ServiceType,
-    StorageConfig, WorkerConfig,
+    AntiAffinityMode, KubernetesConfig, KubernetesConfigBuilder, MasterConfig, NetworkPolicyConfig,
+    PlacementConfig, RbacConfig, SecretConfig, ServiceConfig, ServiceType, StorageConfig,
+    TlsConfig, TlsMode, TopologySpreadConfig, WorkerConfig,
 };
 
 // Re-export dynamic configuration
-pub use self::dynamic::apply_to_kube_config;
+pub use self::dynamic::{apply_config_paths, apply_to_kube_config};
@@ -0,0 +1,261 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Profile overlays for cluster configuration files
+//!
+//! `--profile prod` deep-merges `curvine-cluster.prod.toml` over
+//! `curvine-cluster.toml` before it is deserialized: tables merge key by key
+//! and any other overlay value replaces the base value. Lists are replaced
+//! unless the overlay picks another strategy for them:
+//!
+//! ```toml
+//! [overlay.merge]
+//! "worker.data_dir" = "by-path"
+//! ```
+
+use crate::domain::config::format::ConfigFormat;
+use crate::domain::config::WorkerDataDir;
+use crate::shared::error::KubeError;
+use crate::shared::interpolate::{interpolate, interpolate_redacted, Syntax};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Overlay table holding merge settings; it is removed before merging
+pub const OVERLAY_TABLE: &str = "overlay";
+
+/// How an overlay list combines with the base list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListMerge {
+    /// The overlay list replaces the base list
+    #[default]
+    Replace,
+    /// Overlay entries follow the base entries
+    Append,
+    /// Overlay entries precede the base entries
+    Prepend,
+    /// Overlay entries replace base entries with the same path (`[SSD:100GB]/data`
+    /// replaces `[HDD]/data`) and the others are appended
+    ByPath,
+}
+
+impl std::str::FromStr for ListMerge {
+    type Err = KubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "replace" => Ok(ListMerge::Replace),
+            "append" => Ok(ListMerge::Append),
+            "prepend" => Ok(ListMerge::Prepend),
+            "by-path" => Ok(ListMerge::ByPath),
+            _ => Err(KubeError::ConfigError(format!(
+                "Invalid list merge strategy: {} (expected replace, append, prepend or by-path)",
+                s
+            ))),
+        }
+    }
+}
+
+/// Overlay file of `profile` next to `base`: `curvine-cluster.toml` becomes
+/// `curvine-cluster.<profile>.toml`
+pub fn profile_path(base: &Path, profile: &str) -> PathBuf {
+    let stem = base
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let name = match base.extension() {
+        Some(extension) => format!("{}.{}.{}", stem, profile, extension.to_string_lossy()),
+        None => format!("{}.{}", stem, profile),
+    };
+    base.with_file_name(name)
+}
+
+/// Read `path` and merge the overlay of `profile` (if any, in the same
+/// format) over it
+pub fn load(path: &str, profile: Option<&str>, format: ConfigFormat) -> anyhow::Result<Table> {
+    load_with(path, profile, format, interpolate)
+}
+
+/// Like [`load`], with the contents of `${file:...}` references redacted for display
+pub fn load_redacted(
+    path: &str,
+    profile: Option<&str>,
+    format: ConfigFormat,
+) -> anyhow::Result<Table> {
+    load_with(path, profile, format, interpolate_redacted)
+}
+
+type Interpolate = fn(&str, Syntax) -> Result<String, KubeError>;
+
+fn load_with(
+    path: &str,
+    profile: Option<&str>,
+    format: ConfigFormat,
+    interpolate: Interpolate,
+) -> anyhow::Result<Table> {
+    let mut merged = read_table(Path::new(path), format, interpolate)?;
+    let Some(profile) = profile else {
+        return Ok(merged);
+    };
+
+    let overlay_path = profile_path(Path::new(path), profile);
+    if !overlay_path.exists() {
+        anyhow::bail!(
+            "Profile '{}' not found: {} does not exist",
+            profile,
+            overlay_path.display()
+        );
+    }
+    let overlay = read_table(&overlay_path, format, interpolate)?;
+    merge(&mut merged, overlay)
+        .map_err(|e| anyhow::anyhow!("{}: {}", overlay_path.display(), e))?;
+    Ok(merged)
+}
+
+fn read_table(
+    path: &Path,
+    format: ConfigFormat,
+    interpolate: Interpolate,
+) -> anyhow::Result<Table> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read config file {}: {}", path.display(), e))?;
+    let content = interpolate(&content, format.syntax())
+        .map_err(|e| anyhow::anyhow!("Failed to interpolate {}: {}", path.display(), e))?;
+    format
+        .parse(&content)
+        .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+}
+
+/// Deep-merge `overlay` over `base`, honoring the overlay's `[overlay.merge]`
+/// list strategies
+pub fn merge(base: &mut Table, mut overlay: Table) -> Result<(), KubeError> {
+    let strategies = take_strategies(&mut overlay)?;
+    for path in strategies.keys() {
+        if !matches!(lookup(&overlay, path), Some(Value::Array(_))) {
+            return Err(KubeError::ConfigError(format!(
+                "{}.merge: '{}' is not a list in the overlay",
+                OVERLAY_TABLE, path
+            )));
+        }
+    }
+    merge_table(base, overlay, "", &strategies);
+    Ok(())
+}
+
+fn take_strategies(overlay: &mut Table) -> Result<BTreeMap<String, ListMerge>, KubeError> {
+    let Some(settings) = overlay.remove(OVERLAY_TABLE) else {
+        return Ok(BTreeMap::new());
+    };
+    let Value::Table(mut settings) = settings else {
+        return Err(KubeError::ConfigError(format!(
+            "{} must be a table",
+            OVERLAY_TABLE
+        )));
+    };
+
+    let strategies = match settings.remove("merge") {
+        None => Table::new(),
+        Some(Value::Table(strategies)) => strategies,
+        Some(_) => {
+            return Err(KubeError::ConfigError(format!(
+                "{}.merge must be a table",
+                OVERLAY_TABLE
+            )))
+        }
+    };
+    if let Some(key) = settings.keys().next() {
+        return Err(KubeError::ConfigError(format!(
+            "unknown key '{}.{}'",
+            OVERLAY_TABLE, key
+        )));
+    }
+
+    strategies
+        .into_iter()
+        .map(|(path, strategy)| match strategy {
+            Value::String(strategy) => Ok((path, strategy.parse()?)),
+            other => Err(KubeError::ConfigError(format!(
+                "{}.merge.{}: expected a strategy name, got {}",
+                OVERLAY_TABLE, path, other
+            ))),
+        })
+        .collect()
+}
+
+fn lookup<'a>(table: &'a Table, path: &str) -> Option<&'a Value> {
+    let (first, rest) = path.split_once('.').unwrap_or((path, ""));
+    let value = table.get(first)?;
+    match (rest, value) {
+        ("", value) => Some(value),
+        (rest, Value::Table(table)) => lookup(table, rest),
+        _ => None,
+    }
+}
+
+fn merge_table(
+    base: &mut Table,
+    overlay: Table,
+    prefix: &str,
+    strategies: &BTreeMap<String, ListMerge>,
+) {
+    for (key, value) in overlay {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overlay)) => {
+                merge_table(base, overlay, &path, strategies)
+            }
+            (Some(Value::Array(base)), Value::Array(overlay)) => {
+                let strategy = strategies.get(&path).copied().unwrap_or_default();
+                merge_list(base, overlay, strategy)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_list(base: &mut Vec<Value>, overlay: Vec<Value>, strategy: ListMerge) {
+    match strategy {
+        ListMerge::Replace => *base = overlay,
+        ListMerge::Append => base.extend(overlay),
+        ListMerge::Prepend => {
+            let tail = std::mem::replace(base, overlay);
+            base.extend(tail);
+        }
+        ListMerge::ByPath => {
+            for entry in overlay {
+                let key = entry_path(&entry);
+                match base.iter_mut().find(|b| entry_path(b) == key) {
+                    Some(existing) => *existing = entry,
+                    None => base.push(entry),
+                }
+            }
+        }
+    }
+}
+
+/// The directory of a `worker.data_dir` entry; other entries compare whole
+fn entry_path(entry: &Value) -> Value {
+    match entry {
+        Value::String(s) => WorkerDataDir::parse_data_dir(s)
+            .map(|dir| Value::String(dir.path))
+            .unwrap_or_else(|_| entry.clone()),
+        _ => entry.clone(),
+    }
+}
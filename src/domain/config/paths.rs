@@ -0,0 +1,264 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Set any `KubernetesConfig` field by its dotted path
+//!
+//! The config is round-tripped through `serde_json::Value`, so structured
+//! fields (affinity, tolerations, resources, ...) need no per-key code.
+//! Segments are serde field names (`-` may stand for `_`), map keys or list
+//! indices: `master.tolerations`, `worker.labels.team`, `master.tolerations.0.key`.
+
+use crate::domain::config::{KubernetesConf, KubernetesConfig};
+use crate::shared::error::KubeError;
+use serde_json::{Map, Value};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathError {
+    /// The path names no field
+    Unknown(String),
+    /// The field exists but the value does not fit its type
+    Invalid(String),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::Unknown(message) | PathError::Invalid(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<PathError> for KubeError {
+    fn from(err: PathError) -> Self {
+        KubeError::ConfigError(err.to_string())
+    }
+}
+
+/// Set `path` to a structured value
+pub fn set_value(config: &mut KubernetesConfig, path: &str, value: Value) -> Result<(), PathError> {
+    set_candidates(config, path, with_string_fallback(value, None))
+}
+
+/// Set `path` from a command-line string.
+///
+/// String fields take `raw` verbatim; other fields parse it as YAML (which
+/// includes JSON), so `3`, `true`, `[a, b]` and `{"key": "v"}` all work.
+pub fn set_str(config: &mut KubernetesConfig, path: &str, raw: &str) -> Result<(), PathError> {
+    let mut root = to_value(config)?;
+    let (target, _) = slot(&mut root, path)?;
+    let candidates = if target.is_string() {
+        vec![Value::String(raw.to_string())]
+    } else {
+        let parsed = serde_yaml::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+        with_string_fallback(parsed, Some(raw))
+    };
+    set_candidates(config, path, candidates)
+}
+
+/// Apply the `[client.kubernetes]` keys that have no dedicated field in
+/// `KubernetesConf` (collected in its `overrides` maps).
+///
+/// Returns the keys that name no `KubernetesConfig` field; invalid values
+/// are errors.
+pub fn apply_conf_overrides(
+    kube_conf: &KubernetesConf,
+    config: &mut KubernetesConfig,
+) -> Result<Vec<String>, KubeError> {
+    let sections = [
+        ("", &kube_conf.overrides),
+        ("master.", &kube_conf.master.overrides),
+        ("worker.", &kube_conf.worker.overrides),
+        ("service.", &kube_conf.service.overrides),
+    ];
+
+    let mut leaves = Vec::new();
+    for (prefix, overrides) in sections {
+        for (key, value) in overrides {
+            collect_leaves(format!("{}{}", prefix, key), value, &mut leaves);
+        }
+    }
+
+    let mut unknown = Vec::new();
+    for (path, value) in leaves {
+        let value = serde_json::to_value(value)
+            .map_err(|e| KubeError::ConfigError(format!("client.kubernetes.{}: {}", path, e)))?;
+        match set_value(config, &path, value) {
+            Ok(()) => {}
+            Err(PathError::Unknown(_)) => unknown.push(format!("client.kubernetes.{}", path)),
+            // The message starts with the path
+            Err(PathError::Invalid(message)) => {
+                return Err(KubeError::ConfigError(format!(
+                    "client.kubernetes.{}",
+                    message
+                )))
+            }
+        }
+    }
+    Ok(unknown)
+}
+
+/// Tables are applied key by key, so each leaf gets its own string fallback
+/// and maps merge with values set elsewhere
+fn collect_leaves<'a>(
+    path: String,
+    value: &'a toml::Value,
+    leaves: &mut Vec<(String, &'a toml::Value)>,
+) {
+    match value {
+        toml::Value::Table(table) if !table.is_empty() => {
+            for (key, value) in table {
+                collect_leaves(format!("{}.{}", path, key), value, leaves);
+            }
+        }
+        _ => leaves.push((path, value)),
+    }
+}
+
+/// Numbers and booleans are retried as strings, e.g. `resources.limits.cpu = 2`
+/// for a `Quantity`
+fn with_string_fallback(value: Value, raw: Option<&str>) -> Vec<Value> {
+    let fallback = match (&value, raw) {
+        (Value::String(_), _) => None,
+        (_, Some(raw)) => Some(raw.to_string()),
+        (Value::Number(n), None) => Some(n.to_string()),
+        (Value::Bool(b), None) => Some(b.to_string()),
+        _ => None,
+    };
+    let mut candidates = vec![value];
+    candidates.extend(fallback.map(Value::String));
+    candidates
+}
+
+fn set_candidates(
+    config: &mut KubernetesConfig,
+    path: &str,
+    candidates: Vec<Value>,
+) -> Result<(), PathError> {
+    let root = to_value(config)?;
+    let mut first_error = None;
+
+    for candidate in candidates {
+        let is_null = candidate.is_null();
+        let mut attempt = root.clone();
+        let (target, segments) = slot(&mut attempt, path)?;
+        *target = candidate;
+
+        match serde_json::from_value::<KubernetesConfig>(attempt) {
+            Ok(updated) => {
+                // serde ignores unknown struct fields, so a path that does not
+                // survive the round trip names no field. Kubernetes types omit
+                // unset fields, so clearing one with null cannot be checked.
+                if !is_null {
+                    check_survives(&to_value(&updated)?, &segments)?;
+                }
+                *config = updated;
+                return Ok(());
+            }
+            Err(e) => {
+                first_error.get_or_insert_with(|| format!("{}: {}", path, e));
+            }
+        }
+    }
+
+    Err(PathError::Invalid(first_error.unwrap_or_default()))
+}
+
+fn to_value(config: &KubernetesConfig) -> Result<Value, PathError> {
+    serde_json::to_value(config).map_err(|e| PathError::Invalid(e.to_string()))
+}
+
+/// Walk to `path`, creating missing tables, list entries and map keys.
+/// Returns the slot and the resolved segment names.
+fn slot<'a>(root: &'a mut Value, path: &str) -> Result<(&'a mut Value, Vec<String>), PathError> {
+    let mut current = root;
+    let mut segments = Vec::new();
+
+    for segment in path.split('.') {
+        if segment.is_empty() {
+            return Err(PathError::Unknown(format!("invalid path '{}'", path)));
+        }
+        if current.is_null() {
+            *current = if segment.parse::<usize>().is_ok() {
+                Value::Array(Vec::new())
+            } else {
+                Value::Object(Map::new())
+            };
+        }
+
+        current = match current {
+            Value::Object(map) => {
+                let field = segment.replace('-', "_");
+                let key = if !map.contains_key(segment) && map.contains_key(&field) {
+                    field
+                } else {
+                    segment.to_string()
+                };
+                segments.push(key.clone());
+                map.entry(key).or_insert(Value::Null)
+            }
+            Value::Array(items) => {
+                let index = segment
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|index| *index <= items.len())
+                    .ok_or_else(|| {
+                        PathError::Invalid(format!(
+                            "{}: '{}' is not an index of a list with {} entries",
+                            path,
+                            segment,
+                            items.len()
+                        ))
+                    })?;
+                if index == items.len() {
+                    items.push(Value::Null);
+                }
+                segments.push(segment.to_string());
+                &mut items[index]
+            }
+            _ => {
+                return Err(PathError::Unknown(format!(
+                    "unknown field '{}' in '{}' (not a table)",
+                    segment,
+                    segments.join(".")
+                )))
+            }
+        };
+    }
+
+    Ok((current, segments))
+}
+
+fn check_survives(root: &Value, segments: &[String]) -> Result<(), PathError> {
+    let mut current = root;
+    for (depth, segment) in segments.iter().enumerate() {
+        let next = match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        };
+        current = next.ok_or_else(|| {
+            PathError::Unknown(if depth == 0 {
+                format!("unknown field '{}'", segment)
+            } else {
+                format!(
+                    "unknown field '{}' in '{}'",
+                    segment,
+                    segments[..depth].join(".")
+                )
+            })
+        })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,505 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Registry of the `-D key=value` properties understood by `apply_to_kube_config`
+//!
+//! Keep in sync with `dynamic.rs`: every key applied there has an entry here.
+//! Other `kubernetes.<path>` keys are accepted when `<path>` names a field of
+//! the configuration they are applied to (see `paths`); `validate_properties`
+//! rejects everything else before it is applied.
+
+use crate::domain::config::paths::{self, PathError};
+use crate::domain::config::KubernetesConfig;
+use crate::shared::error::KubeError;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::collections::HashMap;
+
+const DEPLOY: &[&str] = &["deploy"];
+const DEPLOY_UPDATE: &[&str] = &["deploy", "update"];
+
+/// `KubernetesConfig` paths fixed once the cluster is deployed (PVC templates
+/// and the Raft group size); `kubernetes.<path>` keys at or below them only
+/// apply to deploy, like their registered counterparts
+const DEPLOY_ONLY_PATHS: &[&str] = &["storage", "master.replicas", "worker.storage_class"];
+
+/// Placeholder for the free-form part of a key (`kubernetes.master.env.<NAME>`)
+const NAME_PLACEHOLDER: &str = "<NAME>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyType {
+    String,
+    /// Non-negative integer
+    Integer,
+    /// Positive number of cores, e.g. `1.5`
+    Cpu,
+    /// Kubernetes quantity, e.g. `4Gi`
+    Quantity,
+    OneOf(&'static [&'static str]),
+    /// `key1=val1,key2=val2`
+    KeyValues,
+    /// Comma-separated IP addresses
+    IpList,
+    /// Path of an existing file
+    Path,
+}
+
+impl PropertyType {
+    pub fn name(&self) -> String {
+        match self {
+            PropertyType::String => "string".to_string(),
+            PropertyType::Integer => "integer".to_string(),
+            PropertyType::Cpu => "cpu".to_string(),
+            PropertyType::Quantity => "quantity".to_string(),
+            PropertyType::OneOf(values) => values.join("|"),
+            PropertyType::KeyValues => "key=value,...".to_string(),
+            PropertyType::IpList => "ip,...".to_string(),
+            PropertyType::Path => "path".to_string(),
+        }
+    }
+
+    /// Check a value, returning why it is invalid
+    pub fn check(&self, value: &str) -> Result<(), String> {
+        match self {
+            PropertyType::String => {
+                if value.is_empty() {
+                    return Err("value must not be empty".to_string());
+                }
+            }
+            PropertyType::Integer => {
+                value
+                    .parse::<u32>()
+                    .map_err(|_| format!("'{}' is not a non-negative integer", value))?;
+            }
+            PropertyType::Cpu => match value.parse::<f64>() {
+                Ok(cpu) if cpu > 0.0 && cpu.is_finite() => {}
+                _ => return Err(format!("'{}' is not a positive number of cores", value)),
+            },
+            PropertyType::Quantity => {
+                if !is_quantity(value) {
+                    return Err(format!(
+                        "'{}' is not a Kubernetes quantity (e.g. 512Mi, 4Gi)",
+                        value
+                    ));
+                }
+            }
+            PropertyType::OneOf(values) => {
+                if !values.contains(&value) {
+                    return Err(format!("'{}' is not one of {}", value, values.join(", ")));
+                }
+            }
+            PropertyType::KeyValues => {
+                for pair in value.split(',') {
+                    match pair.split_once('=') {
+                        Some((k, _)) if !k.trim().is_empty() => {}
+                        _ => return Err(format!("'{}' is not a key=value pair", pair)),
+                    }
+                }
+            }
+            PropertyType::IpList => {
+                for ip in value.split(',') {
+                    ip.trim()
+                        .parse::<std::net::IpAddr>()
+                        .map_err(|_| format!("'{}' is not an IP address", ip.trim()))?;
+                }
+            }
+            PropertyType::Path => {
+                if !std::path::Path::new(value).is_file() {
+                    return Err(format!("file '{}' does not exist", value));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Quantity syntax accepted by the API server: a decimal number followed by an
+/// optional binary or decimal suffix or exponent
+fn is_quantity(value: &str) -> bool {
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(split);
+    if number.parse::<f64>().is_err() {
+        return false;
+    }
+    matches!(
+        suffix,
+        "" | "m" | "k" | "M" | "G" | "T" | "P" | "E" | "Ki" | "Mi" | "Gi" | "Ti" | "Pi" | "Ei"
+    ) || suffix
+        .strip_prefix(['e', 'E'])
+        .is_some_and(|exp| exp.parse::<i32>().is_ok())
+}
+
+/// A supported `-D` property
+#[derive(Debug, Clone, Copy)]
+pub struct PropertySpec {
+    pub key: &'static str,
+    pub value_type: PropertyType,
+    pub description: &'static str,
+    /// Commands the property has an effect on
+    pub commands: &'static [&'static str],
+}
+
+impl PropertySpec {
+    fn matches(&self, key: &str) -> bool {
+        match self.key.strip_suffix(NAME_PLACEHOLDER) {
+            Some(prefix) => key.strip_prefix(prefix).is_some_and(is_env_name),
+            None => self.key == key,
+        }
+    }
+}
+
+impl Serialize for PropertySpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PropertySpec", 4)?;
+        state.serialize_field("key", self.key)?;
+        state.serialize_field("type", &self.value_type.name())?;
+        state.serialize_field("description", self.description)?;
+        state.serialize_field("commands", self.commands)?;
+        state.end()
+    }
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+const IMAGE_PULL_POLICIES: &[&str] = &["Always", "IfNotPresent", "Never"];
+const SERVICE_TYPES: &[&str] = &["ClusterIP", "NodePort", "LoadBalancer"];
+const DNS_POLICIES: &[&str] = &["ClusterFirst", "ClusterFirstWithHostNet", "Default", "None"];
+
+pub const PROPERTIES: &[PropertySpec] = &[
+    PropertySpec {
+        key: "kubernetes.container.image",
+        value_type: PropertyType::String,
+        description: "Image for both master and worker",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.image",
+        value_type: PropertyType::String,
+        description: "Master image",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.image",
+        value_type: PropertyType::String,
+        description: "Worker image",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.image.pull-policy",
+        value_type: PropertyType::OneOf(IMAGE_PULL_POLICIES),
+        description: "Image pull policy",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.cluster.domain",
+        value_type: PropertyType::String,
+        description: "Cluster DNS domain used in pod and service addresses",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.replicas",
+        value_type: PropertyType::Integer,
+        description: "Master replicas (odd, fixed after deploy)",
+        commands: DEPLOY,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.replicas",
+        value_type: PropertyType::Integer,
+        description: "Worker replicas",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.storage.class",
+        value_type: PropertyType::String,
+        description: "StorageClass for master and worker PVCs",
+        commands: DEPLOY,
+    },
+    PropertySpec {
+        key: "kubernetes.storage.size",
+        value_type: PropertyType::Quantity,
+        description: "PVC size for master and worker unless set individually",
+        commands: DEPLOY,
+    },
+    PropertySpec {
+        key: "kubernetes.storage.master-size",
+        value_type: PropertyType::Quantity,
+        description: "Master PVC size",
+        commands: DEPLOY,
+    },
+    PropertySpec {
+        key: "kubernetes.storage.worker-size",
+        value_type: PropertyType::Quantity,
+        description: "Worker PVC size",
+        commands: DEPLOY,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.storage-class",
+        value_type: PropertyType::String,
+        description: "Worker StorageClass (overrides kubernetes.storage.class)",
+        commands: DEPLOY,
+    },
+    PropertySpec {
+        key: "kubernetes.master.pod-template",
+        value_type: PropertyType::Path,
+        description: "Master Pod template file (YAML)",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.pod-template",
+        value_type: PropertyType::Path,
+        description: "Worker Pod template file (YAML)",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.service.type",
+        value_type: PropertyType::OneOf(SERVICE_TYPES),
+        description: "Master Service type",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.cpu",
+        value_type: PropertyType::Cpu,
+        description: "Master CPU request and limit in cores",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.memory",
+        value_type: PropertyType::Quantity,
+        description: "Master memory request and limit",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.cpu",
+        value_type: PropertyType::Cpu,
+        description: "Worker CPU request and limit in cores",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.memory",
+        value_type: PropertyType::Quantity,
+        description: "Worker memory request and limit",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.node-selector",
+        value_type: PropertyType::KeyValues,
+        description: "Master node selector",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.node-selector",
+        value_type: PropertyType::KeyValues,
+        description: "Worker node selector",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.labels",
+        value_type: PropertyType::KeyValues,
+        description: "Extra master pod labels",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.labels",
+        value_type: PropertyType::KeyValues,
+        description: "Extra worker pod labels",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.annotations",
+        value_type: PropertyType::KeyValues,
+        description: "Extra master pod annotations",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.annotations",
+        value_type: PropertyType::KeyValues,
+        description: "Extra worker pod annotations",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.service-account",
+        value_type: PropertyType::String,
+        description: "Master ServiceAccount",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.service-account",
+        value_type: PropertyType::String,
+        description: "Worker ServiceAccount",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.env.<NAME>",
+        value_type: PropertyType::String,
+        description: "Environment variable NAME of the master container",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.env.<NAME>",
+        value_type: PropertyType::String,
+        description: "Environment variable NAME of the worker container",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.pod.dns-policy",
+        value_type: PropertyType::OneOf(DNS_POLICIES),
+        description: "DNS policy of master and worker pods",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.pod.priority-class",
+        value_type: PropertyType::String,
+        description: "PriorityClass of master and worker pods",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.master.priority-class",
+        value_type: PropertyType::String,
+        description: "Master PriorityClass (overrides kubernetes.pod.priority-class)",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.worker.priority-class",
+        value_type: PropertyType::String,
+        description: "Worker PriorityClass (overrides kubernetes.pod.priority-class)",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.service.annotations",
+        value_type: PropertyType::KeyValues,
+        description: "Master Service annotations",
+        commands: DEPLOY_UPDATE,
+    },
+    PropertySpec {
+        key: "kubernetes.service.external-ips",
+        value_type: PropertyType::IpList,
+        description: "Master Service external IPs",
+        commands: DEPLOY_UPDATE,
+    },
+];
+
+/// Registry entry for a key
+pub fn find_property(key: &str) -> Option<&'static PropertySpec> {
+    PROPERTIES.iter().find(|spec| spec.matches(key))
+}
+
+/// Closest registered key to a misspelled one
+pub fn suggest_key(key: &str) -> Option<&'static str> {
+    PROPERTIES
+        .iter()
+        .filter(|spec| !spec.key.ends_with(NAME_PLACEHOLDER))
+        .map(|spec| (edit_distance(key, spec.key), spec.key))
+        .filter(|(distance, candidate)| {
+            *distance <= 3.max(key.len() / 8) || candidate.starts_with(key)
+        })
+        .min_by_key(|(distance, candidate)| (!candidate.starts_with(key), *distance))
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Check one property for `command`, returning why it is rejected.
+///
+/// `kubernetes.<path>` keys are resolved against `base`, the configuration
+/// the properties are applied to, so list indices it defines are accepted.
+pub fn check_property(
+    key: &str,
+    value: &str,
+    command: &str,
+    base: &KubernetesConfig,
+) -> Result<(), String> {
+    let Some(spec) = find_property(key) else {
+        // Any other `kubernetes.<path>` names a `KubernetesConfig` field
+        let path_error = match key.strip_prefix("kubernetes.") {
+            Some(path) => match paths::set_str(&mut base.clone(), path, value) {
+                Ok(()) if command != "deploy" && is_deploy_only_path(path) => {
+                    return Err(format!("not supported by {} (applies to: deploy)", command))
+                }
+                Ok(()) => return Ok(()),
+                Err(PathError::Invalid(message)) => return Err(message),
+                Err(PathError::Unknown(message)) => Some(message),
+            },
+            None => None,
+        };
+        return Err(match (suggest_key(key), path_error) {
+            (Some(suggestion), _) => format!("unknown key (did you mean '{}'?)", suggestion),
+            (None, Some(message)) => format!("{} (see `curvine-kube config keys`)", message),
+            (None, None) => "unknown key (see `curvine-kube config keys`)".to_string(),
+        });
+    };
+    if !spec.commands.contains(&command) {
+        return Err(format!(
+            "not supported by {} (applies to: {})",
+            command,
+            spec.commands.join(", ")
+        ));
+    }
+    spec.value_type.check(value)
+}
+
+fn is_deploy_only_path(path: &str) -> bool {
+    let path = path.replace('-', "_");
+    DEPLOY_ONLY_PATHS.iter().any(|fixed| {
+        path.strip_prefix(fixed)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// Reject unknown keys, keys not applicable to `command` and invalid values
+pub fn validate_properties(
+    properties: &HashMap<String, String>,
+    command: &str,
+    base: &KubernetesConfig,
+) -> Result<(), KubeError> {
+    let mut keys: Vec<&String> = properties.keys().collect();
+    keys.sort();
+
+    let problems: Vec<String> = keys
+        .into_iter()
+        .filter_map(|key| {
+            check_property(key, &properties[key], command, base)
+                .err()
+                .map(|problem| format!("-D{}: {}", key, problem))
+        })
+        .collect();
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(KubeError::ConfigError(format!(
+            "Invalid dynamic properties:\n  {}",
+            problems.join("\n  ")
+        )))
+    }
+}
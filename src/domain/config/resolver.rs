@@ -0,0 +1,353 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Layered resolution of `KubernetesConfig`
+//!
+//! Layers apply in the order defaults < config file < environment < `-D`
+//! properties < command-line flags. Every leaf of the result remembers the
+//! layer that last set it, which `config resolve` prints as its source.
+
+use crate::domain::config::dynamic::{
+    apply_config_paths, apply_to_kube_config, fill_default_resources,
+};
+use crate::domain::config::paths;
+use crate::domain::config::{
+    AntiAffinityMode, KubernetesConf, KubernetesConfig, KubernetesPlacementConf,
+    NetworkPolicyConfig, PlacementConfig, RbacConfig, SecretConfig, StorageConfig, TlsConfig,
+    TlsMode, TopologySpreadConfig,
+};
+use crate::shared::error::KubeError;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Layer {
+    Default,
+    ConfigFile,
+    Environment,
+    Property,
+    Flag,
+}
+
+impl Layer {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Layer::Default => "default",
+            Layer::ConfigFile => "config-file",
+            Layer::Environment => "environment",
+            Layer::Property => "property",
+            Layer::Flag => "flag",
+        }
+    }
+}
+
+/// Environment variables of the environment layer and the paths they set
+pub const ENVIRONMENT: &[(&str, &[&str])] = &[
+    ("CURVINE_CLUSTER_ID", &["cluster_id"]),
+    ("CURVINE_NAMESPACE", &["namespace"]),
+    ("CURVINE_IMAGE", &["master.image", "worker.image"]),
+    ("CURVINE_IMAGE_PULL_POLICY", &["image_pull_policy"]),
+    ("CURVINE_SERVICE_TYPE", &["service.service_type"]),
+    ("CURVINE_STORAGE_CLASS", &["storage.storage_class"]),
+    ("CURVINE_VERSION", &["curvine_version"]),
+];
+
+/// Builds a `KubernetesConfig` layer by layer; call the layer methods from
+/// lowest to highest precedence.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigResolver {
+    config: KubernetesConfig,
+    sources: BTreeMap<String, Layer>,
+}
+
+impl ConfigResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `[client.kubernetes]` of the configuration file.
+    ///
+    /// Returns the keys that name no `KubernetesConfig` field.
+    pub fn config_file(&mut self, kube_conf: &KubernetesConf) -> Result<Vec<String>, KubeError> {
+        let mut unknown = Vec::new();
+        self.apply(Layer::ConfigFile, |config| {
+            apply_kube_conf(kube_conf, config)?;
+            unknown = paths::apply_conf_overrides(kube_conf, config)?;
+            Ok(())
+        })?;
+        Ok(unknown)
+    }
+
+    /// Variables listed in `ENVIRONMENT`, read through `lookup`
+    pub fn environment(
+        &mut self,
+        lookup: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), KubeError> {
+        for (name, targets) in ENVIRONMENT {
+            let Some(value) = lookup(name).filter(|v| !v.is_empty()) else {
+                continue;
+            };
+            for path in *targets {
+                self.set(Layer::Environment, path, &value)
+                    .map_err(|e| KubeError::ConfigError(format!("{}: {}", name, e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `-D` properties, already checked by `validate_properties`
+    pub fn properties(&mut self, properties: &HashMap<String, String>) -> Result<(), KubeError> {
+        self.apply(Layer::Default, |config| {
+            fill_default_resources(config);
+            Ok(())
+        })?;
+        self.apply(Layer::Property, |config| {
+            apply_to_kube_config(properties, config);
+            apply_config_paths(properties, config)
+        })
+    }
+
+    /// A command-line flag; `None` means the flag was not passed
+    pub fn flag(&mut self, path: &str, value: Option<&str>) -> Result<(), KubeError> {
+        match value {
+            Some(value) => self.set(Layer::Flag, path, value),
+            None => Ok(()),
+        }
+    }
+
+    pub fn finish(self) -> ResolvedConfig {
+        ResolvedConfig {
+            config: self.config,
+            sources: self.sources,
+        }
+    }
+
+    /// Set `path` from `raw` as `layer`, even when the value does not change
+    fn set(&mut self, layer: Layer, path: &str, raw: &str) -> Result<(), KubeError> {
+        self.apply(layer, |config| {
+            paths::set_str(config, path, raw).map_err(KubeError::from)
+        })?;
+        let prefix = format!("{}.", path);
+        for leaf in leaves(&self.config).into_keys() {
+            if leaf == path || leaf.starts_with(&prefix) {
+                self.sources.insert(leaf, layer);
+            }
+        }
+        Ok(())
+    }
+
+    /// Run `apply`, attributing the leaves it changes to `layer`
+    fn apply(
+        &mut self,
+        layer: Layer,
+        apply: impl FnOnce(&mut KubernetesConfig) -> Result<(), KubeError>,
+    ) -> Result<(), KubeError> {
+        let before = leaves(&self.config);
+        apply(&mut self.config)?;
+        let after = leaves(&self.config);
+        for (path, value) in &after {
+            if before.get(path) != Some(value) {
+                self.sources.insert(path.clone(), layer);
+            }
+        }
+        self.sources.retain(|path, _| after.contains_key(path));
+        Ok(())
+    }
+}
+
+/// The effective configuration and where each value came from
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    pub config: KubernetesConfig,
+    sources: BTreeMap<String, Layer>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedEntry {
+    pub path: String,
+    pub value: Value,
+    pub source: Layer,
+}
+
+impl ResolvedConfig {
+    pub fn source(&self, path: &str) -> Layer {
+        self.sources.get(path).copied().unwrap_or(Layer::Default)
+    }
+
+    /// Every set leaf of the configuration with its source; unset options
+    /// and empty defaults are left out
+    pub fn entries(&self) -> Vec<ResolvedEntry> {
+        leaves(&self.config)
+            .into_iter()
+            .map(|(path, value)| ResolvedEntry {
+                source: self.source(&path),
+                path,
+                value,
+            })
+            .filter(|entry| match &entry.value {
+                Value::Null => false,
+                Value::Array(items) => !items.is_empty() || entry.source != Layer::Default,
+                Value::Object(map) => !map.is_empty() || entry.source != Layer::Default,
+                _ => true,
+            })
+            .collect()
+    }
+}
+
+/// Scalars, lists and empty tables of `config` by dotted path
+fn leaves(config: &KubernetesConfig) -> BTreeMap<String, Value> {
+    fn walk(path: String, value: Value, out: &mut BTreeMap<String, Value>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, value) in map {
+                    let path = if path.is_empty() {
+                        key
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    walk(path, value, out);
+                }
+            }
+            value => {
+                out.insert(path, value);
+            }
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    if let Ok(value) = serde_json::to_value(config) {
+        walk(String::new(), value, &mut out);
+    }
+    out
+}
+
+/// Map the modeled `[client.kubernetes]` fields onto `config`
+fn apply_kube_conf(conf: &KubernetesConf, config: &mut KubernetesConfig) -> Result<(), KubeError> {
+    if let Some(cluster_id) = &conf.cluster_id {
+        config.cluster_id = cluster_id.clone();
+    }
+    config.namespace = conf.namespace.clone();
+    config.image_pull_policy = conf.image_pull_policy.clone();
+    config.image_pull_secrets = conf.image_pull_secrets.clone();
+
+    let master = &mut config.master;
+    master.replicas = conf.master.replicas;
+    master.image = conf.master.image.clone();
+    master.pod_template = conf.master.pod_template.clone();
+    master.node_selector = conf.master.node_selector.clone();
+    master.graceful_shutdown = conf.master.graceful_shutdown;
+    master.placement = placement_config(conf.master.placement.as_ref())?;
+
+    let worker = &mut config.worker;
+    worker.replicas = conf.worker.replicas;
+    worker.image = conf.worker.image.clone();
+    worker.pod_template = conf.worker.pod_template.clone();
+    worker.node_selector = conf.worker.node_selector.clone();
+    worker.graceful_shutdown = conf.worker.graceful_shutdown;
+    worker.host_network = conf.worker.host_network;
+    worker.init_container = conf.worker.init_container;
+    worker.placement = placement_config(conf.worker.placement.as_ref())?;
+
+    config.service.service_type = conf.service.service_type.parse()?;
+    config.service.annotations = conf.service.annotations.clone();
+    config.service.session_affinity = conf.service.session_affinity.clone();
+    config.service.external_ips = conf.service.external_ips.clone();
+
+    // worker.storage_class is the older spelling of storage.storage_class
+    if conf.storage.is_some() || conf.worker.storage_class.is_some() {
+        let mut storage = conf
+            .storage
+            .as_ref()
+            .map(|s| StorageConfig {
+                storage_class: s.storage_class.clone(),
+                master_storage_class: s.master_storage_class.clone(),
+                worker_storage_class: s.worker_storage_class.clone(),
+                master_size: s.master_size.clone(),
+                worker_size: s.worker_size.clone(),
+            })
+            .unwrap_or_default();
+        if let Some(storage_class) = &conf.worker.storage_class {
+            storage.storage_class = storage_class.clone();
+        }
+        config.worker.storage_class = Some(storage.storage_class.clone());
+        config.storage = Some(storage);
+    }
+
+    config.network_policy = conf
+        .network_policy
+        .as_ref()
+        .filter(|np| np.enabled)
+        .map(|np| NetworkPolicyConfig {
+            client_namespaces: np.client_namespaces.clone(),
+            client_labels: np.client_labels.clone(),
+            monitoring_namespace: np.monitoring_namespace.clone(),
+        });
+    config.rbac = conf.rbac.as_ref().map(|r| RbacConfig {
+        create_service_accounts: r.create_service_accounts,
+        pod_discovery: r.pod_discovery,
+    });
+    config.secrets = conf
+        .secrets
+        .as_ref()
+        .filter(|s| !s.keys.is_empty())
+        .map(|s| SecretConfig {
+            keys: s.keys.clone(),
+            existing_secret: s.existing_secret.clone(),
+        });
+    config.tls = match conf.tls.as_ref().filter(|t| t.enabled) {
+        Some(t) => Some(TlsConfig {
+            mode: t.mode.parse::<TlsMode>()?,
+            issuer: t.issuer.clone(),
+            issuer_kind: t.issuer_kind.clone(),
+            validity_days: t.validity_days,
+        }),
+        None => None,
+    };
+    Ok(())
+}
+
+/// Resolve a `[client.kubernetes.<component>.placement]` section: the preset
+/// (if any) provides the base and explicit settings override it.
+fn placement_config(
+    conf: Option<&KubernetesPlacementConf>,
+) -> Result<Option<PlacementConfig>, KubeError> {
+    let Some(conf) = conf else {
+        return Ok(None);
+    };
+
+    let mut placement = match &conf.preset {
+        Some(preset) => PlacementConfig::from_preset(preset)?,
+        None => PlacementConfig::default(),
+    };
+    if let Some(mode) = &conf.anti_affinity {
+        placement.anti_affinity = mode.parse::<AntiAffinityMode>()?;
+    }
+    if let Some(key) = &conf.anti_affinity_topology_key {
+        placement.anti_affinity_topology_key = key.clone();
+    }
+    if !conf.topology_spread.is_empty() {
+        placement.topology_spread = conf
+            .topology_spread
+            .iter()
+            .map(|t| TopologySpreadConfig {
+                topology_key: t.topology_key.clone(),
+                max_skew: t.max_skew,
+                when_unsatisfiable: t.when_unsatisfiable.clone(),
+            })
+            .collect();
+    }
+    Ok(Some(placement))
+}
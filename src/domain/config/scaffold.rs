@@ -0,0 +1,398 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Commented `curvine-cluster.toml` scaffolds for `curvine-kube init`
+//!
+//! A `Preset` picks replicas, storage tiers, resources and placement; facts
+//! discovered from the target cluster (StorageClasses, node labels, zones)
+//! prefill the StorageClass and zone-aware placement and are listed in
+//! comments next to the keys they apply to.
+
+use crate::infrastructure::constants::{TOPOLOGY_KEY_HOSTNAME, TOPOLOGY_KEY_ZONE};
+use crate::shared::error::KubeError;
+use k8s_openapi::api::core::v1::Node;
+use k8s_openapi::api::storage::v1::StorageClass;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+const DEFAULT_CLASS_ANNOTATION: &str = "storageclass.kubernetes.io/is-default-class";
+const TOPOLOGY_KEY_REGION: &str = "topology.kubernetes.io/region";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Preset {
+    /// One master and one worker with small volumes
+    #[default]
+    Dev,
+    /// Three masters and workers with resources, spread across nodes
+    Prod,
+    /// Workers caching a mounted under file system in memory and on SSD
+    CacheOnly,
+    /// Five masters spread across nodes and zones
+    Ha,
+}
+
+impl std::str::FromStr for Preset {
+    type Err = KubeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dev" => Ok(Preset::Dev),
+            "prod" => Ok(Preset::Prod),
+            "cache-only" => Ok(Preset::CacheOnly),
+            "ha" => Ok(Preset::Ha),
+            _ => Err(KubeError::ConfigError(format!(
+                "Invalid preset: {} (expected dev, prod, cache-only or ha)",
+                s
+            ))),
+        }
+    }
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [Preset::Dev, Preset::Prod, Preset::CacheOnly, Preset::Ha];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Preset::Dev => "dev",
+            Preset::Prod => "prod",
+            Preset::CacheOnly => "cache-only",
+            Preset::Ha => "ha",
+        }
+    }
+}
+
+/// What `init` knows about the target cluster
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClusterFacts {
+    /// StorageClass names
+    pub storage_classes: Vec<String>,
+    /// StorageClass marked as the cluster default
+    pub default_storage_class: Option<String>,
+    /// Values of `topology.kubernetes.io/zone`
+    pub zones: BTreeSet<String>,
+    /// Node labels that tell nodes apart (not set to the same value on every
+    /// node), with their values; hostname and topology labels are left out
+    pub node_labels: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl ClusterFacts {
+    pub fn from_objects(storage_classes: &[StorageClass], nodes: &[Node]) -> Self {
+        let default_storage_class = storage_classes
+            .iter()
+            .find(|class| {
+                class
+                    .metadata
+                    .annotations
+                    .as_ref()
+                    .and_then(|a| a.get(DEFAULT_CLASS_ANNOTATION))
+                    .is_some_and(|value| value == "true")
+            })
+            .and_then(|class| class.metadata.name.clone());
+        let mut names: Vec<String> = storage_classes
+            .iter()
+            .filter_map(|class| class.metadata.name.clone())
+            .collect();
+        names.sort();
+
+        let labels: Vec<BTreeMap<String, String>> = nodes
+            .iter()
+            .map(|node| node.metadata.labels.clone().unwrap_or_default())
+            .collect();
+        let zones = labels
+            .iter()
+            .filter_map(|l| l.get(TOPOLOGY_KEY_ZONE).cloned())
+            .collect();
+
+        let mut values: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for (key, value) in labels.iter().flatten() {
+            if [
+                TOPOLOGY_KEY_HOSTNAME,
+                TOPOLOGY_KEY_ZONE,
+                TOPOLOGY_KEY_REGION,
+            ]
+            .contains(&key.as_str())
+            {
+                continue;
+            }
+            values.entry(key.clone()).or_default().insert(value.clone());
+        }
+        let node_labels = values
+            .into_iter()
+            .filter(|(key, set)| set.len() > 1 || labels.iter().any(|l| !l.contains_key(key)))
+            .collect();
+
+        Self {
+            storage_classes: names,
+            default_storage_class,
+            zones,
+            node_labels,
+        }
+    }
+}
+
+/// Values of the generated file
+#[derive(Debug, Clone)]
+pub struct ScaffoldOptions {
+    pub preset: Preset,
+    pub cluster_id: String,
+    pub namespace: String,
+    pub image: String,
+    /// StorageClass of the volumes (default: the cluster default from facts)
+    pub storage_class: Option<String>,
+}
+
+struct Layout {
+    master_replicas: u32,
+    worker_replicas: u32,
+    data_dir: &'static [&'static str],
+    master_size: &'static str,
+    worker_size: &'static str,
+    /// CPU and memory limits of masters and workers
+    master_resources: Option<(&'static str, &'static str)>,
+    worker_resources: Option<(&'static str, &'static str)>,
+    master_anti_affinity: Option<&'static str>,
+    worker_anti_affinity: Option<&'static str>,
+    /// Spread across zones even when the cluster shows no zones
+    zone_aware: bool,
+    rbac: bool,
+}
+
+impl Preset {
+    fn layout(&self) -> Layout {
+        match self {
+            Preset::Dev => Layout {
+                master_replicas: 1,
+                worker_replicas: 1,
+                data_dir: &["[SSD:8GB]/data/ssd"],
+                master_size: "5Gi",
+                worker_size: "10Gi",
+                master_resources: None,
+                worker_resources: None,
+                master_anti_affinity: None,
+                worker_anti_affinity: None,
+                zone_aware: false,
+                rbac: false,
+            },
+            Preset::Prod => Layout {
+                master_replicas: 3,
+                worker_replicas: 3,
+                data_dir: &["[SSD:180GB]/data/ssd"],
+                master_size: "20Gi",
+                worker_size: "200Gi",
+                master_resources: Some(("2", "4Gi")),
+                worker_resources: Some(("4", "8Gi")),
+                master_anti_affinity: Some("required"),
+                worker_anti_affinity: Some("preferred"),
+                zone_aware: false,
+                rbac: true,
+            },
+            Preset::CacheOnly => Layout {
+                master_replicas: 3,
+                worker_replicas: 3,
+                data_dir: &["[MEM:4GB]/data/mem", "[SSD:90GB]/data/ssd"],
+                master_size: "10Gi",
+                worker_size: "100Gi",
+                master_resources: Some(("1", "2Gi")),
+                worker_resources: Some(("2", "8Gi")),
+                master_anti_affinity: Some("preferred"),
+                worker_anti_affinity: Some("preferred"),
+                zone_aware: false,
+                rbac: false,
+            },
+            Preset::Ha => Layout {
+                master_replicas: 5,
+                worker_replicas: 3,
+                data_dir: &["[SSD:180GB]/data/ssd"],
+                master_size: "20Gi",
+                worker_size: "200Gi",
+                master_resources: Some(("2", "4Gi")),
+                worker_resources: Some(("4", "8Gi")),
+                master_anti_affinity: Some("required"),
+                worker_anti_affinity: Some("preferred"),
+                zone_aware: true,
+                rbac: true,
+            },
+        }
+    }
+}
+
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+/// `code` with `comment` aligned after it
+fn line(out: &mut String, code: &str, comment: &str) {
+    let _ = writeln!(out, "{:<46}# {}", code, comment);
+}
+
+fn section(out: &mut String, title: &str) {
+    let rule = "=".repeat(76);
+    let _ = writeln!(out, "\n# {}\n# {}\n# {}", rule, title, rule);
+}
+
+/// Render the commented configuration file
+pub fn render(options: &ScaffoldOptions, facts: &ClusterFacts) -> String {
+    let layout = options.preset.layout();
+    let zone_aware = layout.zone_aware || (options.preset != Preset::Dev && facts.zones.len() > 1);
+    let storage_class = options
+        .storage_class
+        .clone()
+        .or_else(|| facts.default_storage_class.clone());
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# Curvine cluster configuration (curvine-kube init --preset {})",
+        options.preset.as_str()
+    );
+    out.push_str("#\n");
+    out.push_str("# Check:    curvine-kube validate --config-file curvine-cluster.toml\n");
+    out.push_str("# Deploy:   curvine-kube deploy --config-file curvine-cluster.toml\n");
+    out.push_str("# All keys: curvine-kube config schema, curvine-kube config keys\n");
+
+    section(&mut out, "Worker storage");
+    out.push_str("[worker]\n");
+    out.push_str("# Tiers as [TYPE:CAPACITY]/path, TYPE being mem, ssd, hdd, disk or ufs.\n");
+    out.push_str("# MEM tiers are memory-backed emptyDirs; every other tier gets its own\n");
+    out.push_str("# PVC of client.kubernetes.storage.worker_size.\n");
+    if options.preset == Preset::CacheOnly {
+        out.push_str("# Workers only cache the under file system (e.g. S3) mounted after\n");
+        out.push_str("# deploy, so losing a tier loses no data.\n");
+    }
+    let data_dir: Vec<String> = layout.data_dir.iter().map(|d| quote(d)).collect();
+    let _ = writeln!(out, "data_dir = [{}]", data_dir.join(", "));
+
+    section(&mut out, "Kubernetes");
+    out.push_str("[client.kubernetes]\n");
+    let _ = writeln!(out, "cluster_id = {}", quote(&options.cluster_id));
+    let _ = writeln!(out, "namespace = {}", quote(&options.namespace));
+    line(
+        &mut out,
+        "image_pull_policy = \"IfNotPresent\"",
+        "Always | IfNotPresent | Never",
+    );
+    out.push_str("# image_pull_secrets = [\"registry-credentials\"]\n");
+
+    let components = [
+        (
+            "master",
+            layout.master_replicas,
+            layout.master_resources,
+            layout.master_anti_affinity,
+            "Odd for Raft: 1, 3, 5",
+        ),
+        (
+            "worker",
+            layout.worker_replicas,
+            layout.worker_resources,
+            layout.worker_anti_affinity,
+            "Change later with update --worker-replicas",
+        ),
+    ];
+    for (component, replicas, resources, anti_affinity, replicas_note) in components {
+        let _ = writeln!(out, "\n[client.kubernetes.{}]", component);
+        line(&mut out, &format!("replicas = {}", replicas), replicas_note);
+        let _ = writeln!(out, "image = {}", quote(&options.image));
+        node_selector_hint(&mut out, facts);
+
+        if let Some((cpu, memory)) = resources {
+            let _ = writeln!(out, "\n[client.kubernetes.{}.resources]", component);
+            for kind in ["requests", "limits"] {
+                let _ = writeln!(
+                    out,
+                    "{} = {{ cpu = {}, memory = {} }}",
+                    kind,
+                    quote(cpu),
+                    quote(memory)
+                );
+            }
+        }
+
+        if anti_affinity.is_some() || zone_aware {
+            let _ = writeln!(out, "\n[client.kubernetes.{}.placement]", component);
+            if zone_aware {
+                let zones: Vec<&str> = facts.zones.iter().map(String::as_str).collect();
+                let note = match zones.is_empty() {
+                    true => "Spread across zones".to_string(),
+                    false => format!("Spread across zones: {}", zones.join(", ")),
+                };
+                line(&mut out, "preset = \"zone-aware\"", &note);
+            }
+            if let Some(mode) = anti_affinity {
+                line(
+                    &mut out,
+                    &format!("anti_affinity = {}", quote(mode)),
+                    "One pod per node: none | preferred | required",
+                );
+            }
+        }
+    }
+
+    out.push_str("\n[client.kubernetes.storage]\n");
+    let class_note = match facts.storage_classes.is_empty() {
+        true => "Cluster default when unset".to_string(),
+        false => format!("Available: {}", facts.storage_classes.join(", ")),
+    };
+    match &storage_class {
+        Some(class) => line(
+            &mut out,
+            &format!("storage_class = {}", quote(class)),
+            &class_note,
+        ),
+        None => line(&mut out, "# storage_class = \"standard\"", &class_note),
+    }
+    let _ = writeln!(out, "master_size = {}", quote(layout.master_size));
+    line(
+        &mut out,
+        &format!("worker_size = {}", quote(layout.worker_size)),
+        "Per worker tier",
+    );
+
+    out.push_str("\n[client.kubernetes.service]\n");
+    line(
+        &mut out,
+        "service_type = \"ClusterIP\"",
+        "ClusterIP | NodePort | LoadBalancer",
+    );
+
+    if layout.rbac {
+        out.push_str("\n[client.kubernetes.rbac]\n");
+        line(
+            &mut out,
+            "create_service_accounts = true",
+            "<cluster-id>-master and <cluster-id>-worker",
+        );
+    }
+    out
+}
+
+/// Commented node selector listing the labels that tell nodes apart
+fn node_selector_hint(out: &mut String, facts: &ClusterFacts) {
+    let Some((key, values)) = facts.node_labels.iter().next() else {
+        out.push_str("# node_selector = { \"node-type\" = \"curvine\" }\n");
+        return;
+    };
+    let value = values.iter().next().map(String::as_str).unwrap_or_default();
+    let _ = writeln!(
+        out,
+        "# node_selector = {{ {} = {} }}",
+        quote(key),
+        quote(value)
+    );
+    out.push_str("# Node labels in the cluster:\n");
+    for (key, values) in &facts.node_labels {
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        let _ = writeln!(out, "#   {} = {}", key, values.join(" | "));
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! JSON Schema of cluster configuration files
+//!
+//! Generated from `ClusterConf` with its field docs, defaults and enums, for
+//! editor validation and completion (taplo, yaml-language-server). Sections
+//! of `[client.kubernetes]` also list the `KubernetesConfig` fields they
+//! accept as overrides (see `paths`).
+
+use crate::domain::config::{ClusterConf, KubernetesConfig, StorageType};
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{
+    ArrayValidation, InstanceType, Metadata, RootSchema, Schema, SchemaObject, StringValidation,
+};
+use schemars::schema_for;
+
+/// `[client.kubernetes]` sections and the `KubernetesConfig` structs whose
+/// remaining fields they accept
+const OVERRIDE_SECTIONS: &[(&str, &str)] = &[
+    ("KubernetesConf", "KubernetesConfig"),
+    ("KubernetesMasterConf", "MasterConfig"),
+    ("KubernetesWorkerConf", "WorkerConfig"),
+    ("KubernetesServiceConf", "ServiceConfig"),
+];
+
+/// Schema of `curvine-cluster.toml` (and its YAML/JSON forms)
+pub fn cluster_conf_schema() -> RootSchema {
+    let mut generator = SchemaGenerator::new(SchemaSettings::draft07());
+    generator.subschema_for::<KubernetesConfig>();
+    let mut root = generator.into_root_schema_for::<ClusterConf>();
+    root.schema.metadata().title = Some("Curvine cluster configuration".to_string());
+
+    for (section, source) in OVERRIDE_SECTIONS {
+        let Some(Schema::Object(source)) = root.definitions.remove(*source) else {
+            continue;
+        };
+        let Some(Schema::Object(section)) = root.definitions.get_mut(*section) else {
+            continue;
+        };
+        let properties = &mut section.object().properties;
+        for (name, schema) in source.object.map(|o| o.properties).unwrap_or_default() {
+            properties.entry(name).or_insert(schema);
+        }
+    }
+    root
+}
+
+/// `worker.data_dir`: `[TYPE:CAPACITY]/path` entries, TYPE being a `StorageType`
+pub fn data_dir(_: &mut SchemaGenerator) -> Schema {
+    let types: Vec<String> = schema_for!(StorageType)
+        .schema
+        .enum_values
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect();
+    let alternatives = types
+        .iter()
+        .flat_map(|t| [t.clone(), t.to_uppercase()])
+        .collect::<Vec<_>>()
+        .join("|");
+    let capacity = r"[0-9.]+\s*[A-Za-z]*";
+
+    let entry = SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(format!(
+                r"^(\[(({})(:{})?|{})\])?[^\[].*$",
+                alternatives, capacity, capacity
+            )),
+            ..Default::default()
+        })),
+        metadata: Some(Box::new(Metadata {
+            description: Some(format!(
+                "`[TYPE:CAPACITY]/path`, `[TYPE]/path`, `[CAPACITY]/path` or `/path`; TYPE is one of {}",
+                types.join(", ")
+            )),
+            examples: vec![serde_json::json!("[SSD:100GB]/data/ssd")],
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(Schema::Object(entry).into()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
@@ -16,6 +16,7 @@
 pub const APP_HOME: &str = "/app";
 pub const CURVINE_HOME: &str = "/app/curvine";
 pub const CURVINE_CONF_FILE: &str = "/app/curvine/conf/curvine-cluster.toml";
+pub const CURVINE_CV_BIN: &str = "/app/curvine/bin/cv";
 
 /// Network configuration
 pub const ORPC_BIND_HOSTNAME: &str = "0.0.0.0";
@@ -31,6 +32,17 @@ pub const MASTER_WEB1_PORT: i32 = 9001;
 pub const WORKER_RPC_PORT: i32 = 8997;
 pub const WORKER_WEB_PORT: i32 = 9001;
 
+/// Number of recent Warning events shown per pod in `status`
+pub const POD_WARNING_EVENTS_LIMIT: usize = 3;
+
+/// `port-forward` reconnect attempts (and seconds between them) while the
+/// target pod is unavailable
+pub const PORT_FORWARD_RETRIES: u32 = 15;
+pub const PORT_FORWARD_RETRY_DELAY: u64 = 2;
+
+/// Log lines collected per container by `diagnose`
+pub const DIAGNOSE_LOG_TAIL_LINES: i64 = 10000;
+
 /// Health check configuration
 pub const LIVENESS_INITIAL_DELAY: i32 = 15;
 pub const LIVENESS_PERIOD: i32 = 300;
@@ -90,6 +102,15 @@ pub const VOLUME_NAME_DATA_DIR_PREFIX: &str = "data-dir-";
 pub const CONFIG_FILE_NAME: &str = "curvine-cluster.toml";
 pub const CONFIG_FILE_MODE: i32 = 0o644;
 
+/// TLS certificates (same key names as `kubernetes.io/tls` and cert-manager)
+pub const VOLUME_NAME_TLS: &str = "curvine-tls";
+pub const TLS_MOUNT_PATH: &str = "/app/curvine/conf/tls";
+pub const TLS_SECRET_SUFFIX: &str = "-tls";
+pub const TLS_CERT_KEY: &str = "tls.crt";
+pub const TLS_PRIVATE_KEY: &str = "tls.key";
+pub const TLS_CA_KEY: &str = "ca.crt";
+pub const TLS_FILE_MODE: i32 = 0o600;
+
 /// Security context
 pub const SECURITY_PRIVILEGED: bool = true;
 
@@ -106,5 +127,12 @@ pub const PORT_NAME_JOURNAL: &str = "journal";
 pub const PORT_NAME_WEB: &str = "web";
 pub const PORT_NAME_WEB1: &str = "web1";
 
-/// Affinity topology key
+/// Affinity topology keys
 pub const TOPOLOGY_KEY_HOSTNAME: &str = "kubernetes.io/hostname";
+pub const TOPOLOGY_KEY_ZONE: &str = "topology.kubernetes.io/zone";
+
+/// Well-known namespace label (set automatically by Kubernetes >= 1.21)
+pub const LABEL_NAMESPACE_NAME: &str = "kubernetes.io/metadata.name";
+
+/// Oldest Kubernetes release (major, minor) `doctor` accepts
+pub const MIN_KUBERNETES_VERSION: (u32, u32) = (1, 23);
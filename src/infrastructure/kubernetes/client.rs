@@ -12,10 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::infrastructure::constants::TLS_SECRET_SUFFIX;
+use crate::infrastructure::kubernetes::resources::tls::certificate_api_resource;
 use crate::shared::error::KubeError;
 use k8s_openapi::api::apps::v1::{Deployment, StatefulSet};
-use k8s_openapi::api::core::v1::{ConfigMap, PersistentVolumeClaim, Pod, Service};
-use kube::{Api, Client};
+use k8s_openapi::api::core::v1::{
+    ConfigMap, Event, PersistentVolumeClaim, Pod, Secret, Service, ServiceAccount,
+};
+use k8s_openapi::api::networking::v1::NetworkPolicy;
+use k8s_openapi::api::rbac::v1::{Role, RoleBinding};
+use kube::api::DynamicObject;
+use kube::core::NamespaceResourceScope;
+use kube::{Api, Client, Resource};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use std::collections::HashMap;
 
 #[async_trait::async_trait]
@@ -36,6 +46,18 @@ pub trait CurvineKubeClient: Send + Sync {
 
     async fn apply_deployment(&self, deployment: &Deployment) -> Result<(), KubeError>;
 
+    async fn apply_network_policy(&self, policy: &NetworkPolicy) -> Result<(), KubeError>;
+
+    async fn apply_service_account(&self, account: &ServiceAccount) -> Result<(), KubeError>;
+
+    async fn apply_role(&self, role: &Role) -> Result<(), KubeError>;
+
+    async fn apply_role_binding(&self, binding: &RoleBinding) -> Result<(), KubeError>;
+
+    async fn apply_secret(&self, secret: &Secret) -> Result<(), KubeError>;
+
+    async fn apply_certificate(&self, certificate: &DynamicObject) -> Result<(), KubeError>;
+
     async fn get_statefulset(&self, name: &str) -> Result<StatefulSet, KubeError>;
 
     async fn get_deployment(&self, name: &str) -> Result<Deployment, KubeError>;
@@ -44,8 +66,27 @@ pub trait CurvineKubeClient: Send + Sync {
 
     async fn get_configmap(&self, name: &str) -> Result<ConfigMap, KubeError>;
 
+    async fn get_secret(&self, name: &str) -> Result<Secret, KubeError>;
+
     async fn list_configmaps(&self, label_selector: &str) -> Result<Vec<ConfigMap>, KubeError>;
 
+    async fn list_configmaps_all_namespaces(
+        &self,
+        label_selector: &str,
+    ) -> Result<Vec<ConfigMap>, KubeError>;
+
+    async fn list_statefulsets(&self, label_selector: &str) -> Result<Vec<StatefulSet>, KubeError>;
+
+    async fn list_statefulsets_all_namespaces(
+        &self,
+        label_selector: &str,
+    ) -> Result<Vec<StatefulSet>, KubeError>;
+
+    async fn get_pvc(&self, name: &str) -> Result<PersistentVolumeClaim, KubeError>;
+
+    /// Warning events whose involved object is the given pod
+    async fn list_pod_warning_events(&self, pod_name: &str) -> Result<Vec<Event>, KubeError>;
+
     fn get_client(&self) -> Client;
 
     async fn get_pods_with_labels(
@@ -61,6 +102,18 @@ pub trait CurvineKubeClient: Send + Sync {
 
     async fn delete_configmap(&self, name: &str) -> Result<(), KubeError>;
 
+    async fn delete_network_policy(&self, name: &str) -> Result<(), KubeError>;
+
+    async fn delete_service_account(&self, name: &str) -> Result<(), KubeError>;
+
+    async fn delete_role(&self, name: &str) -> Result<(), KubeError>;
+
+    async fn delete_role_binding(&self, name: &str) -> Result<(), KubeError>;
+
+    async fn delete_secret(&self, name: &str) -> Result<(), KubeError>;
+
+    async fn delete_certificate(&self, name: &str) -> Result<(), KubeError>;
+
     async fn delete_pod(&self, name: &str) -> Result<(), KubeError>;
 
     async fn delete_pvcs_for_cluster(&self, cluster_id: &str) -> Result<(), KubeError>;
@@ -95,6 +148,29 @@ impl CurvineKubeClientImpl {
         kubeconfig_path: Option<String>,
         context: Option<String>,
     ) -> Result<Self, KubeError> {
+        let config = Self::load_config(kubeconfig_path, context).await?;
+
+        let client = Client::try_from(config).map_err(|e| {
+            KubeError::KubeError(format!("Failed to create Kubernetes client: {}", e))
+        })?;
+
+        Ok(Self { client, namespace })
+    }
+
+    /// Namespace of the selected kubeconfig context (`default` when unset)
+    pub async fn default_namespace(
+        kubeconfig_path: Option<String>,
+        context: Option<String>,
+    ) -> Result<String, KubeError> {
+        Ok(Self::load_config(kubeconfig_path, context)
+            .await?
+            .default_namespace)
+    }
+
+    async fn load_config(
+        kubeconfig_path: Option<String>,
+        context: Option<String>,
+    ) -> Result<kube::Config, KubeError> {
         use kube::config::{KubeConfigOptions, Kubeconfig};
 
         let kubeconfig = if let Some(path) = kubeconfig_path {
@@ -111,17 +187,77 @@ impl CurvineKubeClientImpl {
             user: None,
         };
 
-        let config = kube::Config::from_custom_kubeconfig(kubeconfig, &config_options)
+        kube::Config::from_custom_kubeconfig(kubeconfig, &config_options)
             .await
-            .map_err(|e| {
-                KubeError::KubeError(format!("Failed to create Kubernetes config: {}", e))
-            })?;
+            .map_err(|e| KubeError::KubeError(format!("Failed to create Kubernetes config: {}", e)))
+    }
+}
 
-        let client = Client::try_from(config).map_err(|e| {
-            KubeError::KubeError(format!("Failed to create Kubernetes client: {}", e))
-        })?;
+async fn list_with_labels<K>(api: &Api<K>, label_selector: &str) -> Result<Vec<K>, KubeError>
+where
+    K: Clone + DeserializeOwned + std::fmt::Debug,
+{
+    let list_params = kube::api::ListParams::default().labels(label_selector);
 
-        Ok(Self { client, namespace })
+    api.list(&list_params)
+        .await
+        .map(|list| list.items)
+        .map_err(|e| KubeError::KubeError(e.to_string()))
+}
+
+impl CurvineKubeClientImpl {
+    /// Create or server-side apply a namespaced resource that has no
+    /// immutable fields needing special handling.
+    async fn apply_namespaced<K>(&self, resource: &K) -> Result<(), KubeError>
+    where
+        K: Resource<Scope = NamespaceResourceScope, DynamicType = ()>
+            + Clone
+            + std::fmt::Debug
+            + Serialize
+            + DeserializeOwned,
+    {
+        let kind = K::kind(&());
+        let api: Api<K> = Api::namespaced(self.client.clone(), &self.namespace);
+        let name = resource
+            .meta()
+            .name
+            .as_ref()
+            .ok_or_else(|| KubeError::ConfigError(format!("{} name is required", kind)))?;
+
+        match api.get(name).await {
+            Ok(_) => {
+                let patch_params = kube::api::PatchParams::apply("curvine-cli").force();
+                let patch = serde_json::to_value(resource).map_err(|e| {
+                    KubeError::KubeError(format!("Failed to serialize {}: {}", kind, e))
+                })?;
+                api.patch(name, &patch_params, &kube::api::Patch::Apply(patch))
+                    .await?;
+            }
+            Err(kube::Error::Api(ae)) if ae.code == 404 => {
+                let pp = kube::api::PostParams::default();
+                api.create(&pp, resource).await?;
+            }
+            Err(e) => return Err(KubeError::KubeError(e.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Delete a namespaced resource, treating "not found" as success.
+    async fn delete_namespaced<K>(&self, name: &str) -> Result<(), KubeError>
+    where
+        K: Resource<Scope = NamespaceResourceScope, DynamicType = ()>
+            + Clone
+            + std::fmt::Debug
+            + DeserializeOwned,
+    {
+        let api: Api<K> = Api::namespaced(self.client.clone(), &self.namespace);
+        let dp = kube::api::DeleteParams::default();
+
+        match api.delete(name, &dp).await {
+            Ok(_) => Ok(()),
+            Err(kube::Error::Api(ae)) if ae.code == 404 => Ok(()),
+            Err(e) => Err(KubeError::KubeError(e.to_string())),
+        }
     }
 }
 
@@ -325,6 +461,50 @@ impl CurvineKubeClient for CurvineKubeClientImpl {
         Ok(())
     }
 
+    async fn apply_network_policy(&self, policy: &NetworkPolicy) -> Result<(), KubeError> {
+        self.apply_namespaced(policy).await
+    }
+
+    async fn apply_service_account(&self, account: &ServiceAccount) -> Result<(), KubeError> {
+        self.apply_namespaced(account).await
+    }
+
+    async fn apply_role(&self, role: &Role) -> Result<(), KubeError> {
+        self.apply_namespaced(role).await
+    }
+
+    async fn apply_role_binding(&self, binding: &RoleBinding) -> Result<(), KubeError> {
+        self.apply_namespaced(binding).await
+    }
+
+    async fn apply_secret(&self, secret: &Secret) -> Result<(), KubeError> {
+        self.apply_namespaced(secret).await
+    }
+
+    async fn apply_certificate(&self, certificate: &DynamicObject) -> Result<(), KubeError> {
+        let api: Api<DynamicObject> = Api::namespaced_with(
+            self.client.clone(),
+            &self.namespace,
+            &certificate_api_resource(),
+        );
+        let name =
+            certificate.metadata.name.as_ref().ok_or_else(|| {
+                KubeError::ConfigError("Certificate name is required".to_string())
+            })?;
+
+        let patch_params = kube::api::PatchParams::apply("curvine-cli").force();
+        api.patch(name, &patch_params, &kube::api::Patch::Apply(certificate))
+            .await
+            .map_err(|e| match e {
+                kube::Error::Api(ae) if ae.code == 404 => KubeError::ConfigError(
+                    "cert-manager Certificate CRD not found; is cert-manager installed?"
+                        .to_string(),
+                ),
+                e => KubeError::KubeError(e.to_string()),
+            })?;
+        Ok(())
+    }
+
     async fn get_statefulset(&self, name: &str) -> Result<StatefulSet, KubeError> {
         let api: Api<StatefulSet> = Api::namespaced(self.client.clone(), &self.namespace);
         api.get(name).await.map_err(|e| {
@@ -385,6 +565,17 @@ impl CurvineKubeClient for CurvineKubeClientImpl {
         })
     }
 
+    async fn get_secret(&self, name: &str) -> Result<Secret, KubeError> {
+        let api: Api<Secret> = Api::namespaced(self.client.clone(), &self.namespace);
+        api.get(name).await.map_err(|e| match e {
+            kube::Error::Api(ae) if ae.code == 404 => {
+                KubeError::not_found("Secret", name, &self.namespace)
+            }
+            kube::Error::Api(ae) => KubeError::KubeError(ae.message),
+            e => KubeError::KubeError(e.to_string()),
+        })
+    }
+
     async fn list_configmaps(&self, label_selector: &str) -> Result<Vec<ConfigMap>, KubeError> {
         let api: Api<ConfigMap> = Api::namespaced(self.client.clone(), &self.namespace);
         let list_params = kube::api::ListParams::default().labels(label_selector);
@@ -395,6 +586,51 @@ impl CurvineKubeClient for CurvineKubeClientImpl {
             .map_err(|e| KubeError::KubeError(e.to_string()))
     }
 
+    async fn list_configmaps_all_namespaces(
+        &self,
+        label_selector: &str,
+    ) -> Result<Vec<ConfigMap>, KubeError> {
+        let api: Api<ConfigMap> = Api::all(self.client.clone());
+        list_with_labels(&api, label_selector).await
+    }
+
+    async fn list_statefulsets(&self, label_selector: &str) -> Result<Vec<StatefulSet>, KubeError> {
+        let api: Api<StatefulSet> = Api::namespaced(self.client.clone(), &self.namespace);
+        list_with_labels(&api, label_selector).await
+    }
+
+    async fn list_statefulsets_all_namespaces(
+        &self,
+        label_selector: &str,
+    ) -> Result<Vec<StatefulSet>, KubeError> {
+        let api: Api<StatefulSet> = Api::all(self.client.clone());
+        list_with_labels(&api, label_selector).await
+    }
+
+    async fn get_pvc(&self, name: &str) -> Result<PersistentVolumeClaim, KubeError> {
+        let api: Api<PersistentVolumeClaim> = Api::namespaced(self.client.clone(), &self.namespace);
+        api.get(name).await.map_err(|e| match e {
+            kube::Error::Api(ae) if ae.code == 404 => {
+                KubeError::not_found("PersistentVolumeClaim", name, &self.namespace)
+            }
+            kube::Error::Api(ae) => KubeError::KubeError(ae.message),
+            e => KubeError::KubeError(e.to_string()),
+        })
+    }
+
+    async fn list_pod_warning_events(&self, pod_name: &str) -> Result<Vec<Event>, KubeError> {
+        let api: Api<Event> = Api::namespaced(self.client.clone(), &self.namespace);
+        let list_params = kube::api::ListParams::default().fields(&format!(
+            "involvedObject.kind=Pod,involvedObject.name={},type=Warning",
+            pod_name
+        ));
+
+        api.list(&list_params)
+            .await
+            .map(|list| list.items)
+            .map_err(|e| KubeError::KubeError(e.to_string()))
+    }
+
     fn get_client(&self) -> Client {
         self.client.clone()
     }
@@ -448,6 +684,42 @@ impl CurvineKubeClient for CurvineKubeClientImpl {
         Ok(())
     }
 
+    async fn delete_network_policy(&self, name: &str) -> Result<(), KubeError> {
+        self.delete_namespaced::<NetworkPolicy>(name).await
+    }
+
+    async fn delete_service_account(&self, name: &str) -> Result<(), KubeError> {
+        self.delete_namespaced::<ServiceAccount>(name).await
+    }
+
+    async fn delete_role(&self, name: &str) -> Result<(), KubeError> {
+        self.delete_namespaced::<Role>(name).await
+    }
+
+    async fn delete_role_binding(&self, name: &str) -> Result<(), KubeError> {
+        self.delete_namespaced::<RoleBinding>(name).await
+    }
+
+    async fn delete_secret(&self, name: &str) -> Result<(), KubeError> {
+        self.delete_namespaced::<Secret>(name).await
+    }
+
+    async fn delete_certificate(&self, name: &str) -> Result<(), KubeError> {
+        let api: Api<DynamicObject> = Api::namespaced_with(
+            self.client.clone(),
+            &self.namespace,
+            &certificate_api_resource(),
+        );
+        let dp = kube::api::DeleteParams::default();
+
+        // 404 also covers clusters without the cert-manager CRD
+        match api.delete(name, &dp).await {
+            Ok(_) => Ok(()),
+            Err(kube::Error::Api(ae)) if ae.code == 404 => Ok(()),
+            Err(e) => Err(KubeError::KubeError(e.to_string())),
+        }
+    }
+
     async fn delete_pod(&self, name: &str) -> Result<(), KubeError> {
         let api: Api<Pod> = Api::namespaced(self.client.clone(), &self.namespace);
         let dp = kube::api::DeleteParams::default();
@@ -496,11 +768,38 @@ impl CurvineKubeClient for CurvineKubeClientImpl {
             let _ = self
                 .delete_service(&format!("{}-master-headless", cluster_id))
                 .await;
+            let _ = self
+                .delete_network_policy(&format!("{}-master", cluster_id))
+                .await;
+            let _ = self
+                .delete_network_policy(&format!("{}-worker", cluster_id))
+                .await;
+            let _ = self
+                .delete_role_binding(&format!("{}-pod-discovery", cluster_id))
+                .await;
+            let _ = self
+                .delete_role(&format!("{}-pod-discovery", cluster_id))
+                .await;
+            let _ = self
+                .delete_service_account(&format!("{}-master", cluster_id))
+                .await;
+            let _ = self
+                .delete_service_account(&format!("{}-worker", cluster_id))
+                .await;
+            let _ = self.delete_secret(&format!("{}-secrets", cluster_id)).await;
+            let _ = self
+                .delete_certificate(&format!("{}{}", cluster_id, TLS_SECRET_SUFFIX))
+                .await;
             let _ = self.delete_configmap(&configmap_name).await;
         } else {
             self.delete_configmap(&configmap_name).await?;
         }
 
+        // The cert-manager Secret carries no owner reference
+        let _ = self
+            .delete_secret(&format!("{}{}", cluster_id, TLS_SECRET_SUFFIX))
+            .await;
+
         if delete_pvcs {
             let _ = self.delete_pvcs_for_cluster(cluster_id).await;
         }
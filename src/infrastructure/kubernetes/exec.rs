@@ -0,0 +1,241 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pod exec through kube's WebSocket support (`Api::<Pod>::exec`)
+
+use crate::shared::error::KubeError;
+use k8s_openapi::api::core::v1::Pod;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Status;
+use kube::api::{AttachParams, TerminalSize};
+use kube::{Api, Client};
+use std::io::IsTerminal;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Command to run in a pod container
+#[derive(Debug, Clone)]
+pub struct ExecRequest {
+    pub pod: String,
+    pub container: Option<String>,
+    pub command: Vec<String>,
+    /// Forward local stdin; implied by `tty`
+    pub stdin: bool,
+    /// Allocate a TTY (stderr is then merged into stdout)
+    pub tty: bool,
+}
+
+impl ExecRequest {
+    pub fn new(pod: String, container: Option<String>, command: Vec<String>) -> Self {
+        Self {
+            pod,
+            container,
+            command,
+            stdin: false,
+            tty: false,
+        }
+    }
+
+    /// Forward local stdin to the command (`-i`)
+    pub fn with_stdin(mut self, stdin: bool) -> Self {
+        self.stdin = stdin;
+        self
+    }
+
+    /// Attach a TTY when both stdin and stdout are terminals
+    pub fn with_terminal_tty(mut self) -> Self {
+        self.tty = std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+        self
+    }
+
+    /// Attach parameters; stdin is only attached with a TTY or `-i`, like
+    /// `kubectl exec`, so commands that read it do not wait for input
+    pub fn attach_params(&self) -> AttachParams {
+        let params = AttachParams::default()
+            .stdin(self.stdin || self.tty)
+            .stdout(true)
+            .stderr(!self.tty)
+            .tty(self.tty);
+        match &self.container {
+            Some(container) => params.container(container.clone()),
+            None => params,
+        }
+    }
+}
+
+/// Run the command with local stdio attached and return its exit code.
+pub async fn exec_attached(
+    client: &Client,
+    namespace: &str,
+    request: &ExecRequest,
+) -> Result<i32, KubeError> {
+    let pods: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    let mut process = pods
+        .exec(
+            &request.pod,
+            request.command.clone(),
+            &request.attach_params(),
+        )
+        .await
+        .map_err(|e| KubeError::KubeError(format!("Failed to exec in {}: {}", request.pod, e)))?;
+
+    let _raw_mode = if request.tty {
+        RawModeGuard::enable()
+    } else {
+        None
+    };
+
+    // The v4 exec protocol has no way to close stdin alone: dropping the
+    // writer at EOF closes the stream, which ends the command's stdin
+    let stdin = process.stdin().map(|mut stdin| {
+        tokio::spawn(async move {
+            let _ = pump(&mut tokio::io::stdin(), &mut stdin).await;
+            let _ = stdin.shutdown().await;
+        })
+    });
+    let mut background = Vec::new();
+    if let Some(sizes) = process.terminal_size() {
+        background.push(tokio::spawn(forward_resizes(sizes)));
+    }
+
+    let mut output = Vec::new();
+    if let Some(mut stdout) = process.stdout() {
+        output.push(tokio::spawn(async move {
+            pump(&mut stdout, &mut tokio::io::stdout()).await
+        }));
+    }
+    if let Some(mut stderr) = process.stderr() {
+        output.push(tokio::spawn(async move {
+            pump(&mut stderr, &mut tokio::io::stderr()).await
+        }));
+    }
+
+    let status = match process.take_status() {
+        Some(status) => status.await,
+        None => None,
+    };
+    for task in output {
+        if let Ok(result) = task.await {
+            result?;
+        }
+    }
+    let stdin_closed = stdin.as_ref().is_some_and(|task| task.is_finished());
+    for task in background.into_iter().chain(stdin) {
+        task.abort();
+    }
+    process
+        .join()
+        .await
+        .map_err(|e| KubeError::KubeError(format!("Exec in {} failed: {}", request.pod, e)))?;
+
+    match status {
+        Some(status) => exit_code_from_status(&status).map_err(KubeError::KubeError),
+        None if stdin_closed => Err(KubeError::KubeError(
+            "Exec stream closed at the end of stdin before the command reported a status"
+                .to_string(),
+        )),
+        None => Err(KubeError::KubeError(
+            "Exec stream closed without reporting a status".to_string(),
+        )),
+    }
+}
+
+/// Copy until EOF, flushing after every chunk so prompts show up immediately
+async fn pump<R, W>(reader: &mut R, writer: &mut W) -> std::io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut chunk = vec![0u8; 16 * 1024];
+    loop {
+        let read = reader.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        writer.write_all(&chunk[..read]).await?;
+        writer.flush().await?;
+    }
+}
+
+async fn forward_resizes(sizes: futures::channel::mpsc::Sender<TerminalSize>) {
+    use futures::SinkExt;
+
+    let mut sizes = sizes;
+    if let Some(size) = terminal_size() {
+        if sizes.send(size).await.is_err() {
+            return;
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let Ok(mut resized) = signal(SignalKind::window_change()) else {
+            return;
+        };
+        while resized.recv().await.is_some() {
+            if let Some(size) = terminal_size() {
+                if sizes.send(size).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn terminal_size() -> Option<TerminalSize> {
+    crossterm::terminal::size()
+        .ok()
+        .filter(|(width, _)| *width > 0)
+        .map(|(width, height)| TerminalSize { width, height })
+}
+
+/// Puts the local terminal in raw mode and restores it on drop
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn enable() -> Option<Self> {
+        crossterm::terminal::enable_raw_mode().ok().map(|_| Self)
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+/// Exit code from the `Status` the API server reports when the command ends
+pub fn exit_code_from_status(status: &Status) -> Result<i32, String> {
+    if status.status.as_deref() == Some("Success") {
+        return Ok(0);
+    }
+
+    let exit_code = status
+        .details
+        .as_ref()
+        .and_then(|details| details.causes.as_ref())
+        .into_iter()
+        .flatten()
+        .find(|cause| cause.reason.as_deref() == Some("ExitCode"))
+        .and_then(|cause| cause.message.as_deref())
+        .and_then(|code| code.parse::<i32>().ok());
+
+    match exit_code {
+        Some(code) => Ok(code),
+        None => Err(status
+            .message
+            .clone()
+            .unwrap_or_else(|| "exec failed".to_string())),
+    }
+}
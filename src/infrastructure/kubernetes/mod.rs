@@ -15,6 +15,8 @@
 //! Kubernetes infrastructure
 
 pub mod client;
+pub mod exec;
+pub mod portforward;
 pub mod resources;
 
 pub use client::{CurvineKubeClient, CurvineKubeClientImpl};
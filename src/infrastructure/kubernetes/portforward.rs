@@ -0,0 +1,142 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pod port forwarding through kube's WebSocket support (`Api::<Pod>::portforward`)
+//!
+//! Every local connection gets its own `Portforwarder` carrying a single port.
+
+use crate::shared::error::KubeError;
+use k8s_openapi::api::core::v1::{ContainerPort, Pod};
+use kube::api::Portforwarder;
+use kube::{Api, Client};
+use tokio::net::TcpStream;
+
+/// A named pod port forwarded to a local port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortMapping {
+    pub name: String,
+    pub local: u16,
+    pub remote: u16,
+}
+
+/// Resolve `NAME[:LOCAL_PORT]` entries against the named ports of `container`.
+///
+/// The local port defaults to the container port.
+pub fn resolve_port_mappings(
+    pod: &Pod,
+    container: &str,
+    specs: &[String],
+) -> Result<Vec<PortMapping>, KubeError> {
+    let ports: Vec<&ContainerPort> = pod
+        .spec
+        .as_ref()
+        .into_iter()
+        .flat_map(|s| s.containers.iter())
+        .filter(|c| c.name == container)
+        .flat_map(|c| c.ports.iter().flatten())
+        .collect();
+    let known = || {
+        ports
+            .iter()
+            .filter_map(|p| p.name.as_deref())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    specs
+        .iter()
+        .map(|spec| {
+            let (name, local) = match spec.split_once(':') {
+                Some((name, local)) => (name, Some(local)),
+                None => (spec.as_str(), None),
+            };
+            let port = ports
+                .iter()
+                .find(|p| p.name.as_deref() == Some(name))
+                .ok_or_else(|| {
+                    KubeError::ValidationError(format!(
+                        "Container '{}' has no port named '{}' (available: {})",
+                        container,
+                        name,
+                        known()
+                    ))
+                })?;
+            let remote = u16::try_from(port.container_port).map_err(|_| {
+                KubeError::InvalidResource(format!(
+                    "Invalid container port {}",
+                    port.container_port
+                ))
+            })?;
+            let local = match local {
+                Some(local) => local.parse::<u16>().map_err(|_| {
+                    KubeError::ValidationError(format!("Invalid local port in '{}'", spec))
+                })?,
+                None => remote,
+            };
+
+            Ok(PortMapping {
+                name: name.to_string(),
+                local,
+                remote,
+            })
+        })
+        .collect()
+}
+
+/// Open the WebSocket carrying `port` of `pod`
+pub async fn connect(
+    client: &Client,
+    namespace: &str,
+    pod: &str,
+    port: u16,
+) -> Result<Portforwarder, KubeError> {
+    let pods: Api<Pod> = Api::namespaced(client.clone(), namespace);
+    pods.portforward(pod, &[port])
+        .await
+        .map_err(|e| KubeError::KubeError(format!("Failed to connect to {}: {}", pod, e)))
+}
+
+/// Copy data between an accepted local connection and a connected port
+/// until either side closes it.
+pub async fn forward(
+    mut forwarder: Portforwarder,
+    mut local: TcpStream,
+    pod: &str,
+    port: u16,
+) -> Result<(), KubeError> {
+    let mut upstream = forwarder.take_stream(port).ok_or_else(|| {
+        KubeError::KubeError(format!("Port {} of {} is not forwarded", port, pod))
+    })?;
+    let error = forwarder.take_error(port);
+
+    let copied = tokio::io::copy_bidirectional(&mut local, &mut upstream).await;
+    drop(upstream);
+    let joined = forwarder.join().await;
+
+    if let Some(error) = error {
+        if let Some(message) = error.await {
+            return Err(KubeError::KubeError(format!(
+                "Port forward to {}:{} failed: {}",
+                pod,
+                port,
+                message.trim()
+            )));
+        }
+    }
+    joined.map_err(|e| {
+        KubeError::KubeError(format!("Port forward to {}:{} failed: {}", pod, port, e))
+    })?;
+    copied?;
+    Ok(())
+}
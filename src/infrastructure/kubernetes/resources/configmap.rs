@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::domain::config::kubernetes::KubernetesConfigBuilder;
+use crate::domain::config::kubernetes::{KubernetesConfigBuilder, TlsConfig};
 use crate::domain::config::ClusterConf;
+use crate::infrastructure::kubernetes::resources::secret::{merge_secret_keys, split_secret_keys};
 use crate::shared::error::KubeError;
 use k8s_openapi::api::core::v1::ConfigMap;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
@@ -24,6 +25,9 @@ pub struct ConfigMapBuilder {
     cluster_id: String,
     namespace: String,
     master_replicas: u32,
+    cluster_domain: String,
+    secret_keys: Vec<String>,
+    tls: Option<TlsConfig>,
 }
 
 impl ConfigMapBuilder {
@@ -38,11 +42,55 @@ impl ConfigMapBuilder {
             cluster_id,
             namespace,
             master_replicas,
+            cluster_domain: "cluster.local".to_string(),
+            secret_keys: Vec::new(),
+            tls: None,
         }
     }
 
+    /// DNS domain of the Kubernetes cluster, used for the master addresses
+    /// and Raft peers in the rendered config
+    pub fn with_cluster_domain(mut self, cluster_domain: String) -> Self {
+        self.cluster_domain = cluster_domain;
+        self
+    }
+
+    /// Keep these dotted config keys out of the rendered ConfigMap (see
+    /// `build_pod_config` for the config the pods mount)
+    pub fn with_secret_keys(mut self, keys: Vec<String>) -> Self {
+        self.secret_keys = keys;
+        self
+    }
+
+    /// Point the rendered config at the mounted TLS certificates
+    pub fn with_tls(mut self, tls: Option<TlsConfig>) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Values of the secret keys present in the cluster config.
+    ///
+    /// Every configured key must be present, as pods reference each one.
+    pub fn secret_values(&self) -> Result<BTreeMap<String, String>, KubeError> {
+        let (_, values) = split_secret_keys(&self.build_cluster_side_config()?, &self.secret_keys)?;
+        if let Some(missing) = self.secret_keys.iter().find(|k| !values.contains_key(*k)) {
+            return Err(KubeError::ConfigError(format!(
+                "Secret key '{}' not found in cluster config",
+                missing
+            )));
+        }
+        Ok(values)
+    }
+
+    /// Complete config mounted by the pods when secret keys are configured:
+    /// the rendered config with `values` set at their dotted keys
+    pub fn build_pod_config(&self, values: &BTreeMap<String, String>) -> Result<String, KubeError> {
+        merge_secret_keys(&self.build_cluster_side_config()?, values)
+    }
+
     pub fn build(&self) -> Result<ConfigMap, KubeError> {
-        let cluster_side_config = self.build_cluster_side_config()?;
+        let (cluster_side_config, _) =
+            split_secret_keys(&self.build_cluster_side_config()?, &self.secret_keys)?;
 
         let metadata = ObjectMeta {
             name: Some(format!("{}-config", self.cluster_id)),
@@ -86,6 +134,7 @@ impl ConfigMapBuilder {
                 env_vars: HashMap::new(),
                 dns_policy: None,
                 priority_class: None,
+                placement: None,
             },
             worker: WorkerConfig {
                 replicas: 1,
@@ -106,6 +155,7 @@ impl ConfigMapBuilder {
                 env_vars: HashMap::new(),
                 dns_policy: None,
                 priority_class: None,
+                placement: None,
             },
             service: ServiceConfig {
                 service_type: ServiceType::ClusterIP,
@@ -117,7 +167,13 @@ impl ConfigMapBuilder {
             storage: None,
             image_pull_policy: "IfNotPresent".to_string(),
             image_pull_secrets: Vec::new(),
-            cluster_domain: "cluster.local".to_string(),
+            cluster_domain: self.cluster_domain.clone(),
+            network_policy: None,
+            rbac: None,
+            secrets: None,
+            tls: self.tls.clone(),
+            curvine_version: None,
+            interpolate_pod_templates: false,
         };
 
         let config_builder = KubernetesConfigBuilder::new(self.cluster_conf.clone(), kube_config);
@@ -16,11 +16,21 @@
 
 pub mod configmap;
 pub mod headless_service;
+pub mod network_policy;
 pub mod pod;
+pub mod rbac;
+pub mod secret;
 pub mod service;
 pub mod statefulset;
+pub mod tls;
 
 pub use configmap::ConfigMapBuilder;
 pub use headless_service::HeadlessServiceBuilder;
+pub use network_policy::NetworkPolicyBuilder;
+pub use rbac::{build_cli_cluster_role, RbacBuilder};
+pub use secret::{
+    config_secret_volume, merge_secret_keys, secret_key_values, split_secret_keys, SecretBuilder,
+};
 pub use service::ServiceBuilder;
 pub use statefulset::{MasterBuilder, WorkerBuilder};
+pub use tls::TlsBuilder;
@@ -0,0 +1,238 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::config::kubernetes::NetworkPolicyConfig;
+use crate::infrastructure::constants::*;
+use crate::shared::error::KubeError;
+use k8s_openapi::api::networking::v1::{
+    NetworkPolicy, NetworkPolicyIngressRule, NetworkPolicyPeer, NetworkPolicyPort,
+    NetworkPolicySpec,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, ObjectMeta};
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use std::collections::BTreeMap;
+
+/// Builds ingress-only NetworkPolicies for master and worker pods.
+///
+/// Any traffic not matched by a rule below is denied once a policy selects
+/// the pod:
+/// - master journal port: other master pods only
+/// - master RPC port: workers, masters and configured clients
+/// - worker RPC port: masters, workers and configured clients
+/// - web ports: the configured monitoring namespace only
+pub struct NetworkPolicyBuilder {
+    cluster_id: String,
+    namespace: String,
+    config: NetworkPolicyConfig,
+}
+
+impl NetworkPolicyBuilder {
+    pub fn new(cluster_id: String, namespace: String, config: NetworkPolicyConfig) -> Self {
+        Self {
+            cluster_id,
+            namespace,
+            config,
+        }
+    }
+
+    pub fn build(&self) -> Result<Vec<NetworkPolicy>, KubeError> {
+        self.build_with_owner(None)
+    }
+
+    pub fn build_with_owner(
+        &self,
+        owner_uid: Option<String>,
+    ) -> Result<Vec<NetworkPolicy>, KubeError> {
+        Ok(vec![
+            self.build_master_policy(owner_uid.clone()),
+            self.build_worker_policy(owner_uid),
+        ])
+    }
+
+    fn build_master_policy(&self, owner_uid: Option<String>) -> NetworkPolicy {
+        let mut rpc_peers = vec![
+            self.component_peer(COMPONENT_MASTER),
+            self.component_peer(COMPONENT_WORKER),
+        ];
+        rpc_peers.extend(self.client_peers());
+
+        let mut ingress = vec![
+            NetworkPolicyIngressRule {
+                from: Some(vec![self.component_peer(COMPONENT_MASTER)]),
+                ports: Some(vec![Self::tcp_port(MASTER_JOURNAL_PORT)]),
+            },
+            NetworkPolicyIngressRule {
+                from: Some(rpc_peers),
+                ports: Some(vec![Self::tcp_port(MASTER_RPC_PORT)]),
+            },
+        ];
+        if let Some(rule) = self.monitoring_rule(&[MASTER_WEB_PORT, MASTER_WEB1_PORT]) {
+            ingress.push(rule);
+        }
+
+        self.build_policy(COMPONENT_MASTER, ingress, owner_uid)
+    }
+
+    fn build_worker_policy(&self, owner_uid: Option<String>) -> NetworkPolicy {
+        let mut rpc_peers = vec![
+            self.component_peer(COMPONENT_MASTER),
+            self.component_peer(COMPONENT_WORKER),
+        ];
+        rpc_peers.extend(self.client_peers());
+
+        let mut ingress = vec![NetworkPolicyIngressRule {
+            from: Some(rpc_peers),
+            ports: Some(vec![Self::tcp_port(WORKER_RPC_PORT)]),
+        }];
+        if let Some(rule) = self.monitoring_rule(&[WORKER_WEB_PORT]) {
+            ingress.push(rule);
+        }
+
+        self.build_policy(COMPONENT_WORKER, ingress, owner_uid)
+    }
+
+    fn build_policy(
+        &self,
+        component: &str,
+        ingress: Vec<NetworkPolicyIngressRule>,
+        owner_uid: Option<String>,
+    ) -> NetworkPolicy {
+        let mut metadata = ObjectMeta {
+            name: Some(format!("{}-{}", self.cluster_id, component)),
+            namespace: Some(self.namespace.clone()),
+            labels: Some(self.get_labels(component)),
+            ..Default::default()
+        };
+
+        if let Some(uid) = owner_uid {
+            metadata.owner_references = Some(vec![
+                k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference {
+                    api_version: "v1".to_string(),
+                    kind: "ConfigMap".to_string(),
+                    name: format!("{}-config", self.cluster_id),
+                    uid,
+                    controller: Some(true),
+                    block_owner_deletion: Some(true),
+                },
+            ]);
+        }
+
+        NetworkPolicy {
+            metadata,
+            spec: Some(NetworkPolicySpec {
+                pod_selector: LabelSelector {
+                    match_labels: Some(self.selector_labels(component)),
+                    ..Default::default()
+                },
+                policy_types: Some(vec!["Ingress".to_string()]),
+                ingress: Some(ingress),
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Peer matching pods of one component of this cluster (same namespace)
+    fn component_peer(&self, component: &str) -> NetworkPolicyPeer {
+        NetworkPolicyPeer {
+            pod_selector: Some(LabelSelector {
+                match_labels: Some(self.selector_labels(component)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Peers for external clients.
+    ///
+    /// Each namespace in `client_namespaces` becomes one peer, narrowed to
+    /// `client_labels` when set. Labels without namespaces match pods in the
+    /// cluster's own namespace.
+    fn client_peers(&self) -> Vec<NetworkPolicyPeer> {
+        let pod_selector = if self.config.client_labels.is_empty() {
+            None
+        } else {
+            Some(LabelSelector {
+                match_labels: Some(
+                    self.config
+                        .client_labels
+                        .iter()
+                        .map(|(k, v)| (k.clone(), v.clone()))
+                        .collect(),
+                ),
+                ..Default::default()
+            })
+        };
+
+        if self.config.client_namespaces.is_empty() {
+            return pod_selector
+                .map(|selector| NetworkPolicyPeer {
+                    pod_selector: Some(selector),
+                    ..Default::default()
+                })
+                .into_iter()
+                .collect();
+        }
+
+        self.config
+            .client_namespaces
+            .iter()
+            .map(|ns| NetworkPolicyPeer {
+                namespace_selector: Some(Self::namespace_selector(ns)),
+                pod_selector: pod_selector.clone(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn monitoring_rule(&self, ports: &[i32]) -> Option<NetworkPolicyIngressRule> {
+        let namespace = self.config.monitoring_namespace.as_ref()?;
+        Some(NetworkPolicyIngressRule {
+            from: Some(vec![NetworkPolicyPeer {
+                namespace_selector: Some(Self::namespace_selector(namespace)),
+                ..Default::default()
+            }]),
+            ports: Some(ports.iter().map(|p| Self::tcp_port(*p)).collect()),
+        })
+    }
+
+    fn namespace_selector(namespace: &str) -> LabelSelector {
+        let mut labels = BTreeMap::new();
+        labels.insert(LABEL_NAMESPACE_NAME.to_string(), namespace.to_string());
+        LabelSelector {
+            match_labels: Some(labels),
+            ..Default::default()
+        }
+    }
+
+    fn tcp_port(port: i32) -> NetworkPolicyPort {
+        NetworkPolicyPort {
+            port: Some(IntOrString::Int(port)),
+            protocol: Some("TCP".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn selector_labels(&self, component: &str) -> BTreeMap<String, String> {
+        let mut labels = BTreeMap::new();
+        labels.insert(LABEL_APP.to_string(), self.cluster_id.clone());
+        labels.insert(LABEL_COMPONENT.to_string(), component.to_string());
+        labels
+    }
+
+    pub fn get_labels(&self, component: &str) -> BTreeMap<String, String> {
+        let mut labels = self.selector_labels(component);
+        labels.insert(LABEL_TYPE.to_string(), LABEL_TYPE_VALUE.to_string());
+        labels
+    }
+}
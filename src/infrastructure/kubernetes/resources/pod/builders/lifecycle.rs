@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::infrastructure::constants::CURVINE_CV_BIN;
 use k8s_openapi::api::core::v1::{ExecAction, Lifecycle, LifecycleHandler};
 
 pub struct LifecycleBuilder;
@@ -27,8 +28,8 @@ impl LifecycleBuilder {
         }
 
         let stop_command = format!(
-            "sleep {} && /app/curvine/bin/cv {} stop || true",
-            shutdown_delay, component
+            "sleep {} && {} {} stop || true",
+            shutdown_delay, CURVINE_CV_BIN, component
         );
 
         Some(Lifecycle {
@@ -16,8 +16,10 @@
 
 pub mod env;
 pub mod lifecycle;
+pub mod placement;
 pub mod traits;
 
 pub use env::EnvironmentBuilder;
 pub use lifecycle::LifecycleBuilder;
+pub use placement::PlacementBuilder;
 pub use traits::PodBuilder;
@@ -0,0 +1,137 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pod placement builder
+//!
+//! Turns a component's `PlacementConfig` into pod anti-affinity and
+//! topology spread constraints selecting pods of the same component.
+
+use crate::domain::config::kubernetes::{AntiAffinityMode, PlacementConfig};
+use crate::infrastructure::constants::{LABEL_APP, LABEL_COMPONENT};
+use k8s_openapi::api::core::v1::{
+    Affinity, PodAffinityTerm, PodAntiAffinity, TopologySpreadConstraint, WeightedPodAffinityTerm,
+};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::LabelSelector;
+use std::collections::BTreeMap;
+
+pub struct PlacementBuilder<'a> {
+    cluster_id: &'a str,
+    component: &'static str,
+    placement: Option<&'a PlacementConfig>,
+}
+
+impl<'a> PlacementBuilder<'a> {
+    pub fn new(
+        cluster_id: &'a str,
+        component: &'static str,
+        placement: Option<&'a PlacementConfig>,
+    ) -> Self {
+        Self {
+            cluster_id,
+            component,
+            placement,
+        }
+    }
+
+    pub fn build_affinity(&self) -> Option<Affinity> {
+        let placement = self.placement?;
+
+        let term = PodAffinityTerm {
+            label_selector: Some(self.label_selector()),
+            topology_key: placement.anti_affinity_topology_key.clone(),
+            ..Default::default()
+        };
+
+        let anti_affinity = match placement.anti_affinity {
+            AntiAffinityMode::None => return None,
+            AntiAffinityMode::Preferred => PodAntiAffinity {
+                preferred_during_scheduling_ignored_during_execution: Some(vec![
+                    WeightedPodAffinityTerm {
+                        weight: 100,
+                        pod_affinity_term: term,
+                    },
+                ]),
+                ..Default::default()
+            },
+            AntiAffinityMode::Required => PodAntiAffinity {
+                required_during_scheduling_ignored_during_execution: Some(vec![term]),
+                ..Default::default()
+            },
+        };
+
+        Some(Affinity {
+            pod_anti_affinity: Some(anti_affinity),
+            ..Default::default()
+        })
+    }
+
+    /// `affinity` set explicitly in the config, with the placement's
+    /// anti-affinity terms appended to its own
+    pub fn merge_affinity(&self, affinity: Option<Affinity>) -> Option<Affinity> {
+        let Some(mut affinity) = affinity else {
+            return self.build_affinity();
+        };
+        let Some(placement) = self.build_affinity().and_then(|a| a.pod_anti_affinity) else {
+            return Some(affinity);
+        };
+
+        let anti_affinity = affinity
+            .pod_anti_affinity
+            .get_or_insert_with(Default::default);
+        if let Some(terms) = placement.preferred_during_scheduling_ignored_during_execution {
+            anti_affinity
+                .preferred_during_scheduling_ignored_during_execution
+                .get_or_insert_with(Vec::new)
+                .extend(terms);
+        }
+        if let Some(terms) = placement.required_during_scheduling_ignored_during_execution {
+            anti_affinity
+                .required_during_scheduling_ignored_during_execution
+                .get_or_insert_with(Vec::new)
+                .extend(terms);
+        }
+        Some(affinity)
+    }
+
+    pub fn build_topology_spread_constraints(&self) -> Option<Vec<TopologySpreadConstraint>> {
+        let placement = self.placement?;
+        if placement.topology_spread.is_empty() {
+            return None;
+        }
+
+        Some(
+            placement
+                .topology_spread
+                .iter()
+                .map(|spread| TopologySpreadConstraint {
+                    label_selector: Some(self.label_selector()),
+                    topology_key: spread.topology_key.clone(),
+                    max_skew: spread.max_skew,
+                    when_unsatisfiable: spread.when_unsatisfiable.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    fn label_selector(&self) -> LabelSelector {
+        let mut labels = BTreeMap::new();
+        labels.insert(LABEL_APP.to_string(), self.cluster_id.to_string());
+        labels.insert(LABEL_COMPONENT.to_string(), self.component.to_string());
+        LabelSelector {
+            match_labels: Some(labels),
+            ..Default::default()
+        }
+    }
+}
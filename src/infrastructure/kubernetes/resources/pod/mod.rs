@@ -19,7 +19,7 @@ pub mod merger;
 pub mod template;
 pub mod template_utils;
 
-pub use self::builders::{EnvironmentBuilder, LifecycleBuilder, PodBuilder};
+pub use self::builders::{EnvironmentBuilder, LifecycleBuilder, PlacementBuilder, PodBuilder};
 pub use self::merger::merge_pod_with_template;
 pub use self::template::CurvinePod;
 pub use self::template_utils::load_pod_from_template_file;
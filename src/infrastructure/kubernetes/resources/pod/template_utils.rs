@@ -14,12 +14,17 @@
 
 use crate::infrastructure::kubernetes::resources::pod::template::CurvinePod;
 use crate::shared::error::KubeError;
+use crate::shared::interpolate::{interpolate, Syntax};
 use k8s_openapi::api::core::v1::Pod;
 use std::path::PathBuf;
 
+/// Load a pod template; `${...}` references are expanded only when
+/// `interpolate_references` is set, since shell variables such as
+/// `${HOSTNAME}` in container commands look the same
 pub fn load_pod_from_template_file(
     file_path: &str,
     main_container_name: &str,
+    interpolate_references: bool,
 ) -> Result<CurvinePod, KubeError> {
     let path = resolve_pod_template_path(file_path)?;
 
@@ -38,6 +43,18 @@ pub fn load_pod_from_template_file(
         ))
     })?;
 
+    let content = if interpolate_references {
+        interpolate(&content, Syntax::Yaml).map_err(|e| {
+            KubeError::ConfigError(format!(
+                "Failed to interpolate pod template file {}: {}",
+                path.display(),
+                e
+            ))
+        })?
+    } else {
+        content
+    };
+
     let pod: Pod = serde_yaml::from_str(&content).map_err(|e| {
         KubeError::ConfigError(format!(
             "Failed to parse pod template file {}: {}",
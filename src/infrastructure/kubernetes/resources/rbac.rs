@@ -0,0 +1,228 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::config::kubernetes::KubernetesConfig;
+use crate::infrastructure::constants::*;
+use crate::shared::error::KubeError;
+use k8s_openapi::api::core::v1::ServiceAccount;
+use k8s_openapi::api::rbac::v1::{ClusterRole, PolicyRule, Role, RoleBinding, RoleRef, Subject};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+use std::collections::BTreeMap;
+
+const RBAC_API_GROUP: &str = "rbac.authorization.k8s.io";
+const POD_DISCOVERY_SUFFIX: &str = "-pod-discovery";
+const DEFAULT_SERVICE_ACCOUNT: &str = "default";
+
+/// Builds the per-cluster ServiceAccounts and the pod-discovery Role/RoleBinding.
+pub struct RbacBuilder {
+    cluster_id: String,
+    namespace: String,
+    config: KubernetesConfig,
+}
+
+impl RbacBuilder {
+    pub fn new(cluster_id: String, namespace: String, config: KubernetesConfig) -> Self {
+        Self {
+            cluster_id,
+            namespace,
+            config,
+        }
+    }
+
+    /// ServiceAccounts to create; empty unless `create_service_accounts` is set.
+    /// Components with an explicit `service_account` keep using that one.
+    pub fn build_service_accounts(
+        &self,
+        owner_uid: Option<String>,
+    ) -> Result<Vec<ServiceAccount>, KubeError> {
+        if !self
+            .config
+            .rbac
+            .as_ref()
+            .is_some_and(|r| r.create_service_accounts)
+        {
+            return Ok(Vec::new());
+        }
+
+        let mut accounts = Vec::new();
+        if self.config.master.service_account.is_none() {
+            accounts.push(self.build_service_account(COMPONENT_MASTER, owner_uid.clone()));
+        }
+        if self.config.worker.service_account.is_none() {
+            accounts.push(self.build_service_account(COMPONENT_WORKER, owner_uid));
+        }
+        Ok(accounts)
+    }
+
+    /// Generated ServiceAccounts that `build_service_accounts` no longer
+    /// produces and no component uses; an update removes them.
+    pub fn stale_service_accounts(&self) -> Result<Vec<String>, KubeError> {
+        let built: Vec<String> = self
+            .build_service_accounts(None)?
+            .into_iter()
+            .filter_map(|account| account.metadata.name)
+            .collect();
+        let in_use = [
+            self.config.master.service_account.as_deref(),
+            self.config.worker.service_account.as_deref(),
+        ];
+
+        Ok([COMPONENT_MASTER, COMPONENT_WORKER]
+            .iter()
+            .map(|component| format!("{}-{}", self.cluster_id, component))
+            .filter(|name| !built.contains(name) && !in_use.contains(&Some(name.as_str())))
+            .collect())
+    }
+
+    /// Name of the pod-discovery Role and RoleBinding when they are no longer
+    /// built; an update removes them.
+    pub fn stale_pod_discovery(&self) -> Result<Option<String>, KubeError> {
+        Ok(self
+            .build_pod_discovery(None)?
+            .is_none()
+            .then(|| format!("{}{}", self.cluster_id, POD_DISCOVERY_SUFFIX)))
+    }
+
+    fn build_service_account(&self, component: &str, owner_uid: Option<String>) -> ServiceAccount {
+        ServiceAccount {
+            metadata: self.metadata(
+                format!("{}-{}", self.cluster_id, component),
+                component,
+                owner_uid,
+            ),
+            ..Default::default()
+        }
+    }
+
+    /// Role and RoleBinding for pod self-discovery, if enabled.
+    pub fn build_pod_discovery(
+        &self,
+        owner_uid: Option<String>,
+    ) -> Result<Option<(Role, RoleBinding)>, KubeError> {
+        if !self.config.rbac.as_ref().is_some_and(|r| r.pod_discovery) {
+            return Ok(None);
+        }
+
+        let name = format!("{}{}", self.cluster_id, POD_DISCOVERY_SUFFIX);
+
+        let role = Role {
+            metadata: self.metadata(name.clone(), "rbac", owner_uid.clone()),
+            rules: Some(vec![
+                policy_rule("", &["pods", "endpoints"], &["get", "list", "watch"]),
+                policy_rule("", &["services"], &["get"]),
+            ]),
+        };
+
+        let subjects = [
+            self.config.master_service_account(),
+            self.config.worker_service_account(),
+        ]
+        .into_iter()
+        .map(|sa| sa.unwrap_or_else(|| DEFAULT_SERVICE_ACCOUNT.to_string()))
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .map(|sa| Subject {
+            kind: "ServiceAccount".to_string(),
+            name: sa,
+            namespace: Some(self.namespace.clone()),
+            ..Default::default()
+        })
+        .collect();
+
+        let binding = RoleBinding {
+            metadata: self.metadata(name.clone(), "rbac", owner_uid),
+            role_ref: RoleRef {
+                api_group: RBAC_API_GROUP.to_string(),
+                kind: "Role".to_string(),
+                name,
+            },
+            subjects: Some(subjects),
+        };
+
+        Ok(Some((role, binding)))
+    }
+
+    fn metadata(&self, name: String, component: &str, owner_uid: Option<String>) -> ObjectMeta {
+        let mut labels = BTreeMap::new();
+        labels.insert(LABEL_APP.to_string(), self.cluster_id.clone());
+        labels.insert(LABEL_COMPONENT.to_string(), component.to_string());
+        labels.insert(LABEL_TYPE.to_string(), LABEL_TYPE_VALUE.to_string());
+
+        ObjectMeta {
+            name: Some(name),
+            namespace: Some(self.namespace.clone()),
+            labels: Some(labels),
+            owner_references: owner_uid.map(|uid| {
+                vec![OwnerReference {
+                    api_version: "v1".to_string(),
+                    kind: "ConfigMap".to_string(),
+                    name: format!("{}-config", self.cluster_id),
+                    uid,
+                    controller: Some(true),
+                    block_owner_deletion: Some(true),
+                }]
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+/// Minimal ClusterRole needed by the curvine-kube CLI itself.
+///
+/// Keep in sync with the resources touched by `CurvineKubeClient`,
+/// `KubernetesValidator` and the `doctor` preflight checks.
+pub fn build_cli_cluster_role(name: &str) -> ClusterRole {
+    let write = &[
+        "get", "list", "watch", "create", "patch", "update", "delete",
+    ];
+
+    ClusterRole {
+        metadata: ObjectMeta {
+            name: Some(name.to_string()),
+            ..Default::default()
+        },
+        rules: Some(vec![
+            policy_rule("apps", &["statefulsets"], write),
+            policy_rule("", &["configmaps", "services"], write),
+            policy_rule("", &["pods"], &["get", "list", "watch", "delete"]),
+            policy_rule("", &["pods/log"], &["get"]),
+            policy_rule("", &["pods/exec", "pods/portforward"], &["get", "create"]),
+            policy_rule("", &["events"], &["list", "watch"]),
+            policy_rule("", &["persistentvolumeclaims"], &["get", "list", "delete"]),
+            policy_rule("storage.k8s.io", &["storageclasses"], &["get", "list"]),
+            policy_rule("", &["nodes"], &["get", "list"]),
+            policy_rule("", &["namespaces"], &["get"]),
+            policy_rule("", &["resourcequotas", "limitranges"], &["list"]),
+            policy_rule(
+                "authorization.k8s.io",
+                &["selfsubjectaccessreviews"],
+                &["create"],
+            ),
+            policy_rule("networking.k8s.io", &["networkpolicies"], write),
+            policy_rule("", &["serviceaccounts", "secrets"], write),
+            policy_rule(RBAC_API_GROUP, &["roles", "rolebindings"], write),
+            policy_rule("cert-manager.io", &["certificates"], write),
+        ]),
+        ..Default::default()
+    }
+}
+
+fn policy_rule(api_group: &str, resources: &[&str], verbs: &[&str]) -> PolicyRule {
+    PolicyRule {
+        api_groups: Some(vec![api_group.to_string()]),
+        resources: Some(resources.iter().map(|r| r.to_string()).collect()),
+        verbs: verbs.iter().map(|v| v.to_string()).collect(),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::config::SecretConfig;
+use crate::infrastructure::constants::*;
+use crate::shared::error::KubeError;
+use k8s_openapi::api::core::v1::{KeyToPath, Secret, SecretVolumeSource, Volume};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+use k8s_openapi::ByteString;
+use std::collections::BTreeMap;
+
+/// Builds an `Opaque` Secret, e.g. the one holding the complete cluster config.
+///
+/// Values are stored in `data` (base64) under their given keys.
+pub struct SecretBuilder {
+    cluster_id: String,
+    namespace: String,
+    name: String,
+    values: BTreeMap<String, String>,
+}
+
+impl SecretBuilder {
+    pub fn new(
+        cluster_id: String,
+        namespace: String,
+        name: String,
+        values: BTreeMap<String, String>,
+    ) -> Self {
+        Self {
+            cluster_id,
+            namespace,
+            name,
+            values,
+        }
+    }
+
+    pub fn build(&self) -> Result<Secret, KubeError> {
+        self.build_with_owner(None)
+    }
+
+    pub fn build_with_owner(&self, owner_uid: Option<String>) -> Result<Secret, KubeError> {
+        let mut labels = BTreeMap::new();
+        labels.insert(LABEL_APP.to_string(), self.cluster_id.clone());
+        labels.insert(LABEL_COMPONENT.to_string(), "secrets".to_string());
+        labels.insert(LABEL_TYPE.to_string(), LABEL_TYPE_VALUE.to_string());
+
+        let metadata = ObjectMeta {
+            name: Some(self.name.clone()),
+            namespace: Some(self.namespace.clone()),
+            labels: Some(labels),
+            owner_references: owner_uid.map(|uid| {
+                vec![OwnerReference {
+                    api_version: "v1".to_string(),
+                    kind: "ConfigMap".to_string(),
+                    name: format!("{}-config", self.cluster_id),
+                    uid,
+                    controller: Some(true),
+                    block_owner_deletion: Some(true),
+                }]
+            }),
+            ..Default::default()
+        };
+
+        let data = self
+            .values
+            .iter()
+            .map(|(k, v)| (k.clone(), ByteString(v.as_bytes().to_vec())))
+            .collect();
+
+        Ok(Secret {
+            metadata,
+            type_: Some("Opaque".to_string()),
+            data: Some(data),
+            ..Default::default()
+        })
+    }
+}
+
+/// Remove dotted `keys` from a rendered TOML config.
+///
+/// Returns the remaining TOML and the removed values; keys absent from the
+/// config are skipped. Non-string values are kept in their TOML form.
+pub fn split_secret_keys(
+    toml_str: &str,
+    keys: &[String],
+) -> Result<(String, BTreeMap<String, String>), KubeError> {
+    if keys.is_empty() {
+        return Ok((toml_str.to_string(), BTreeMap::new()));
+    }
+
+    let mut root: toml::Value =
+        toml::from_str(toml_str).map_err(|e| KubeError::ConfigError(e.to_string()))?;
+
+    let mut values = BTreeMap::new();
+    for key in keys {
+        if let Some(value) = remove_path(&mut root, key) {
+            let value = match value {
+                toml::Value::String(s) => s,
+                other => other.to_string(),
+            };
+            values.insert(key.clone(), value);
+        }
+    }
+
+    let remaining = toml::to_string(&root).map_err(|e| KubeError::ConfigError(e.to_string()))?;
+    Ok((remaining, values))
+}
+
+/// Set dotted `keys` in a rendered TOML config, creating missing tables.
+///
+/// Values are inserted as strings, except where the config already holds a
+/// non-string value of which the new value is a valid TOML form.
+pub fn merge_secret_keys(
+    toml_str: &str,
+    values: &BTreeMap<String, String>,
+) -> Result<String, KubeError> {
+    if values.is_empty() {
+        return Ok(toml_str.to_string());
+    }
+
+    let mut root: toml::Value =
+        toml::from_str(toml_str).map_err(|e| KubeError::ConfigError(e.to_string()))?;
+
+    for (key, value) in values {
+        let (parent, leaf) = match key.rsplit_once('.') {
+            Some((parent, leaf)) => (Some(parent), leaf),
+            None => (None, key.as_str()),
+        };
+        let mut table = root
+            .as_table_mut()
+            .ok_or_else(|| KubeError::ConfigError("Invalid cluster config".to_string()))?;
+        for part in parent.into_iter().flat_map(|p| p.split('.')) {
+            table = table
+                .entry(part)
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+                .as_table_mut()
+                .ok_or_else(|| {
+                    KubeError::ConfigError(format!("Secret key '{}' is not in a table", key))
+                })?;
+        }
+
+        let typed = match table.get(leaf) {
+            Some(current) if !current.is_str() => {
+                toml::from_str::<toml::Table>(&format!("value = {}", value))
+                    .ok()
+                    .and_then(|mut parsed| parsed.remove("value"))
+                    .filter(|parsed| parsed.same_type(current))
+            }
+            _ => None,
+        };
+        table.insert(
+            leaf.to_string(),
+            typed.unwrap_or_else(|| toml::Value::String(value.clone())),
+        );
+    }
+
+    toml::to_string(&root).map_err(|e| KubeError::ConfigError(e.to_string()))
+}
+
+/// Values of dotted `keys` in a user-managed Secret, one entry per key
+pub fn secret_key_values(
+    secret: &Secret,
+    keys: &[String],
+) -> Result<BTreeMap<String, String>, KubeError> {
+    let name = secret.metadata.name.as_deref().unwrap_or_default();
+    keys.iter()
+        .map(|key| {
+            let value = secret
+                .data
+                .as_ref()
+                .and_then(|data| data.get(key))
+                .map(|bytes| String::from_utf8(bytes.0.clone()))
+                .or_else(|| {
+                    secret
+                        .string_data
+                        .as_ref()
+                        .and_then(|data| data.get(key))
+                        .map(|value| Ok(value.clone()))
+                })
+                .ok_or_else(|| {
+                    KubeError::ConfigError(format!("Secret '{}' has no key '{}'", name, key))
+                })?
+                .map_err(|_| {
+                    KubeError::ConfigError(format!(
+                        "Key '{}' of Secret '{}' is not valid UTF-8",
+                        key, name
+                    ))
+                })?;
+            Ok((key.clone(), value))
+        })
+        .collect()
+}
+
+/// Config volume backed by the config Secret, used instead of the ConfigMap
+/// when secret keys are configured
+pub fn config_secret_volume(cluster_id: &str) -> Volume {
+    Volume {
+        name: VOLUME_NAME_CONFIG.to_string(),
+        secret: Some(SecretVolumeSource {
+            secret_name: Some(SecretConfig::config_secret_name(cluster_id)),
+            default_mode: Some(CONFIG_FILE_MODE),
+            items: Some(vec![KeyToPath {
+                key: CONFIG_FILE_NAME.to_string(),
+                path: CONFIG_FILE_NAME.to_string(),
+                mode: Some(CONFIG_FILE_MODE),
+            }]),
+            optional: Some(false),
+        }),
+        ..Default::default()
+    }
+}
+
+fn remove_path(root: &mut toml::Value, key: &str) -> Option<toml::Value> {
+    let (parent, leaf) = match key.rsplit_once('.') {
+        Some((parent, leaf)) => (Some(parent), leaf),
+        None => (None, key),
+    };
+
+    let mut table = root.as_table_mut()?;
+    if let Some(parent) = parent {
+        for part in parent.split('.') {
+            table = table.get_mut(part)?.as_table_mut()?;
+        }
+    }
+    table.remove(leaf)
+}
@@ -17,8 +17,10 @@ use crate::domain::config::ClusterConf;
 use crate::infrastructure::constants::*;
 use crate::infrastructure::kubernetes::resources::pod::template_utils::load_pod_from_template_file;
 use crate::infrastructure::kubernetes::resources::pod::{
-    merge_pod_with_template, EnvironmentBuilder, LifecycleBuilder, PodBuilder,
+    merge_pod_with_template, EnvironmentBuilder, LifecycleBuilder, PlacementBuilder, PodBuilder,
 };
+use crate::infrastructure::kubernetes::resources::secret::config_secret_volume;
+use crate::infrastructure::kubernetes::resources::tls::{tls_volume, tls_volume_mount};
 use crate::shared::error::Result;
 use k8s_openapi::api::apps::v1::{StatefulSet, StatefulSetSpec};
 use k8s_openapi::api::core::v1::PersistentVolumeClaim;
@@ -93,6 +95,7 @@ impl MasterBuilder {
             Some(load_pod_from_template_file(
                 template_file,
                 CONTAINER_NAME_MASTER,
+                self.config.interpolate_pod_templates,
             )?)
         } else {
             None
@@ -232,6 +235,12 @@ impl MasterBuilder {
             None
         };
 
+        let placement = PlacementBuilder::new(
+            &self.cluster_id,
+            COMPONENT_MASTER,
+            self.config.master.placement.as_ref(),
+        );
+
         let pod = k8s_openapi::api::core::v1::Pod {
             metadata: ObjectMeta {
                 labels: Some(all_labels),
@@ -247,9 +256,10 @@ impl MasterBuilder {
                     }
                     btm
                 }),
-                affinity: self.config.master.affinity.clone(),
+                affinity: placement.merge_affinity(self.config.master.affinity.clone()),
+                topology_spread_constraints: placement.build_topology_spread_constraints(),
                 restart_policy: Some(RESTART_POLICY_ALWAYS.to_string()),
-                service_account_name: self.config.master.service_account.clone(),
+                service_account_name: self.config.master_service_account(),
                 tolerations: if !self.config.master.tolerations.is_empty() {
                     Some(self.config.master.tolerations.clone())
                 } else {
@@ -268,20 +278,30 @@ impl MasterBuilder {
     pub fn build_volumes_impl(&self) -> Result<Vec<k8s_openapi::api::core::v1::Volume>> {
         let mut volumes = Vec::new();
 
-        volumes.push(k8s_openapi::api::core::v1::Volume {
-            name: VOLUME_NAME_CONFIG.to_string(),
-            config_map: Some(k8s_openapi::api::core::v1::ConfigMapVolumeSource {
-                name: format!("{}{}", self.cluster_id, SERVICE_SUFFIX_CONFIG),
-                default_mode: Some(CONFIG_FILE_MODE),
-                items: Some(vec![k8s_openapi::api::core::v1::KeyToPath {
-                    key: CONFIG_FILE_NAME.to_string(),
-                    path: CONFIG_FILE_NAME.to_string(),
-                    mode: Some(CONFIG_FILE_MODE),
-                }]),
-                optional: Some(false),
-            }),
-            ..Default::default()
-        });
+        // Config Volume (required): the config Secret holds the complete
+        // config when secret keys are configured, the ConfigMap otherwise
+        if self.config.secrets.is_some() {
+            volumes.push(config_secret_volume(&self.cluster_id));
+        } else {
+            volumes.push(k8s_openapi::api::core::v1::Volume {
+                name: VOLUME_NAME_CONFIG.to_string(),
+                config_map: Some(k8s_openapi::api::core::v1::ConfigMapVolumeSource {
+                    name: format!("{}{}", self.cluster_id, SERVICE_SUFFIX_CONFIG),
+                    default_mode: Some(CONFIG_FILE_MODE),
+                    items: Some(vec![k8s_openapi::api::core::v1::KeyToPath {
+                        key: CONFIG_FILE_NAME.to_string(),
+                        path: CONFIG_FILE_NAME.to_string(),
+                        mode: Some(CONFIG_FILE_MODE),
+                    }]),
+                    optional: Some(false),
+                }),
+                ..Default::default()
+            });
+        }
+
+        if self.config.tls.is_some() {
+            volumes.push(tls_volume(&self.cluster_id));
+        }
 
         Ok(volumes)
     }
@@ -313,6 +333,10 @@ impl MasterBuilder {
             ..Default::default()
         });
 
+        if self.config.tls.is_some() {
+            mounts.push(tls_volume_mount());
+        }
+
         Ok(mounts)
     }
 
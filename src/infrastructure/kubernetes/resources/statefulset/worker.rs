@@ -19,8 +19,10 @@ use crate::infrastructure::kubernetes::resources::pod::template_utils::{
     format_bytes, load_pod_from_template_file,
 };
 use crate::infrastructure::kubernetes::resources::pod::{
-    merge_pod_with_template, EnvironmentBuilder, LifecycleBuilder, PodBuilder,
+    merge_pod_with_template, EnvironmentBuilder, LifecycleBuilder, PlacementBuilder, PodBuilder,
 };
+use crate::infrastructure::kubernetes::resources::secret::config_secret_volume;
+use crate::infrastructure::kubernetes::resources::tls::{tls_volume, tls_volume_mount};
 use crate::shared::error::Result;
 use k8s_openapi::api::apps::v1::{StatefulSet, StatefulSetSpec};
 use k8s_openapi::api::core::v1::{Container, PersistentVolumeClaim, PodSpec, PodTemplateSpec};
@@ -89,6 +91,7 @@ impl WorkerBuilder {
             Some(load_pod_from_template_file(
                 template_file,
                 CONTAINER_NAME_WORKER,
+                self.config.interpolate_pod_templates,
             )?)
         } else {
             None
@@ -242,7 +245,8 @@ impl WorkerBuilder {
                     btm
                 }),
                 affinity: self.build_affinity(),
-                service_account_name: self.config.worker.service_account.clone(),
+                topology_spread_constraints: self.placement().build_topology_spread_constraints(),
+                service_account_name: self.config.worker_service_account(),
                 tolerations: if !self.config.worker.tolerations.is_empty() {
                     Some(self.config.worker.tolerations.clone())
                 } else {
@@ -340,21 +344,26 @@ impl WorkerBuilder {
     pub fn build_volumes_impl(&self) -> Result<Vec<k8s_openapi::api::core::v1::Volume>> {
         let mut volumes = Vec::new();
 
-        // ConfigMap Volume (required)
-        volumes.push(k8s_openapi::api::core::v1::Volume {
-            name: VOLUME_NAME_CONFIG.to_string(),
-            config_map: Some(k8s_openapi::api::core::v1::ConfigMapVolumeSource {
-                name: format!("{}{}", self.cluster_id, SERVICE_SUFFIX_CONFIG),
-                default_mode: Some(CONFIG_FILE_MODE),
-                items: Some(vec![k8s_openapi::api::core::v1::KeyToPath {
-                    key: CONFIG_FILE_NAME.to_string(),
-                    path: CONFIG_FILE_NAME.to_string(),
-                    mode: Some(CONFIG_FILE_MODE),
-                }]),
-                optional: Some(false),
-            }),
-            ..Default::default()
-        });
+        // Config Volume (required): the config Secret holds the complete
+        // config when secret keys are configured, the ConfigMap otherwise
+        if self.config.secrets.is_some() {
+            volumes.push(config_secret_volume(&self.cluster_id));
+        } else {
+            volumes.push(k8s_openapi::api::core::v1::Volume {
+                name: VOLUME_NAME_CONFIG.to_string(),
+                config_map: Some(k8s_openapi::api::core::v1::ConfigMapVolumeSource {
+                    name: format!("{}{}", self.cluster_id, SERVICE_SUFFIX_CONFIG),
+                    default_mode: Some(CONFIG_FILE_MODE),
+                    items: Some(vec![k8s_openapi::api::core::v1::KeyToPath {
+                        key: CONFIG_FILE_NAME.to_string(),
+                        path: CONFIG_FILE_NAME.to_string(),
+                        mode: Some(CONFIG_FILE_MODE),
+                    }]),
+                    optional: Some(false),
+                }),
+                ..Default::default()
+            });
+        }
 
         // Data directory Volumes
         let data_dirs = self.parse_data_dirs()?;
@@ -405,6 +414,10 @@ impl WorkerBuilder {
             }
         }
 
+        if self.config.tls.is_some() {
+            volumes.push(tls_volume(&self.cluster_id));
+        }
+
         Ok(volumes)
     }
 
@@ -433,6 +446,10 @@ impl WorkerBuilder {
             });
         }
 
+        if self.config.tls.is_some() {
+            mounts.push(tls_volume_mount());
+        }
+
         Ok(mounts)
     }
 
@@ -451,8 +468,22 @@ impl WorkerBuilder {
 
     // merge_with_template is now in pod_merger module
 
+    fn placement(&self) -> PlacementBuilder<'_> {
+        PlacementBuilder::new(
+            &self.cluster_id,
+            COMPONENT_WORKER,
+            self.config.worker.placement.as_ref(),
+        )
+    }
+
     /// Build PodAntiAffinity for Worker nodes
+    ///
+    /// A configured placement wins over the legacy `anti_affinity` flag,
+    /// which keeps its soft per-host spreading.
     fn build_affinity(&self) -> Option<k8s_openapi::api::core::v1::Affinity> {
+        if self.config.worker.placement.is_some() {
+            return self.placement().build_affinity();
+        }
         if !self.config.worker.anti_affinity {
             return None;
         }
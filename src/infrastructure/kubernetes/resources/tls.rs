@@ -0,0 +1,277 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::domain::config::kubernetes::{KubernetesConfig, TlsConfig};
+use crate::infrastructure::constants::*;
+use crate::shared::error::KubeError;
+use k8s_openapi::api::core::v1::{Secret, SecretVolumeSource, Volume, VolumeMount};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ObjectMeta, OwnerReference};
+use k8s_openapi::ByteString;
+use kube::api::{ApiResource, DynamicObject, GroupVersionKind};
+use openssl::asn1::Asn1Time;
+use openssl::bn::{BigNum, MsbOption};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::x509::extension::{
+    BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName, SubjectKeyIdentifier,
+};
+use openssl::x509::{X509Builder, X509NameBuilder, X509NameRef, X509};
+use std::collections::BTreeMap;
+
+/// cert-manager `Certificate` resource (cert-manager.io/v1)
+pub fn certificate_api_resource() -> ApiResource {
+    ApiResource::from_gvk(&GroupVersionKind::gvk(
+        "cert-manager.io",
+        "v1",
+        "Certificate",
+    ))
+}
+
+/// Builds the certificate material for `<cluster-id>-tls`: either a
+/// cert-manager `Certificate` or a locally generated self-signed Secret.
+pub struct TlsBuilder {
+    cluster_id: String,
+    namespace: String,
+    config: KubernetesConfig,
+}
+
+impl TlsBuilder {
+    pub fn new(cluster_id: String, namespace: String, config: KubernetesConfig) -> Self {
+        Self {
+            cluster_id,
+            namespace,
+            config,
+        }
+    }
+
+    fn tls_config(&self) -> Result<&TlsConfig, KubeError> {
+        self.config
+            .tls
+            .as_ref()
+            .ok_or_else(|| KubeError::ConfigError("TLS is not enabled".to_string()))
+    }
+
+    pub fn secret_name(&self) -> String {
+        TlsConfig::secret_name(&self.cluster_id)
+    }
+
+    pub fn build_certificate(&self, owner_uid: Option<String>) -> Result<DynamicObject, KubeError> {
+        let tls = self.tls_config()?;
+        let issuer = tls.issuer.clone().ok_or_else(|| {
+            KubeError::ConfigError("tls.issuer is required for cert-manager".to_string())
+        })?;
+
+        let mut certificate = DynamicObject::new(&self.secret_name(), &certificate_api_resource())
+            .within(&self.namespace);
+        certificate.metadata = self.metadata(self.secret_name(), owner_uid);
+        certificate.data = serde_json::json!({
+            "spec": {
+                "secretName": self.secret_name(),
+                "commonName": self.cluster_id,
+                "dnsNames": self.config.tls_dns_names(),
+                "duration": format!("{}h", tls.validity_days * 24),
+                "privateKey": { "algorithm": "ECDSA", "size": 256 },
+                "usages": ["server auth", "client auth"],
+                "issuerRef": {
+                    "name": issuer,
+                    "kind": tls.issuer_kind,
+                    "group": "cert-manager.io",
+                },
+            }
+        });
+
+        Ok(certificate)
+    }
+
+    /// Generate a CA and a leaf certificate for all cluster DNS names.
+    pub fn build_self_signed_secret(&self, owner_uid: Option<String>) -> Result<Secret, KubeError> {
+        let days = self.tls_config()?.validity_days;
+
+        let ca_key = generate_key()?;
+        let mut ca = new_cert_builder(&format!("{}-ca", self.cluster_id), None, &ca_key, days)?;
+        ca.append_extension(
+            BasicConstraints::new()
+                .critical()
+                .ca()
+                .build()
+                .map_err(tls_error)?,
+        )
+        .map_err(tls_error)?;
+        ca.append_extension(
+            KeyUsage::new()
+                .critical()
+                .key_cert_sign()
+                .crl_sign()
+                .build()
+                .map_err(tls_error)?,
+        )
+        .map_err(tls_error)?;
+        let ski = SubjectKeyIdentifier::new()
+            .build(&ca.x509v3_context(None, None))
+            .map_err(tls_error)?;
+        ca.append_extension(ski).map_err(tls_error)?;
+        ca.sign(&ca_key, MessageDigest::sha256())
+            .map_err(tls_error)?;
+        let ca_cert = ca.build();
+
+        let key = generate_key()?;
+        let mut leaf =
+            new_cert_builder(&self.cluster_id, Some(ca_cert.subject_name()), &key, days)?;
+        leaf.append_extension(BasicConstraints::new().build().map_err(tls_error)?)
+            .map_err(tls_error)?;
+        leaf.append_extension(
+            KeyUsage::new()
+                .critical()
+                .digital_signature()
+                .key_encipherment()
+                .build()
+                .map_err(tls_error)?,
+        )
+        .map_err(tls_error)?;
+        leaf.append_extension(
+            ExtendedKeyUsage::new()
+                .server_auth()
+                .client_auth()
+                .build()
+                .map_err(tls_error)?,
+        )
+        .map_err(tls_error)?;
+        let mut san = SubjectAlternativeName::new();
+        for name in self.config.tls_dns_names() {
+            san.dns(&name);
+        }
+        let san = san
+            .build(&leaf.x509v3_context(Some(&ca_cert), None))
+            .map_err(tls_error)?;
+        leaf.append_extension(san).map_err(tls_error)?;
+        leaf.sign(&ca_key, MessageDigest::sha256())
+            .map_err(tls_error)?;
+        let leaf_cert = leaf.build();
+
+        let mut data = BTreeMap::new();
+        data.insert(
+            TLS_CERT_KEY.to_string(),
+            ByteString(leaf_cert.to_pem().map_err(tls_error)?),
+        );
+        data.insert(
+            TLS_PRIVATE_KEY.to_string(),
+            ByteString(key.private_key_to_pem_pkcs8().map_err(tls_error)?),
+        );
+        data.insert(
+            TLS_CA_KEY.to_string(),
+            ByteString(ca_cert.to_pem().map_err(tls_error)?),
+        );
+
+        Ok(Secret {
+            metadata: self.metadata(self.secret_name(), owner_uid),
+            type_: Some("kubernetes.io/tls".to_string()),
+            data: Some(data),
+            ..Default::default()
+        })
+    }
+
+    fn metadata(&self, name: String, owner_uid: Option<String>) -> ObjectMeta {
+        let mut labels = BTreeMap::new();
+        labels.insert(LABEL_APP.to_string(), self.cluster_id.clone());
+        labels.insert(LABEL_COMPONENT.to_string(), "tls".to_string());
+        labels.insert(LABEL_TYPE.to_string(), LABEL_TYPE_VALUE.to_string());
+
+        ObjectMeta {
+            name: Some(name),
+            namespace: Some(self.namespace.clone()),
+            labels: Some(labels),
+            owner_references: owner_uid.map(|uid| {
+                vec![OwnerReference {
+                    api_version: "v1".to_string(),
+                    kind: "ConfigMap".to_string(),
+                    name: format!("{}-config", self.cluster_id),
+                    uid,
+                    controller: Some(true),
+                    block_owner_deletion: Some(true),
+                }]
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+/// Volume exposing `<cluster-id>-tls` to master and worker pods
+pub fn tls_volume(cluster_id: &str) -> Volume {
+    Volume {
+        name: VOLUME_NAME_TLS.to_string(),
+        secret: Some(SecretVolumeSource {
+            secret_name: Some(TlsConfig::secret_name(cluster_id)),
+            default_mode: Some(TLS_FILE_MODE),
+            optional: Some(false),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+pub fn tls_volume_mount() -> VolumeMount {
+    VolumeMount {
+        name: VOLUME_NAME_TLS.to_string(),
+        mount_path: TLS_MOUNT_PATH.to_string(),
+        read_only: Some(true),
+        ..Default::default()
+    }
+}
+
+fn generate_key() -> Result<PKey<Private>, KubeError> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).map_err(tls_error)?;
+    let key = EcKey::generate(&group).map_err(tls_error)?;
+    PKey::from_ec_key(key).map_err(tls_error)
+}
+
+/// Certificate builder with subject, validity and key set; the issuer
+/// defaults to the subject (self-signed).
+fn new_cert_builder(
+    common_name: &str,
+    issuer: Option<&X509NameRef>,
+    key: &PKey<Private>,
+    days: u32,
+) -> Result<X509Builder, KubeError> {
+    let mut name = X509NameBuilder::new().map_err(tls_error)?;
+    name.append_entry_by_nid(Nid::COMMONNAME, common_name)
+        .map_err(tls_error)?;
+    let name = name.build();
+
+    let mut serial = BigNum::new().map_err(tls_error)?;
+    serial
+        .rand(128, MsbOption::MAYBE_ZERO, false)
+        .map_err(tls_error)?;
+
+    let serial = serial.to_asn1_integer().map_err(tls_error)?;
+    let not_before = Asn1Time::days_from_now(0).map_err(tls_error)?;
+    let not_after = Asn1Time::days_from_now(days).map_err(tls_error)?;
+
+    let mut builder = X509::builder().map_err(tls_error)?;
+    builder.set_version(2).map_err(tls_error)?;
+    builder.set_serial_number(&serial).map_err(tls_error)?;
+    builder.set_subject_name(&name).map_err(tls_error)?;
+    builder
+        .set_issuer_name(issuer.unwrap_or(&name))
+        .map_err(tls_error)?;
+    builder.set_pubkey(key).map_err(tls_error)?;
+    builder.set_not_before(&not_before).map_err(tls_error)?;
+    builder.set_not_after(&not_after).map_err(tls_error)?;
+    Ok(builder)
+}
+
+fn tls_error(e: openssl::error::ErrorStack) -> KubeError {
+    KubeError::ConfigError(format!("Failed to generate TLS certificate: {}", e))
+}
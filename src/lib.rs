@@ -21,8 +21,9 @@ pub mod shared;
 // Re-export commonly used types
 pub use domain::cluster::{CurvineClusterDescriptor, KubernetesValidator};
 pub use domain::config::{
-    ClusterConf, KubernetesConfig, MasterConfig, ServiceConfig, ServiceType, StorageConfig,
-    StorageType, WorkerConfig, WorkerDataDir,
+    AntiAffinityMode, ClusterConf, KubernetesConfig, MasterConfig, NetworkPolicyConfig,
+    PlacementConfig, RbacConfig, SecretConfig, ServiceConfig, ServiceType, StorageConfig,
+    StorageType, TlsConfig, TlsMode, TopologySpreadConfig, WorkerConfig, WorkerDataDir,
 };
 pub use infrastructure::kubernetes::{CurvineKubeClient, CurvineKubeClientImpl};
 pub use shared::{KubeError, Result};
@@ -32,5 +33,6 @@ pub use shared::{KubeError, Result};
 pub use domain::config::KubernetesConfigBuilder;
 #[doc(hidden)]
 pub use infrastructure::kubernetes::resources::{
-    ConfigMapBuilder, HeadlessServiceBuilder, MasterBuilder, ServiceBuilder, WorkerBuilder,
+    ConfigMapBuilder, HeadlessServiceBuilder, MasterBuilder, NetworkPolicyBuilder, RbacBuilder,
+    SecretBuilder, ServiceBuilder, TlsBuilder, WorkerBuilder,
 };
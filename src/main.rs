@@ -23,10 +23,22 @@ async fn main() -> anyhow::Result<()> {
     let args = CliArgs::parse();
 
     match args.command {
+        Commands::Init(cmd) => cmd.execute().await,
         Commands::Deploy(cmd) => cmd.execute().await,
         Commands::Update(cmd) => cmd.execute().await,
         Commands::List(cmd) => cmd.execute().await,
         Commands::Status(cmd) => cmd.execute().await,
         Commands::Delete(cmd) => cmd.execute().await,
+        Commands::Logs(cmd) => cmd.execute().await,
+        Commands::Cv(cmd) => {
+            let code = cmd.execute().await?;
+            std::process::exit(code)
+        }
+        Commands::PortForward(cmd) => cmd.execute().await,
+        Commands::Diagnose(cmd) => cmd.execute().await,
+        Commands::Doctor(cmd) => cmd.execute().await,
+        Commands::Validate(cmd) => cmd.execute().await,
+        Commands::Config(cmd) => cmd.execute().await,
+        Commands::Rbac(cmd) => cmd.execute().await,
     }
 }
@@ -0,0 +1,356 @@
+// Copyright 2025 JiangLong.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `${...}` interpolation of configuration files before they are parsed
+//!
+//! - `${VAR}`: environment variable `VAR`
+//! - `${VAR:-default}`: `default` when `VAR` is unset or empty
+//! - `${file:/path}`: contents of `/path` without the trailing newline
+//!
+//! `$${` stays a literal `${` and comments are left as they are. Values are
+//! escaped for the quoted string they are inserted into; unquoted references
+//! and single-quoted strings reject values that would change the structure
+//! of the document (line breaks, quotes, `key: value`, ...).
+
+use crate::shared::error::KubeError;
+
+/// Syntax of the interpolated file, which decides how values are escaped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Toml,
+    Yaml,
+    Json,
+}
+
+/// Inserted instead of file contents by [`interpolate_redacted`]
+pub const REDACTED_FILE: &str = "<redacted>";
+
+/// Characters that cannot start a YAML plain scalar
+const YAML_INDICATORS: &[char] = &[
+    ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`',
+];
+
+/// Interpolate `content` from the process environment and the file system
+pub fn interpolate(content: &str, syntax: Syntax) -> Result<String, KubeError> {
+    interpolate_with(
+        content,
+        syntax,
+        |name| std::env::var(name).ok(),
+        |path| std::fs::read_to_string(path).ok(),
+    )
+}
+
+/// Like [`interpolate`], but `${file:...}` references, which usually hold
+/// secrets, resolve to [`REDACTED_FILE`] so that the result can be shown
+pub fn interpolate_redacted(content: &str, syntax: Syntax) -> Result<String, KubeError> {
+    interpolate_with(
+        content,
+        syntax,
+        |name| std::env::var(name).ok(),
+        |path| {
+            std::path::Path::new(path)
+                .is_file()
+                .then(|| REDACTED_FILE.to_string())
+        },
+    )
+}
+
+/// Interpolate `content`, reading variables through `var` and files through
+/// `file`; every unresolved reference is reported in one error
+pub fn interpolate_with(
+    content: &str,
+    syntax: Syntax,
+    var: impl Fn(&str) -> Option<String>,
+    file: impl Fn(&str) -> Option<String>,
+) -> Result<String, KubeError> {
+    let mut output = String::with_capacity(content.len());
+    let mut missing: Vec<String> = Vec::new();
+    // Only TOML multi-line strings continue on the next line
+    let mut quote = Quote::None;
+
+    for line in content.split_inclusive('\n') {
+        let mut scanner = Scanner::new(syntax, quote);
+        let mut rest = line;
+        while let Some(c) = rest.chars().next() {
+            if rest.starts_with("$${") {
+                output.push_str("${");
+                scanner.inserted();
+                rest = &rest[3..];
+                continue;
+            }
+            if !rest.starts_with("${") {
+                if scanner.starts_comment(c) {
+                    output.push_str(rest);
+                    break;
+                }
+                let used = scanner.advance(rest);
+                output.push_str(&rest[..used]);
+                rest = &rest[used..];
+                continue;
+            }
+
+            let Some(end) = rest.find('}') else {
+                return Err(KubeError::ConfigError(format!(
+                    "unterminated reference: {}",
+                    rest.trim_end()
+                )));
+            };
+            let reference = &rest[2..end];
+            rest = &rest[end + 1..];
+
+            match resolve(reference, &var, &file)? {
+                Some(value) => {
+                    let escaped = scanner.escape(&value).map_err(|reason| {
+                        KubeError::ConfigError(format!(
+                            "cannot insert ${{{}}}: the value {}",
+                            reference, reason
+                        ))
+                    })?;
+                    output.push_str(&escaped);
+                    scanner.inserted();
+                }
+                None => {
+                    if !missing.iter().any(|name| name == reference) {
+                        missing.push(reference.to_string());
+                    }
+                }
+            }
+        }
+
+        quote = match scanner.quote {
+            Quote::TripleDouble | Quote::TripleSingle => scanner.quote,
+            _ => Quote::None,
+        };
+    }
+
+    if !missing.is_empty() {
+        return Err(KubeError::ConfigError(format!(
+            "unresolved references: {}",
+            missing.join(", ")
+        )));
+    }
+    Ok(output)
+}
+
+fn resolve(
+    reference: &str,
+    var: &impl Fn(&str) -> Option<String>,
+    file: &impl Fn(&str) -> Option<String>,
+) -> Result<Option<String>, KubeError> {
+    if let Some(path) = reference.strip_prefix("file:") {
+        return Ok(file(path).map(|content| content.trim_end_matches(['\n', '\r']).to_string()));
+    }
+
+    let (name, default) = match reference.split_once(":-") {
+        Some((name, default)) => (name, Some(default)),
+        None => (reference, None),
+    };
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(KubeError::ConfigError(format!(
+            "invalid reference: ${{{}}}",
+            reference
+        )));
+    }
+
+    Ok(match (var(name), default) {
+        (Some(value), Some(default)) if value.is_empty() => Some(default.to_string()),
+        (Some(value), _) => Some(value),
+        (None, default) => default.map(str::to_string),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quote {
+    None,
+    Double,
+    Single,
+    /// TOML `"""`
+    TripleDouble,
+    /// TOML `'''`
+    TripleSingle,
+}
+
+/// Tracks whether the scanned position of a line is inside a string
+struct Scanner {
+    syntax: Syntax,
+    quote: Quote,
+    /// Last non-blank character of the line so far
+    last: Option<char>,
+    /// Whether the previous character was blank (or the line just started)
+    after_blank: bool,
+}
+
+impl Scanner {
+    fn new(syntax: Syntax, quote: Quote) -> Self {
+        Self {
+            syntax,
+            quote,
+            last: None,
+            after_blank: true,
+        }
+    }
+
+    /// Record text inserted for a reference
+    fn inserted(&mut self) {
+        self.last = Some('$');
+        self.after_blank = false;
+    }
+
+    /// Step over the next character of `rest`, taking escape sequences and
+    /// triple quotes as a whole; returns the number of bytes consumed
+    fn advance(&mut self, rest: &str) -> usize {
+        let c = rest.chars().next().unwrap_or_default();
+        let mut used = c.len_utf8();
+        match self.quote {
+            Quote::None if self.opens_quote(c) => {
+                let triple = self.syntax == Syntax::Toml
+                    && (rest.starts_with("\"\"\"") || rest.starts_with("'''"));
+                self.quote = match (c, triple) {
+                    ('"', true) => Quote::TripleDouble,
+                    ('"', false) => Quote::Double,
+                    (_, true) => Quote::TripleSingle,
+                    (_, false) => Quote::Single,
+                };
+                if triple {
+                    used = 3;
+                }
+            }
+            Quote::Double | Quote::TripleDouble if c == '\\' => {
+                used += rest[1..].chars().next().map_or(0, char::len_utf8);
+            }
+            Quote::Double if c == '"' => self.quote = Quote::None,
+            // `''` is an escaped quote in YAML
+            Quote::Single
+                if c == '\'' && self.syntax == Syntax::Yaml && rest[1..].starts_with('\'') =>
+            {
+                used = 2
+            }
+            Quote::Single if c == '\'' => self.quote = Quote::None,
+            Quote::TripleDouble if rest.starts_with("\"\"\"") => {
+                self.quote = Quote::None;
+                used = 3;
+            }
+            Quote::TripleSingle if rest.starts_with("'''") => {
+                self.quote = Quote::None;
+                used = 3;
+            }
+            _ => {}
+        }
+        if !c.is_whitespace() {
+            self.last = Some(c);
+        }
+        self.after_blank = c.is_whitespace();
+        used
+    }
+
+    /// Whether `c` starts a string here; in YAML a quote only does so at the
+    /// start of a scalar, elsewhere it is a plain character
+    fn opens_quote(&self, c: char) -> bool {
+        match c {
+            '"' => self.syntax != Syntax::Yaml || self.starts_yaml_scalar(),
+            '\'' => match self.syntax {
+                Syntax::Toml => true,
+                Syntax::Yaml => self.starts_yaml_scalar(),
+                Syntax::Json => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the next character begins a YAML scalar (value, list item, ...)
+    fn starts_yaml_scalar(&self) -> bool {
+        matches!(self.last, None | Some(':' | '-' | '?' | ',' | '[' | '{'))
+    }
+
+    /// Whether `c` at the current position starts a comment
+    fn starts_comment(&self, c: char) -> bool {
+        c == '#'
+            && self.quote == Quote::None
+            && match self.syntax {
+                Syntax::Toml => true,
+                // `a#b` is part of a plain scalar
+                Syntax::Yaml => self.after_blank,
+                Syntax::Json => false,
+            }
+    }
+
+    /// Escape `value` for the current position, or say why it cannot be inserted
+    fn escape(&self, value: &str) -> Result<String, &'static str> {
+        let line_break = value.chars().any(|c| c.is_control() && c != '\t');
+        match self.quote {
+            Quote::Double | Quote::TripleDouble => Ok(escape_double_quoted(value)),
+            Quote::Single | Quote::TripleSingle if line_break => {
+                Err("contains a line break, which a single-quoted string cannot hold")
+            }
+            Quote::Single | Quote::TripleSingle if self.syntax == Syntax::Yaml => {
+                Ok(value.replace('\'', "''"))
+            }
+            Quote::Single | Quote::TripleSingle if value.contains('\'') => {
+                Err("contains a quote, which a TOML literal string cannot hold")
+            }
+            Quote::Single | Quote::TripleSingle => Ok(value.to_string()),
+            Quote::None if line_break => Err("contains a line break; quote the reference"),
+            Quote::None => {
+                let structural = match self.syntax {
+                    Syntax::Toml => value.contains(['#', '"', '\'', '=', '[', ']', '{', '}', ',']),
+                    Syntax::Json => value.contains(['"', '{', '}', '[', ']', ',', ':']),
+                    Syntax::Yaml => {
+                        value.contains(": ")
+                            || value.contains(" #")
+                            || value.ends_with(':')
+                            || (self.starts_yaml_scalar() && starts_with_indicator(value))
+                    }
+                };
+                if structural {
+                    Err("is not a plain value; quote the reference")
+                } else {
+                    Ok(value.to_string())
+                }
+            }
+        }
+    }
+}
+
+/// Escapes shared by TOML basic strings, YAML double-quoted scalars and JSON strings
+fn escape_double_quoted(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Whether `value` would be read as something other than a plain scalar at
+/// the start of a YAML value (`- a`, `*alias`, `[list]`, ...)
+fn starts_with_indicator(value: &str) -> bool {
+    let mut chars = value.chars();
+    match chars.next() {
+        Some(c) if YAML_INDICATORS.contains(&c) => true,
+        Some('-' | '?' | ':') => chars.next().is_none_or(char::is_whitespace),
+        _ => false,
+    }
+}
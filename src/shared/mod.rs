@@ -15,5 +15,6 @@
 //! Shared utilities and types
 
 pub mod error;
+pub mod interpolate;
 
 pub use error::{KubeError, Result};
@@ -42,6 +42,7 @@ mod tests {
                 env_vars: HashMap::new(),
                 dns_policy: None,
                 priority_class: None,
+                placement: None,
             },
             worker: WorkerConfig {
                 replicas: 3,
@@ -62,6 +63,7 @@ mod tests {
                 env_vars: HashMap::new(),
                 dns_policy: None,
                 priority_class: None,
+                placement: None,
             },
             service: ServiceConfig {
                 service_type: ServiceType::ClusterIP,
@@ -80,6 +82,12 @@ mod tests {
             image_pull_policy: "IfNotPresent".to_string(),
             image_pull_secrets: Vec::new(),
             cluster_domain: "cluster.local".to_string(),
+            network_policy: None,
+            rbac: None,
+            secrets: None,
+            tls: None,
+            curvine_version: None,
+            interpolate_pod_templates: false,
         }
     }
 
@@ -51,6 +51,7 @@ mod test_utils {
                 env_vars: HashMap::new(),
                 dns_policy: None,
                 priority_class: None,
+                placement: None,
             },
             worker: WorkerConfig {
                 replicas: 3,
@@ -71,6 +72,7 @@ mod test_utils {
                 env_vars: HashMap::new(),
                 dns_policy: None,
                 priority_class: None,
+                placement: None,
             },
             service: ServiceConfig {
                 service_type: ServiceType::ClusterIP,
@@ -89,6 +91,12 @@ mod test_utils {
             image_pull_policy: "IfNotPresent".to_string(),
             image_pull_secrets: Vec::new(),
             cluster_domain: "cluster.local".to_string(),
+            network_policy: None,
+            rbac: None,
+            secrets: None,
+            tls: None,
+            curvine_version: None,
+            interpolate_pod_templates: false,
         }
     }
 }
@@ -425,6 +433,29 @@ fn test_configmap_builder_labels() {
     );
 }
 
+#[test]
+fn test_configmap_peers_use_cluster_domain() {
+    let conf = test_utils::create_test_cluster_conf();
+    let builder = ConfigMapBuilder::new(conf, "test".to_string(), "default".to_string(), 3)
+        .with_cluster_domain("corp.example".to_string());
+
+    let configmap = builder.build().unwrap();
+    let rendered: toml::Value =
+        toml::from_str(&configmap.data.unwrap()["curvine-cluster.toml"]).unwrap();
+
+    let peers = rendered["journal"]["journal_addrs"].as_array().unwrap();
+    assert_eq!(peers.len(), 3);
+    assert_eq!(
+        peers[2]["hostname"].as_str(),
+        Some("test-master-2.test-master.default.svc.corp.example")
+    );
+    let masters = rendered["client"]["master_addrs"].as_array().unwrap();
+    assert!(masters.iter().all(|addr| addr["hostname"]
+        .as_str()
+        .unwrap()
+        .ends_with(".svc.corp.example")));
+}
+
 // ============================================================================
 // Tests for Master Path Resolution
 // ============================================================================
@@ -618,3 +649,3666 @@ fn test_worker_ufs_volume_creation() {
     assert_eq!(volumes.len(), 1);
     assert!(volumes.iter().any(|v| v.name == "curvine-conf"));
 }
+
+// ============================================================================
+// Tests for NetworkPolicy Builder
+// ============================================================================
+
+fn ingress_ports(rule: &k8s_openapi::api::networking::v1::NetworkPolicyIngressRule) -> Vec<i32> {
+    use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+
+    rule.ports
+        .as_ref()
+        .unwrap()
+        .iter()
+        .filter_map(|p| match p.port {
+            Some(IntOrString::Int(port)) => Some(port),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_network_policy_master_rules() {
+    let config = NetworkPolicyConfig {
+        client_namespaces: vec!["apps".to_string()],
+        client_labels: HashMap::from([("curvine-client".to_string(), "true".to_string())]),
+        monitoring_namespace: Some("monitoring".to_string()),
+    };
+    let builder = NetworkPolicyBuilder::new("test".to_string(), "default".to_string(), config);
+
+    let policies = builder.build().unwrap();
+    assert_eq!(policies.len(), 2);
+
+    let master = &policies[0];
+    assert_eq!(master.metadata.name, Some("test-master".to_string()));
+    let spec = master.spec.as_ref().unwrap();
+    assert_eq!(spec.policy_types, Some(vec!["Ingress".to_string()]));
+    assert_eq!(
+        spec.pod_selector
+            .match_labels
+            .as_ref()
+            .unwrap()
+            .get("component"),
+        Some(&"master".to_string())
+    );
+
+    let ingress = spec.ingress.as_ref().unwrap();
+    assert_eq!(ingress.len(), 3);
+
+    // Journal: master peers only
+    assert_eq!(ingress_ports(&ingress[0]), vec![8996]);
+    let journal_peers = ingress[0].from.as_ref().unwrap();
+    assert_eq!(journal_peers.len(), 1);
+    assert!(journal_peers[0].namespace_selector.is_none());
+
+    // RPC: master, worker and one client namespace peer
+    assert_eq!(ingress_ports(&ingress[1]), vec![8995]);
+    let rpc_peers = ingress[1].from.as_ref().unwrap();
+    assert_eq!(rpc_peers.len(), 3);
+    let client_peer = &rpc_peers[2];
+    assert_eq!(
+        client_peer
+            .namespace_selector
+            .as_ref()
+            .unwrap()
+            .match_labels
+            .as_ref()
+            .unwrap()
+            .get("kubernetes.io/metadata.name"),
+        Some(&"apps".to_string())
+    );
+    assert!(client_peer.pod_selector.is_some());
+
+    // Web: monitoring namespace
+    assert_eq!(ingress_ports(&ingress[2]), vec![9000, 9001]);
+}
+
+#[test]
+fn test_network_policy_worker_without_monitoring() {
+    let builder = NetworkPolicyBuilder::new(
+        "test".to_string(),
+        "default".to_string(),
+        NetworkPolicyConfig::default(),
+    );
+
+    let policies = builder.build_with_owner(Some("uid-1".to_string())).unwrap();
+    let worker = &policies[1];
+    assert_eq!(worker.metadata.name, Some("test-worker".to_string()));
+    assert_eq!(
+        worker.metadata.owner_references.as_ref().unwrap()[0].name,
+        "test-config"
+    );
+
+    // Without monitoring namespace or clients only the in-cluster RPC rule remains
+    let ingress = worker.spec.as_ref().unwrap().ingress.as_ref().unwrap();
+    assert_eq!(ingress.len(), 1);
+    assert_eq!(ingress_ports(&ingress[0]), vec![8997]);
+    assert_eq!(ingress[0].from.as_ref().unwrap().len(), 2);
+}
+
+#[test]
+fn test_network_policy_conf_from_toml() {
+    let conf: ClusterConf = toml::from_str(
+        r#"
+[client.kubernetes.network_policy]
+enabled = true
+client_namespaces = ["apps", "batch"]
+monitoring_namespace = "monitoring"
+"#,
+    )
+    .unwrap();
+
+    let np = conf
+        .client
+        .kubernetes
+        .unwrap()
+        .network_policy
+        .expect("network_policy section should be parsed");
+    assert!(np.enabled);
+    assert_eq!(np.client_namespaces, vec!["apps", "batch"]);
+    assert_eq!(np.monitoring_namespace, Some("monitoring".to_string()));
+    assert!(np.client_labels.is_empty());
+}
+
+// ============================================================================
+// Tests for RBAC Builder
+// ============================================================================
+
+#[test]
+fn test_rbac_service_accounts_respect_explicit_names() {
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.master.service_account = Some("custom-master".to_string());
+    config.rbac = Some(RbacConfig {
+        create_service_accounts: true,
+        pod_discovery: true,
+    });
+
+    assert_eq!(
+        config.master_service_account(),
+        Some("custom-master".to_string())
+    );
+    assert_eq!(
+        config.worker_service_account(),
+        Some("test-worker".to_string())
+    );
+
+    let builder = RbacBuilder::new("test".to_string(), "default".to_string(), config);
+    let accounts = builder
+        .build_service_accounts(Some("uid-1".to_string()))
+        .unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].metadata.name, Some("test-worker".to_string()));
+
+    let (role, binding) = builder.build_pod_discovery(None).unwrap().unwrap();
+    assert_eq!(role.metadata.name, Some("test-pod-discovery".to_string()));
+    assert_eq!(binding.role_ref.name, "test-pod-discovery");
+    let subjects: Vec<_> = binding
+        .subjects
+        .unwrap()
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    assert_eq!(subjects, vec!["custom-master", "test-worker"]);
+}
+
+#[test]
+fn test_rbac_disabled_builds_nothing() {
+    let config = test_utils::create_test_kubernetes_config();
+    assert_eq!(config.master_service_account(), None);
+
+    let builder = RbacBuilder::new("test".to_string(), "default".to_string(), config);
+    assert!(builder.build_service_accounts(None).unwrap().is_empty());
+    assert!(builder.build_pod_discovery(None).unwrap().is_none());
+}
+
+#[test]
+fn test_rbac_stale_resources_when_disabled() {
+    let disabled = RbacBuilder::new(
+        "test".to_string(),
+        "default".to_string(),
+        test_utils::create_test_kubernetes_config(),
+    );
+    assert_eq!(
+        disabled.stale_service_accounts().unwrap(),
+        vec!["test-master", "test-worker"]
+    );
+    assert_eq!(
+        disabled.stale_pod_discovery().unwrap(),
+        Some("test-pod-discovery".to_string())
+    );
+
+    // Generated accounts still in use, or named explicitly, are kept
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.master.service_account = Some("test-master".to_string());
+    config.rbac = Some(RbacConfig {
+        create_service_accounts: true,
+        pod_discovery: true,
+    });
+    config.worker.service_account = Some("custom-worker".to_string());
+    let enabled = RbacBuilder::new("test".to_string(), "default".to_string(), config);
+    assert_eq!(
+        enabled.stale_service_accounts().unwrap(),
+        vec!["test-worker"]
+    );
+    assert_eq!(enabled.stale_pod_discovery().unwrap(), None);
+}
+
+#[test]
+fn test_rbac_pod_discovery_binds_default_account() {
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.rbac = Some(RbacConfig {
+        create_service_accounts: false,
+        pod_discovery: true,
+    });
+
+    let builder = RbacBuilder::new("test".to_string(), "default".to_string(), config);
+    let (_, binding) = builder.build_pod_discovery(None).unwrap().unwrap();
+    let subjects = binding.subjects.unwrap();
+    assert_eq!(subjects.len(), 1);
+    assert_eq!(subjects[0].name, "default");
+    assert_eq!(subjects[0].namespace, Some("default".to_string()));
+}
+
+#[test]
+fn test_cli_cluster_role_covers_managed_resources() {
+    let role =
+        curvine_kube::infrastructure::kubernetes::resources::build_cli_cluster_role("curvine-kube");
+    let resources: Vec<String> = role
+        .rules
+        .unwrap()
+        .into_iter()
+        .flat_map(|r| r.resources.unwrap_or_default())
+        .collect();
+
+    for expected in [
+        "statefulsets",
+        "configmaps",
+        "services",
+        "pods",
+        "persistentvolumeclaims",
+        "networkpolicies",
+        "serviceaccounts",
+        "roles",
+        "rolebindings",
+    ] {
+        assert!(
+            resources.iter().any(|r| r == expected),
+            "missing {}",
+            expected
+        );
+    }
+}
+
+#[test]
+fn test_rbac_conf_from_toml() {
+    let conf: ClusterConf = toml::from_str(
+        r#"
+[client.kubernetes.rbac]
+create_service_accounts = true
+"#,
+    )
+    .unwrap();
+
+    let rbac = conf.client.kubernetes.unwrap().rbac.unwrap();
+    assert!(rbac.create_service_accounts);
+    assert!(!rbac.pod_discovery);
+}
+
+// ============================================================================
+// Tests for Secret Management
+// ============================================================================
+
+fn secret_cluster_conf() -> ClusterConf {
+    let mut conf = test_utils::create_test_cluster_conf();
+    conf.s3_gateway.access_key = Some("AKIAEXAMPLE".to_string());
+    conf.s3_gateway.secret_key = Some("s3cr3t".to_string());
+    conf
+}
+
+#[test]
+fn test_secret_keys_removed_from_configmap() {
+    let keys = vec![
+        "s3_gateway.access_key".to_string(),
+        "s3_gateway.secret_key".to_string(),
+    ];
+    let builder = ConfigMapBuilder::new(
+        secret_cluster_conf(),
+        "test".to_string(),
+        "default".to_string(),
+        3,
+    )
+    .with_secret_keys(keys);
+
+    let configmap = builder.build().unwrap();
+    let toml_content = &configmap.data.unwrap()["curvine-cluster.toml"];
+    assert!(!toml_content.contains("s3cr3t"));
+    assert!(!toml_content.contains("AKIAEXAMPLE"));
+    assert!(toml_content.contains("[s3_gateway]"));
+
+    let values = builder.secret_values().unwrap();
+    assert_eq!(values["s3_gateway.secret_key"], "s3cr3t");
+    assert_eq!(values["s3_gateway.access_key"], "AKIAEXAMPLE");
+
+    let secret = SecretBuilder::new(
+        "test".to_string(),
+        "default".to_string(),
+        "test-secrets".to_string(),
+        values,
+    )
+    .build_with_owner(Some("uid-1".to_string()))
+    .unwrap();
+    assert_eq!(secret.type_, Some("Opaque".to_string()));
+    assert_eq!(
+        secret.data.unwrap()["s3_gateway.secret_key"].0,
+        b"s3cr3t".to_vec()
+    );
+}
+
+#[test]
+fn test_secret_values_missing_key_is_error() {
+    let builder = ConfigMapBuilder::new(
+        test_utils::create_test_cluster_conf(),
+        "test".to_string(),
+        "default".to_string(),
+        3,
+    )
+    .with_secret_keys(vec!["s3_gateway.secret_key".to_string()]);
+
+    let err = builder.secret_values().unwrap_err();
+    assert!(err.to_string().contains("s3_gateway.secret_key"));
+}
+
+#[test]
+fn test_pods_mount_config_with_secret_values() {
+    use curvine_kube::infrastructure::constants::{CONFIG_FILE_NAME, CURVINE_CONF_FILE};
+
+    let keys = vec!["s3_gateway.secret_key".to_string()];
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.secrets = Some(SecretConfig {
+        keys: keys.clone(),
+        existing_secret: None,
+    });
+
+    let statefulset = MasterBuilder::new(
+        "test".to_string(),
+        "default".to_string(),
+        config,
+        secret_cluster_conf(),
+        false,
+    )
+    .build()
+    .unwrap();
+    let pod = statefulset.spec.unwrap().template.spec.unwrap();
+    let container = &pod.containers[0];
+    assert!(!container.env.iter().flatten().any(|e| e
+        .value_from
+        .as_ref()
+        .is_some_and(|v| v.secret_key_ref.is_some())));
+
+    // Follow the mount of the config file back to the Secret entry it reads
+    let mount = container
+        .volume_mounts
+        .iter()
+        .flatten()
+        .find(|m| m.mount_path == CURVINE_CONF_FILE)
+        .unwrap();
+    let volume = pod
+        .volumes
+        .iter()
+        .flatten()
+        .find(|v| v.name == mount.name)
+        .unwrap();
+    assert!(volume.config_map.is_none());
+    let source = volume.secret.as_ref().unwrap();
+    assert_eq!(source.secret_name.as_deref(), Some("test-secrets"));
+    let item = source
+        .items
+        .iter()
+        .flatten()
+        .find(|i| Some(i.path.as_str()) == mount.sub_path.as_deref())
+        .unwrap();
+
+    let builder = ConfigMapBuilder::new(
+        secret_cluster_conf(),
+        "test".to_string(),
+        "default".to_string(),
+        3,
+    )
+    .with_secret_keys(keys);
+    let secret = SecretBuilder::new(
+        "test".to_string(),
+        "default".to_string(),
+        SecretConfig::config_secret_name("test"),
+        [(
+            CONFIG_FILE_NAME.to_string(),
+            builder
+                .build_pod_config(&builder.secret_values().unwrap())
+                .unwrap(),
+        )]
+        .into(),
+    )
+    .build()
+    .unwrap();
+    assert_eq!(secret.metadata.name, source.secret_name);
+
+    let data = secret.data.unwrap();
+    let mounted = String::from_utf8(data[&item.key].0.clone()).unwrap();
+    let mounted_conf: ClusterConf = toml::from_str(&mounted).unwrap();
+    assert_eq!(
+        mounted_conf.s3_gateway.secret_key.as_deref(),
+        Some("s3cr3t")
+    );
+    assert_eq!(
+        mounted_conf.s3_gateway.access_key.as_deref(),
+        Some("AKIAEXAMPLE")
+    );
+
+    let configmap = builder.build().unwrap();
+    assert!(!configmap.data.unwrap()[CONFIG_FILE_NAME].contains("s3cr3t"));
+}
+
+#[test]
+fn test_existing_secret_values_merged_into_pod_config() {
+    use curvine_kube::infrastructure::kubernetes::resources::secret_key_values;
+    use k8s_openapi::api::core::v1::Secret;
+    use k8s_openapi::ByteString;
+
+    let keys = vec![
+        "s3_gateway.secret_key".to_string(),
+        "master.rpc_port".to_string(),
+    ];
+    let existing = Secret {
+        metadata: kube::api::ObjectMeta {
+            name: Some("external-creds".to_string()),
+            ..Default::default()
+        },
+        data: Some(
+            [
+                (
+                    "s3_gateway.secret_key".to_string(),
+                    ByteString(b"from-secret".to_vec()),
+                ),
+                ("master.rpc_port".to_string(), ByteString(b"9000".to_vec())),
+            ]
+            .into(),
+        ),
+        ..Default::default()
+    };
+    let values = secret_key_values(&existing, &keys).unwrap();
+
+    // The config file does not need to hold the values itself
+    let builder = ConfigMapBuilder::new(
+        test_utils::create_test_cluster_conf(),
+        "test".to_string(),
+        "default".to_string(),
+        3,
+    )
+    .with_secret_keys(keys.clone());
+    let mounted: ClusterConf = toml::from_str(&builder.build_pod_config(&values).unwrap()).unwrap();
+    assert_eq!(
+        mounted.s3_gateway.secret_key.as_deref(),
+        Some("from-secret")
+    );
+    assert_eq!(mounted.master.rpc_port, 9000);
+
+    let err = secret_key_values(&existing, &["s3_gateway.access_key".to_string()]).unwrap_err();
+    assert!(err.to_string().contains("external-creds"));
+    assert!(err.to_string().contains("s3_gateway.access_key"));
+}
+
+#[test]
+fn test_invalid_secret_key_rejected() {
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.secrets = Some(SecretConfig {
+        keys: vec!["s3_gateway..secret_key".to_string()],
+        existing_secret: None,
+    });
+    assert!(config.validate().is_err());
+}
+
+// ============================================================================
+// Tests for TLS
+// ============================================================================
+
+fn tls_kubernetes_config(mode: TlsMode) -> KubernetesConfig {
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.tls = Some(TlsConfig {
+        mode,
+        issuer: Some("ca-issuer".to_string()),
+        issuer_kind: "ClusterIssuer".to_string(),
+        validity_days: 30,
+    });
+    config
+}
+
+#[test]
+fn test_tls_dns_names_cover_headless_hosts() {
+    let config = tls_kubernetes_config(TlsMode::SelfSigned);
+    let names = config.tls_dns_names();
+
+    for i in 0..3 {
+        let host = format!("test-master-{}.test-master.default.svc.cluster.local", i);
+        assert!(names.contains(&host), "missing {}", host);
+    }
+    assert!(names.contains(&"*.test-worker.default.svc.cluster.local".to_string()));
+    assert!(names.contains(&"test-master.default.svc".to_string()));
+}
+
+#[test]
+fn test_tls_self_signed_secret_is_signed_by_ca() {
+    let config = tls_kubernetes_config(TlsMode::SelfSigned);
+    let builder = TlsBuilder::new("test".to_string(), "default".to_string(), config);
+    let secret = builder
+        .build_self_signed_secret(Some("uid-1".to_string()))
+        .unwrap();
+
+    assert_eq!(secret.metadata.name, Some("test-tls".to_string()));
+    assert_eq!(secret.type_, Some("kubernetes.io/tls".to_string()));
+
+    let data = secret.data.unwrap();
+    let cert = openssl::x509::X509::from_pem(&data["tls.crt"].0).unwrap();
+    let ca = openssl::x509::X509::from_pem(&data["ca.crt"].0).unwrap();
+    assert!(openssl::pkey::PKey::private_key_from_pem(&data["tls.key"].0).is_ok());
+    assert!(cert.verify(&ca.public_key().unwrap()).unwrap());
+
+    let sans: Vec<String> = cert
+        .subject_alt_names()
+        .unwrap()
+        .iter()
+        .filter_map(|n| n.dnsname().map(str::to_string))
+        .collect();
+    assert!(sans.contains(&"test-master-0.test-master.default.svc.cluster.local".to_string()));
+}
+
+#[test]
+fn test_tls_cert_manager_certificate() {
+    let config = tls_kubernetes_config(TlsMode::CertManager);
+    let builder = TlsBuilder::new("test".to_string(), "default".to_string(), config);
+    let certificate = builder.build_certificate(None).unwrap();
+
+    assert_eq!(
+        certificate.types.as_ref().unwrap().api_version,
+        "cert-manager.io/v1"
+    );
+    let spec = &certificate.data["spec"];
+    assert_eq!(spec["secretName"], "test-tls");
+    assert_eq!(spec["issuerRef"]["name"], "ca-issuer");
+    assert_eq!(spec["issuerRef"]["kind"], "ClusterIssuer");
+    assert_eq!(spec["duration"], "720h");
+    assert!(spec["dnsNames"]
+        .as_array()
+        .unwrap()
+        .contains(&serde_json::json!(
+            "*.test-master.default.svc.cluster.local"
+        )));
+}
+
+#[test]
+fn test_tls_mounted_and_configured() {
+    let config = tls_kubernetes_config(TlsMode::SelfSigned);
+    let conf = test_utils::create_test_cluster_conf();
+
+    let worker = WorkerBuilder::new(
+        "test".to_string(),
+        "default".to_string(),
+        config.clone(),
+        conf.clone(),
+    );
+    let volumes = worker.build_volumes_impl().unwrap();
+    let tls_volume = volumes.iter().find(|v| v.name == "curvine-tls").unwrap();
+    assert_eq!(
+        tls_volume.secret.as_ref().unwrap().secret_name,
+        Some("test-tls".to_string())
+    );
+    let mounts = worker.build_volume_mounts_impl().unwrap();
+    assert!(mounts
+        .iter()
+        .any(|m| m.name == "curvine-tls" && m.mount_path == "/app/curvine/conf/tls"));
+
+    let configmap =
+        ConfigMapBuilder::new(conf.clone(), "test".to_string(), "default".to_string(), 3)
+            .with_tls(config.tls.clone())
+            .build()
+            .unwrap();
+    let toml_content = &configmap.data.unwrap()["curvine-cluster.toml"];
+    let parsed: ClusterConf = toml::from_str(toml_content).unwrap();
+    let tls = parsed.tls.expect("tls section should be rendered");
+    assert!(tls.enabled);
+    assert_eq!(tls.cert_file, "/app/curvine/conf/tls/tls.crt");
+    assert_eq!(tls.key_file, "/app/curvine/conf/tls/tls.key");
+    assert_eq!(tls.ca_file, "/app/curvine/conf/tls/ca.crt");
+
+    // Without TLS no section is rendered
+    let configmap = ConfigMapBuilder::new(conf, "test".to_string(), "default".to_string(), 3)
+        .build()
+        .unwrap();
+    let parsed: toml::Table =
+        toml::from_str(&configmap.data.unwrap()["curvine-cluster.toml"]).unwrap();
+    assert!(!parsed.contains_key("tls"));
+}
+
+#[test]
+fn test_tls_cert_manager_requires_issuer() {
+    let mut config = tls_kubernetes_config(TlsMode::CertManager);
+    config.tls.as_mut().unwrap().issuer = None;
+    assert!(config.validate().is_err());
+
+    assert!("cert-manager".parse::<TlsMode>().is_ok());
+    assert!("acme".parse::<TlsMode>().is_err());
+}
+
+#[test]
+fn test_tls_conf_from_toml() {
+    let conf: ClusterConf = toml::from_str(
+        r#"
+[client.kubernetes.tls]
+enabled = true
+mode = "cert-manager"
+issuer = "letsencrypt"
+"#,
+    )
+    .unwrap();
+
+    let tls = conf.client.kubernetes.unwrap().tls.unwrap();
+    assert!(tls.enabled);
+    assert_eq!(tls.mode, "cert-manager");
+    assert_eq!(tls.issuer_kind, "Issuer");
+    assert_eq!(tls.validity_days, 365);
+}
+
+// ============================================================================
+// Tests for Pod Placement
+// ============================================================================
+
+fn build_pod_spec(
+    config: KubernetesConfig,
+    component: &str,
+) -> k8s_openapi::api::core::v1::PodSpec {
+    let conf = test_utils::create_test_cluster_conf();
+    let statefulset = if component == "master" {
+        MasterBuilder::new(
+            "test".to_string(),
+            "default".to_string(),
+            config,
+            conf,
+            false,
+        )
+        .build()
+        .unwrap()
+    } else {
+        WorkerBuilder::new("test".to_string(), "default".to_string(), config, conf)
+            .build()
+            .unwrap()
+    };
+    statefulset.spec.unwrap().template.spec.unwrap()
+}
+
+#[test]
+fn test_zone_aware_master_placement() {
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.master.placement = Some(PlacementConfig::zone_aware());
+
+    let spec = build_pod_spec(config, "master");
+    let spread = spec.topology_spread_constraints.unwrap();
+    assert_eq!(spread.len(), 1);
+    assert_eq!(spread[0].topology_key, "topology.kubernetes.io/zone");
+    assert_eq!(spread[0].max_skew, 1);
+    assert_eq!(spread[0].when_unsatisfiable, "DoNotSchedule");
+    let selector = spread[0].label_selector.as_ref().unwrap();
+    assert_eq!(
+        selector.match_labels.as_ref().unwrap()["component"],
+        "master"
+    );
+
+    let anti_affinity = spec.affinity.unwrap().pod_anti_affinity.unwrap();
+    let preferred = anti_affinity
+        .preferred_during_scheduling_ignored_during_execution
+        .unwrap();
+    assert_eq!(
+        preferred[0].pod_affinity_term.topology_key,
+        "kubernetes.io/hostname"
+    );
+}
+
+#[test]
+fn test_master_affinity_merged_with_placement() {
+    use k8s_openapi::api::core::v1::{
+        Affinity, NodeAffinity, NodeSelector, NodeSelectorRequirement, NodeSelectorTerm,
+    };
+
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.master.placement = Some(PlacementConfig::zone_aware());
+    config.master.affinity = Some(Affinity {
+        node_affinity: Some(NodeAffinity {
+            required_during_scheduling_ignored_during_execution: Some(NodeSelector {
+                node_selector_terms: vec![NodeSelectorTerm {
+                    match_expressions: Some(vec![NodeSelectorRequirement {
+                        key: "node-role/storage".to_string(),
+                        operator: "Exists".to_string(),
+                        values: None,
+                    }]),
+                    ..Default::default()
+                }],
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let affinity = build_pod_spec(config, "master").affinity.unwrap();
+    let node_terms = affinity
+        .node_affinity
+        .unwrap()
+        .required_during_scheduling_ignored_during_execution
+        .unwrap()
+        .node_selector_terms;
+    assert_eq!(
+        node_terms[0].match_expressions.as_ref().unwrap()[0].key,
+        "node-role/storage"
+    );
+    let preferred = affinity
+        .pod_anti_affinity
+        .unwrap()
+        .preferred_during_scheduling_ignored_during_execution
+        .unwrap();
+    assert_eq!(preferred.len(), 1);
+    assert_eq!(
+        preferred[0].pod_affinity_term.topology_key,
+        "kubernetes.io/hostname"
+    );
+}
+
+#[test]
+fn test_required_worker_anti_affinity_overrides_legacy_flag() {
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.worker.anti_affinity = true;
+    config.worker.placement = Some(PlacementConfig {
+        anti_affinity: AntiAffinityMode::Required,
+        ..Default::default()
+    });
+
+    let spec = build_pod_spec(config, "worker");
+    assert!(spec.topology_spread_constraints.is_none());
+    let anti_affinity = spec.affinity.unwrap().pod_anti_affinity.unwrap();
+    assert!(anti_affinity
+        .preferred_during_scheduling_ignored_during_execution
+        .is_none());
+    let required = anti_affinity
+        .required_during_scheduling_ignored_during_execution
+        .unwrap();
+    assert_eq!(required[0].topology_key, "kubernetes.io/hostname");
+}
+
+#[test]
+fn test_no_placement_keeps_defaults() {
+    let config = test_utils::create_test_kubernetes_config();
+    let spec = build_pod_spec(config, "master");
+    assert!(spec.affinity.is_none());
+    assert!(spec.topology_spread_constraints.is_none());
+}
+
+#[test]
+fn test_placement_validation_and_parsing() {
+    let mut config = test_utils::create_test_kubernetes_config();
+    let mut placement = PlacementConfig::zone_aware();
+    placement.topology_spread[0].when_unsatisfiable = "Sometimes".to_string();
+    config.master.placement = Some(placement);
+    assert!(config.validate().is_err());
+
+    assert!(PlacementConfig::from_preset("zone-aware")
+        .unwrap()
+        .is_zone_aware());
+    assert!(PlacementConfig::from_preset("rack-aware").is_err());
+    assert!(!PlacementConfig::default().is_zone_aware());
+    assert_eq!(
+        "required".parse::<AntiAffinityMode>().unwrap(),
+        AntiAffinityMode::Required
+    );
+    assert!("strict".parse::<AntiAffinityMode>().is_err());
+}
+
+#[test]
+fn test_zone_warning() {
+    use k8s_openapi::api::core::v1::Node;
+
+    let node = |zone: Option<&str>| Node {
+        metadata: k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta {
+            labels: zone.map(|z| {
+                [("topology.kubernetes.io/zone".to_string(), z.to_string())]
+                    .into_iter()
+                    .collect()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let nodes = vec![
+        node(Some("a")),
+        node(Some("b")),
+        node(Some("a")),
+        node(None),
+    ];
+    assert_eq!(KubernetesValidator::count_zones(&nodes), 2);
+
+    assert!(KubernetesValidator::zone_warning(2, 3).is_some());
+    assert!(KubernetesValidator::zone_warning(3, 3).is_none());
+    assert!(KubernetesValidator::zone_warning(0, 1).is_some());
+}
+
+#[test]
+fn test_placement_conf_from_toml() {
+    let conf: ClusterConf = toml::from_str(
+        r#"
+[client.kubernetes.master.placement]
+preset = "zone-aware"
+anti_affinity = "required"
+
+[[client.kubernetes.worker.placement.topology_spread]]
+topology_key = "kubernetes.io/hostname"
+when_unsatisfiable = "ScheduleAnyway"
+"#,
+    )
+    .unwrap();
+
+    let kube = conf.client.kubernetes.unwrap();
+    let master = kube.master.placement.unwrap();
+    assert_eq!(master.preset.as_deref(), Some("zone-aware"));
+    assert_eq!(master.anti_affinity.as_deref(), Some("required"));
+
+    let worker = kube.worker.placement.unwrap();
+    assert_eq!(worker.topology_spread[0].max_skew, 1);
+    assert_eq!(
+        worker.topology_spread[0].when_unsatisfiable,
+        "ScheduleAnyway"
+    );
+}
+
+// ============================================================================
+// Tests for Structured Output
+// ============================================================================
+
+fn sample_cluster_status() -> curvine_kube::domain::cluster::descriptor::ClusterStatus {
+    use curvine_kube::domain::cluster::descriptor::{ClusterStatus, PodStatus, StatefulSetStatus};
+
+    ClusterStatus {
+        cluster_id: "test-cluster".to_string(),
+        namespace: "curvine".to_string(),
+        master: Some(StatefulSetStatus {
+            name: "test-cluster-master".to_string(),
+            replicas: 3,
+            ready_replicas: 2,
+        }),
+        worker: None,
+        service: None,
+        configmap: None,
+        pods: vec![PodStatus {
+            name: "test-cluster-master-0".to_string(),
+            component: Some("master".to_string()),
+            phase: Some("Running".to_string()),
+            ready: true,
+            restarts: 1,
+            node: Some("node-a".to_string()),
+            pod_ip: Some("10.0.0.5".to_string()),
+            created_at: Some("2025-01-01T00:00:00+00:00".to_string()),
+            ..Default::default()
+        }],
+    }
+}
+
+#[test]
+fn test_status_json_output_is_versioned() {
+    use curvine_kube::cli::display::output::{
+        render_structured, OutputFormat, OUTPUT_SCHEMA_VERSION,
+    };
+
+    let status = sample_cluster_status();
+    let json = render_structured(OutputFormat::Json, "ClusterStatus", &status)
+        .unwrap()
+        .unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(value["schema_version"], OUTPUT_SCHEMA_VERSION);
+    assert_eq!(value["kind"], "ClusterStatus");
+    assert_eq!(value["cluster_id"], "test-cluster");
+    assert_eq!(value["master"]["ready_replicas"], 2);
+    assert!(value["worker"].is_null());
+    assert_eq!(value["pods"][0]["node"], "node-a");
+    assert_eq!(value["pods"][0]["restarts"], 1);
+}
+
+#[test]
+fn test_list_yaml_output_is_versioned() {
+    use curvine_kube::cli::display::output::{render_structured, ItemList, OutputFormat};
+    use curvine_kube::domain::cluster::descriptor::ClusterInfo;
+
+    let clusters = vec![ClusterInfo {
+        cluster_id: "test-cluster".to_string(),
+        namespace: "default".to_string(),
+        master_replicas: 3,
+        master_ready: 3,
+        worker_replicas: 2,
+        worker_ready: 1,
+        context: None,
+        image: Some("docker.io/curvine:v1.0.0".to_string()),
+        version: Some("v1.0.0".to_string()),
+        health: curvine_kube::domain::cluster::descriptor::ClusterHealth::Degraded,
+    }];
+    let yaml = render_structured(
+        OutputFormat::Yaml,
+        "ClusterList",
+        &ItemList { items: &clusters },
+    )
+    .unwrap()
+    .unwrap();
+    let value: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+
+    assert_eq!(value["schema_version"].as_str(), Some("curvine-kube/v1"));
+    assert_eq!(value["kind"].as_str(), Some("ClusterList"));
+    assert_eq!(value["items"][0]["worker_ready"].as_u64(), Some(1));
+    assert_eq!(value["items"][0]["health"].as_str(), Some("Degraded"));
+
+    assert!(
+        render_structured(OutputFormat::Wide, "ClusterList", &clusters)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_format_age() {
+    use curvine_kube::cli::display::output::format_age;
+
+    let now = chrono::DateTime::parse_from_rfc3339("2025-01-03T01:00:00+00:00")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    assert_eq!(format_age(Some("2025-01-03T00:59:30+00:00"), now), "30s");
+    assert_eq!(format_age(Some("2025-01-03T00:48:00+00:00"), now), "12m");
+    assert_eq!(format_age(Some("2025-01-02T22:00:00+00:00"), now), "3h");
+    assert_eq!(format_age(Some("2025-01-01T00:00:00+00:00"), now), "2d");
+    assert_eq!(format_age(None, now), "<unknown>");
+}
+
+// ============================================================================
+// Tests for Pod Status Detail
+// ============================================================================
+
+fn master_pod(name: &str) -> curvine_kube::domain::cluster::descriptor::PodStatus {
+    curvine_kube::domain::cluster::descriptor::PodStatus {
+        name: name.to_string(),
+        component: Some("master".to_string()),
+        phase: Some("Running".to_string()),
+        ready: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_render_pod_details() {
+    use curvine_kube::cli::display::TableRenderer;
+    use curvine_kube::domain::cluster::descriptor::{PodEvent, PodStatus, PvcStatus};
+
+    let mut status = sample_cluster_status();
+    status.pods = vec![
+        master_pod("test-cluster-master-0"),
+        PodStatus {
+            name: "test-cluster-worker-0".to_string(),
+            component: Some("worker".to_string()),
+            phase: Some("Pending".to_string()),
+            pvcs: vec![PvcStatus {
+                name: "data-test-cluster-worker-0".to_string(),
+                phase: Some("Pending".to_string()),
+            }],
+            warnings: vec![PodEvent {
+                reason: Some("FailedScheduling".to_string()),
+                message: Some("0/3 nodes are available".to_string()),
+                count: 4,
+                last_seen: None,
+            }],
+            unavailable: vec!["PVC data-test-cluster-worker-1 not checked: forbidden".to_string()],
+            ..Default::default()
+        },
+    ];
+    // A lookup failing for every pod is reported once
+    for pod in &mut status.pods {
+        pod.unavailable
+            .push("Warning events not listed: forbidden".to_string());
+    }
+
+    let output = TableRenderer::new().render_pod_details(&status);
+    assert_eq!(
+        output
+            .matches("Warning events not listed: forbidden")
+            .count(),
+        1
+    );
+    assert!(output.contains("PVC data-test-cluster-worker-1 not checked"));
+    assert!(output.contains("COMPONENT"));
+    assert!(output.contains("data-test-cluster-worker-0: Pending"));
+    assert!(output.contains("FailedScheduling"));
+    assert!(output.contains("0/3 nodes are available"));
+
+    let json = serde_json::to_value(&status).unwrap();
+    assert_eq!(json["pods"][0]["component"], "master");
+    assert!(json["pods"][0].get("raft_role").is_none());
+    assert_eq!(json["pods"][1]["pvcs"][0]["phase"], "Pending");
+    assert_eq!(json["pods"][1]["warnings"][0]["count"], 4);
+}
+
+// ============================================================================
+// Tests for Watch Dashboard
+// ============================================================================
+
+fn watched_pod(name: &str, phase: &str, ready: bool) -> k8s_openapi::api::core::v1::Pod {
+    use k8s_openapi::api::core::v1::{ContainerStatus, Pod, PodStatus};
+
+    let mut pod = Pod::default();
+    pod.metadata.name = Some(name.to_string());
+    pod.status = Some(PodStatus {
+        phase: Some(phase.to_string()),
+        container_statuses: Some(vec![ContainerStatus {
+            name: "cv-worker".to_string(),
+            ready,
+            ..Default::default()
+        }]),
+        ..Default::default()
+    });
+    pod
+}
+
+#[test]
+fn test_dashboard_tracks_pod_transitions() {
+    use curvine_kube::cli::display::dashboard::Dashboard;
+
+    let now = chrono::Utc::now();
+    let mut dashboard = Dashboard::new("test-cluster".to_string(), "default".to_string());
+
+    // Pods from the initial listing are not reported as transitions
+    dashboard.apply_pod(&watched_pod("test-cluster-worker-0", "Running", true), now);
+    dashboard.pods_synced();
+    let output = dashboard.render(now);
+    assert!(output.contains("test-cluster-worker-0"));
+    assert!(output.contains("none yet"));
+
+    dashboard.apply_pod(&watched_pod("test-cluster-worker-1", "Pending", false), now);
+    dashboard.apply_pod(&watched_pod("test-cluster-worker-1", "Running", true), now);
+    dashboard.delete_pod(&watched_pod("test-cluster-worker-0", "Running", true), now);
+
+    let output = dashboard.render(now);
+    assert!(output.contains("test-cluster-worker-1: created (Pending)"));
+    assert!(output.contains("test-cluster-worker-1: Pending → Running/Ready"));
+    assert!(output.contains("test-cluster-worker-0: deleted"));
+}
+
+#[test]
+fn test_dashboard_warnings_and_rollout() {
+    use curvine_kube::cli::display::dashboard::Dashboard;
+    use k8s_openapi::api::apps::v1::{StatefulSet, StatefulSetSpec, StatefulSetStatus};
+    use k8s_openapi::api::core::v1::{Event, ObjectReference};
+
+    let mut dashboard = Dashboard::new("test-cluster".to_string(), "default".to_string());
+
+    let warning = |object: &str| Event {
+        type_: Some("Warning".to_string()),
+        reason: Some("BackOff".to_string()),
+        message: Some("Back-off restarting failed container".to_string()),
+        involved_object: ObjectReference {
+            name: Some(object.to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    dashboard.apply_event(&warning("test-cluster-master-1"));
+    dashboard.apply_event(&warning("other-cluster-master-0"));
+
+    let mut statefulset = StatefulSet::default();
+    statefulset.metadata.name = Some("test-cluster-worker".to_string());
+    statefulset.spec = Some(StatefulSetSpec {
+        replicas: Some(3),
+        ..Default::default()
+    });
+    statefulset.status = Some(StatefulSetStatus {
+        replicas: 3,
+        ready_replicas: Some(3),
+        updated_replicas: Some(1),
+        current_revision: Some("rev-1".to_string()),
+        update_revision: Some("rev-2".to_string()),
+        ..Default::default()
+    });
+    assert!(Dashboard::rollout_in_progress(&statefulset));
+    dashboard.apply_statefulset(&statefulset);
+
+    let output = dashboard.render(chrono::Utc::now());
+    assert!(output.contains("test-cluster-master-1 BackOff"));
+    assert!(!output.contains("other-cluster-master-0"));
+    assert!(output.contains("In progress"));
+    assert!(output.contains("1/3"));
+
+    let status = statefulset.status.as_mut().unwrap();
+    status.updated_replicas = Some(3);
+    status.current_revision = Some("rev-2".to_string());
+    assert!(!Dashboard::rollout_in_progress(&statefulset));
+}
+
+// ============================================================================
+// Tests for Cluster Listing
+// ============================================================================
+
+fn listed_configmap(namespace: &str, name: &str) -> k8s_openapi::api::core::v1::ConfigMap {
+    let mut cm = k8s_openapi::api::core::v1::ConfigMap::default();
+    cm.metadata.namespace = Some(namespace.to_string());
+    cm.metadata.name = Some(name.to_string());
+    cm
+}
+
+fn listed_statefulset(
+    namespace: &str,
+    name: &str,
+    image: &str,
+    replicas: i32,
+    ready: i32,
+) -> k8s_openapi::api::apps::v1::StatefulSet {
+    use k8s_openapi::api::apps::v1::{StatefulSet, StatefulSetSpec, StatefulSetStatus};
+    use k8s_openapi::api::core::v1::{Container, PodSpec, PodTemplateSpec};
+
+    let mut ss = StatefulSet::default();
+    ss.metadata.namespace = Some(namespace.to_string());
+    ss.metadata.name = Some(name.to_string());
+    ss.spec = Some(StatefulSetSpec {
+        replicas: Some(replicas),
+        template: PodTemplateSpec {
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: "cv".to_string(),
+                    image: Some(image.to_string()),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        ..Default::default()
+    });
+    ss.status = Some(StatefulSetStatus {
+        replicas,
+        ready_replicas: Some(ready),
+        ..Default::default()
+    });
+    ss
+}
+
+#[test]
+fn test_summarize_clusters_across_namespaces() {
+    use curvine_kube::domain::cluster::descriptor::{summarize_clusters, ClusterHealth};
+
+    let configmaps = vec![
+        listed_configmap("team-b", "analytics-config"),
+        listed_configmap("team-a", "analytics-config"),
+        listed_configmap("team-a", "not-a-cluster"),
+    ];
+    let statefulsets = vec![
+        listed_statefulset("team-a", "analytics-master", "curvine:v1.2.0", 3, 3),
+        listed_statefulset("team-a", "analytics-worker", "curvine:v1.2.0", 2, 2),
+        listed_statefulset("team-b", "analytics-master", "curvine:v1.1.0", 3, 1),
+        listed_statefulset("team-b", "analytics-worker", "curvine:v1.1.0", 2, 0),
+    ];
+
+    let clusters = summarize_clusters(&configmaps, &statefulsets);
+    assert_eq!(clusters.len(), 2);
+
+    assert_eq!(clusters[0].namespace, "team-a");
+    assert_eq!(clusters[0].version.as_deref(), Some("v1.2.0"));
+    assert_eq!(clusters[0].health, ClusterHealth::Healthy);
+
+    assert_eq!(clusters[1].namespace, "team-b");
+    assert_eq!(clusters[1].master_ready, 1);
+    assert_eq!(clusters[1].version.as_deref(), Some("v1.1.0"));
+    assert_eq!(clusters[1].health, ClusterHealth::Degraded);
+}
+
+#[test]
+fn test_cluster_health_and_image_version() {
+    use curvine_kube::domain::cluster::descriptor::{image_version, ClusterHealth};
+
+    assert_eq!(
+        ClusterHealth::from_replicas(0, 0, 0, 0),
+        ClusterHealth::Unknown
+    );
+    assert_eq!(
+        ClusterHealth::from_replicas(0, 3, 0, 2),
+        ClusterHealth::Failed
+    );
+    assert_eq!(
+        ClusterHealth::from_replicas(3, 3, 1, 2),
+        ClusterHealth::Degraded
+    );
+
+    assert_eq!(image_version("docker.io/curvine:v1.0.0"), "v1.0.0");
+    assert_eq!(image_version("localhost:5000/curvine"), "latest");
+    assert_eq!(
+        image_version("curvine@sha256:0123456789abcdef0123"),
+        "@0123456789ab"
+    );
+}
+
+#[test]
+fn test_list_command_flags() {
+    use clap::Parser;
+    use curvine_kube::cli::CliArgs;
+
+    let args = CliArgs::try_parse_from([
+        "curvine-kube",
+        "list",
+        "-A",
+        "--contexts",
+        "prod,staging",
+        "-o",
+        "json",
+    ])
+    .unwrap();
+    match args.command {
+        curvine_kube::cli::commands::Commands::List(cmd) => {
+            assert!(cmd.all_namespaces);
+            assert_eq!(cmd.contexts, vec!["prod", "staging"]);
+        }
+        _ => panic!("expected list command"),
+    }
+
+    assert!(CliArgs::try_parse_from(["curvine-kube", "list", "-A", "-n", "default"]).is_err());
+    assert!(CliArgs::try_parse_from([
+        "curvine-kube",
+        "list",
+        "--context",
+        "a",
+        "--contexts",
+        "b,c"
+    ])
+    .is_err());
+}
+
+// ============================================================================
+// Tests for Logs Command
+// ============================================================================
+
+#[test]
+fn test_parse_duration_secs() {
+    use curvine_kube::cli::k8s::parse_duration_secs;
+
+    assert_eq!(parse_duration_secs("45s"), Ok(45));
+    assert_eq!(parse_duration_secs("10m"), Ok(600));
+    assert_eq!(parse_duration_secs("2h"), Ok(7200));
+    assert_eq!(parse_duration_secs("1d"), Ok(86400));
+    assert_eq!(parse_duration_secs("90"), Ok(90));
+    assert!(parse_duration_secs("10x").is_err());
+    assert!(parse_duration_secs("m").is_err());
+    assert!(parse_duration_secs("999999999999999999d").is_err());
+}
+
+#[test]
+fn test_select_log_pods() {
+    use curvine_kube::cli::k8s::{select_log_pods, LogComponent};
+    use curvine_kube::domain::cluster::descriptor::PodStatus;
+
+    let pod = |name: &str, component: &str| PodStatus {
+        name: name.to_string(),
+        component: Some(component.to_string()),
+        ..Default::default()
+    };
+    let pods = vec![
+        pod("c-master-0", "master"),
+        pod("c-master-1", "master"),
+        pod("c-worker-0", "worker"),
+        pod("c-worker-10", "worker"),
+    ];
+
+    assert_eq!(select_log_pods(&pods, None, None).len(), 4);
+    assert_eq!(
+        select_log_pods(&pods, Some(LogComponent::Worker), None).len(),
+        2
+    );
+
+    let selected = select_log_pods(&pods, None, Some(0));
+    let names: Vec<&str> = selected.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["c-master-0", "c-worker-0"]);
+
+    let selected = select_log_pods(&pods, Some(LogComponent::Worker), Some(1));
+    assert!(selected.is_empty());
+}
+
+#[test]
+fn test_logs_command_flags() {
+    use clap::Parser;
+    use curvine_kube::cli::commands::Commands;
+    use curvine_kube::cli::CliArgs;
+
+    let args = CliArgs::try_parse_from([
+        "curvine-kube",
+        "logs",
+        "-c",
+        "my-cluster",
+        "--component",
+        "master",
+        "--pod",
+        "2",
+        "-f",
+        "--since",
+        "10m",
+        "--previous",
+    ])
+    .unwrap();
+    match args.command {
+        Commands::Logs(cmd) => {
+            assert_eq!(cmd.pod, Some(2));
+            assert!(cmd.follow && cmd.previous);
+            assert_eq!(cmd.since, Some(600));
+        }
+        _ => panic!("expected logs command"),
+    }
+
+    assert!(
+        CliArgs::try_parse_from(["curvine-kube", "logs", "-c", "x", "--since", "soon"]).is_err()
+    );
+}
+
+// ============================================================================
+// Tests for Cv Exec
+// ============================================================================
+
+#[test]
+fn test_exec_exit_code_from_status() {
+    use curvine_kube::infrastructure::kubernetes::exec::exit_code_from_status;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Status;
+
+    let status = |json: serde_json::Value| serde_json::from_value::<Status>(json).unwrap();
+
+    assert_eq!(
+        exit_code_from_status(&status(
+            serde_json::json!({"metadata": {}, "status": "Success"})
+        )),
+        Ok(0)
+    );
+
+    let failed = status(serde_json::json!({
+        "status": "Failure",
+        "message": "command terminated with non-zero exit code",
+        "reason": "NonZeroExitCode",
+        "details": {"causes": [{"reason": "ExitCode", "message": "3"}]}
+    }));
+    assert_eq!(exit_code_from_status(&failed), Ok(3));
+
+    let error = status(serde_json::json!({"status": "Failure", "message": "container not found"}));
+    assert_eq!(
+        exit_code_from_status(&error),
+        Err("container not found".to_string())
+    );
+}
+
+#[test]
+fn test_exec_attach_params() {
+    use curvine_kube::infrastructure::kubernetes::exec::ExecRequest;
+
+    let request = ExecRequest::new(
+        "c-master-0".to_string(),
+        Some("cv-master".to_string()),
+        vec!["/app/curvine/bin/cv".to_string(), "fs".to_string()],
+    );
+    let params = request.attach_params();
+    assert_eq!(params.container.as_deref(), Some("cv-master"));
+    assert!(params.stdout && params.stderr);
+    assert!(!params.stdin && !params.tty);
+
+    let params = request.clone().with_stdin(true).attach_params();
+    assert!(params.stdin && params.stderr && !params.tty);
+
+    let mut tty = request.clone();
+    tty.tty = true;
+    let params = tty.attach_params();
+    assert!(params.stdin && params.stdout && params.tty);
+    assert!(!params.stderr);
+}
+
+#[test]
+fn test_cv_command_args() {
+    use clap::Parser;
+    use curvine_kube::cli::commands::Commands;
+    use curvine_kube::cli::CliArgs;
+
+    let args = CliArgs::try_parse_from([
+        "curvine-kube",
+        "cv",
+        "-c",
+        "my-cluster",
+        "--",
+        "fs",
+        "ls",
+        "-l",
+        "/",
+    ])
+    .unwrap();
+    match args.command {
+        Commands::Cv(cmd) => {
+            assert_eq!(cmd.cluster_id.as_deref(), Some("my-cluster"));
+            assert_eq!(cmd.pod, None);
+            assert!(!cmd.stdin);
+            assert_eq!(cmd.args, vec!["fs", "ls", "-l", "/"]);
+        }
+        _ => panic!("expected cv command"),
+    }
+
+    let args =
+        CliArgs::try_parse_from(["curvine-kube", "cv", "-c", "c", "-i", "--", "shell"]).unwrap();
+    let Commands::Cv(cmd) = args.command else {
+        panic!("expected cv command");
+    };
+    assert!(cmd.stdin);
+}
+
+// ============================================================================
+// Tests for Port Forward
+// ============================================================================
+
+fn pod_with_ports(container: &str, ports: &[(&str, i32)]) -> k8s_openapi::api::core::v1::Pod {
+    use k8s_openapi::api::core::v1::{Container, ContainerPort, Pod, PodSpec};
+
+    Pod {
+        spec: Some(PodSpec {
+            containers: vec![Container {
+                name: container.to_string(),
+                ports: Some(
+                    ports
+                        .iter()
+                        .map(|(name, port)| ContainerPort {
+                            name: Some(name.to_string()),
+                            container_port: *port,
+                            ..Default::default()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_resolve_port_mappings() {
+    use curvine_kube::infrastructure::kubernetes::portforward::{
+        resolve_port_mappings, PortMapping,
+    };
+
+    let pod = pod_with_ports("cv-master", &[("rpc", 8995), ("web", 9000)]);
+    let mappings = resolve_port_mappings(
+        &pod,
+        "cv-master",
+        &["web".to_string(), "rpc:18995".to_string()],
+    )
+    .unwrap();
+    assert_eq!(
+        mappings,
+        vec![
+            PortMapping {
+                name: "web".to_string(),
+                local: 9000,
+                remote: 9000,
+            },
+            PortMapping {
+                name: "rpc".to_string(),
+                local: 18995,
+                remote: 8995,
+            },
+        ]
+    );
+
+    let err = resolve_port_mappings(&pod, "cv-master", &["journal".to_string()]).unwrap_err();
+    assert!(err.to_string().contains("available: rpc, web"));
+    assert!(resolve_port_mappings(&pod, "cv-worker", &["web".to_string()]).is_err());
+    assert!(resolve_port_mappings(&pod, "cv-master", &["web:http".to_string()]).is_err());
+}
+
+#[test]
+fn test_port_forward_command_args() {
+    use clap::Parser;
+    use curvine_kube::cli::commands::Commands;
+    use curvine_kube::cli::k8s::LogComponent;
+    use curvine_kube::cli::CliArgs;
+
+    let args = CliArgs::try_parse_from(["curvine-kube", "port-forward", "-c", "c"]).unwrap();
+    match args.command {
+        Commands::PortForward(cmd) => {
+            assert_eq!(cmd.component, LogComponent::Master);
+            assert_eq!(cmd.ports, vec!["web"]);
+            assert_eq!(cmd.address, "127.0.0.1");
+        }
+        _ => panic!("expected port-forward command"),
+    }
+
+    let args = CliArgs::try_parse_from([
+        "curvine-kube",
+        "port-forward",
+        "-c",
+        "c",
+        "--component",
+        "worker",
+        "--ports",
+        "web,rpc:18997",
+    ])
+    .unwrap();
+    match args.command {
+        Commands::PortForward(cmd) => {
+            assert_eq!(cmd.component, LogComponent::Worker);
+            assert_eq!(cmd.ports, vec!["web", "rpc:18997"]);
+        }
+        _ => panic!("expected port-forward command"),
+    }
+}
+
+// ============================================================================
+// Tests for Diagnose Bundle
+// ============================================================================
+
+#[test]
+fn test_diagnose_redacts_sensitive_values() {
+    use curvine_kube::domain::cluster::diagnose::{
+        is_sensitive_key, redact_text, redact_value, REDACTED,
+    };
+
+    assert!(is_sensitive_key("s3.secret-key"));
+    assert!(is_sensitive_key("AWS_SECRET_ACCESS_KEY"));
+    assert!(is_sensitive_key("password"));
+    assert!(!is_sensitive_key("secretName"));
+    assert!(!is_sensitive_key("secretKeyRef"));
+    assert!(!is_sensitive_key("journal_addrs"));
+
+    let config = "[s3]\naccess_key = \"AKIA123\"\nendpoint = \"http://minio:9000\"\n";
+    let redacted = redact_text(config);
+    assert_eq!(
+        redacted,
+        "[s3]\naccess_key = \"<redacted>\"\nendpoint = \"http://minio:9000\"\n"
+    );
+
+    // Every sensitive pair of a log line, not just the first one
+    assert_eq!(
+        redact_text("ts=2025-01-01T10:00:00Z level=info password=hunter2 user=bob token=\"a b\""),
+        "ts=2025-01-01T10:00:00Z level=info password=<redacted> user=bob token=\"<redacted>\""
+    );
+    assert_eq!(
+        redact_text("GET /api status=200 Authorization: Bearer eyJhbGciOi path=/x"),
+        "GET /api status=200 Authorization: <redacted> path=/x"
+    );
+    assert_eq!(
+        redact_text(r#"{"level":"info","api_key":"k-123","msg":"ok"}"#),
+        r#"{"level":"info","api_key":"<redacted>","msg":"ok"}"#
+    );
+    assert_eq!(
+        redact_text("  secret: my secret phrase\nsecret:\n"),
+        "  secret: \"<redacted>\"\nsecret:\n"
+    );
+
+    let mut pod = serde_json::json!({
+        "spec": {"containers": [{
+            "env": [
+                {"name": "DB_PASSWORD", "value": "hunter2"},
+                {"name": "LOG_LEVEL", "value": "info"},
+                {"name": "API_TOKEN", "valueFrom": {"secretKeyRef": {"name": "s", "key": "t"}}}
+            ]
+        }]},
+        "data": {"curvine-cluster.toml": config, "token": "abc"}
+    });
+    redact_value(&mut pod);
+    let env = &pod["spec"]["containers"][0]["env"];
+    assert_eq!(env[0]["value"], REDACTED);
+    assert_eq!(env[1]["value"], "info");
+    assert_eq!(env[2]["valueFrom"]["secretKeyRef"]["name"], "s");
+    assert_eq!(pod["data"]["curvine-cluster.toml"], redacted);
+    assert_eq!(pod["data"]["token"], REDACTED);
+}
+
+#[test]
+fn test_diagnose_events_of_own_objects() {
+    use curvine_kube::domain::cluster::diagnose::filter_cluster_events;
+    use k8s_openapi::api::core::v1::Event;
+    use std::collections::BTreeSet;
+
+    let events: Vec<Event> = serde_json::from_value(serde_json::json!([
+        {"metadata": {"name": "a"}, "involvedObject": {"kind": "Pod", "name": "foo-master-0"}},
+        {"metadata": {"name": "b"}, "involvedObject": {"kind": "Pod", "name": "foo-bar-master-0"}},
+        {"metadata": {"name": "c"}, "involvedObject": {"kind": "StatefulSet", "name": "foo-worker"}},
+        {"metadata": {"name": "d"}, "involvedObject": {"kind": "Service", "name": "foo-master-0"}}
+    ]))
+    .unwrap();
+    let objects: BTreeSet<(&str, &str)> = [("Pod", "foo-master-0"), ("StatefulSet", "foo-worker")]
+        .into_iter()
+        .collect();
+
+    let names: Vec<_> = filter_cluster_events(events, &objects)
+        .into_iter()
+        .filter_map(|e| e.metadata.name)
+        .collect();
+    assert_eq!(names, vec!["a", "c"]);
+}
+
+#[test]
+fn test_diagnose_detects_problems() {
+    use curvine_kube::domain::cluster::diagnose::detect_problems;
+    use k8s_openapi::api::core::v1::{Event, PersistentVolumeClaim, Pod};
+
+    let pods: Vec<Pod> = serde_json::from_value(serde_json::json!([
+        {
+            "metadata": {"name": "test-cluster-master-1"},
+            "status": {
+                "phase": "Running",
+                "containerStatuses": [{
+                    "name": "cv-master", "image": "curvine", "imageID": "", "ready": false,
+                    "restartCount": 4,
+                    "state": {"waiting": {"reason": "CrashLoopBackOff", "message": "back-off"}},
+                    "lastState": {"terminated": {"exitCode": 137, "reason": "OOMKilled"}}
+                }]
+            }
+        },
+        {
+            "metadata": {"name": "test-cluster-master-2"},
+            "status": {
+                "phase": "Pending",
+                "conditions": [{
+                    "type": "PodScheduled", "status": "False",
+                    "message": "0/3 nodes are available"
+                }]
+            }
+        }
+    ]))
+    .unwrap();
+    let pvcs: Vec<PersistentVolumeClaim> = serde_json::from_value(serde_json::json!([
+        {"metadata": {"name": "data-test-cluster-master-0"}, "status": {"phase": "Bound"}},
+        {"metadata": {"name": "data-test-cluster-master-2"}, "status": {"phase": "Pending"}}
+    ]))
+    .unwrap();
+    let events: Vec<Event> = serde_json::from_value(serde_json::json!([
+        {"metadata": {}, "involvedObject": {}, "type": "Warning", "reason": "BackOff", "count": 7},
+        {"metadata": {}, "involvedObject": {}, "type": "Warning", "reason": "FailedScheduling"},
+        {"metadata": {}, "involvedObject": {}, "type": "Normal", "reason": "Pulled"}
+    ]))
+    .unwrap();
+
+    let problems = detect_problems(Some(&sample_cluster_status()), &pods, &pvcs, &events);
+    assert_eq!(
+        problems,
+        vec![
+            "StatefulSet test-cluster-master has 2/3 ready replicas",
+            "Pod test-cluster-master-1 container cv-master is waiting: CrashLoopBackOff (back-off)",
+            "Pod test-cluster-master-1 container cv-master was OOMKilled",
+            "Pod test-cluster-master-1 container cv-master restarted 4 time(s)",
+            "Pod test-cluster-master-2 cannot be scheduled: 0/3 nodes are available",
+            "PVC data-test-cluster-master-2 is Pending",
+            "Warning events: BackOff (7), FailedScheduling (1)",
+        ]
+    );
+}
+
+#[test]
+fn test_diagnose_without_cluster_status() {
+    use curvine_kube::domain::cluster::diagnose::{detect_problems, render_summary};
+
+    let pvcs: Vec<k8s_openapi::api::core::v1::PersistentVolumeClaim> =
+        serde_json::from_value(serde_json::json!([
+            {"metadata": {"name": "data-c-master-0"}, "status": {"phase": "Pending"}}
+        ]))
+        .unwrap();
+    let problems = detect_problems(None, &[], &pvcs, &[]);
+    assert_eq!(problems, vec!["PVC data-c-master-0 is Pending"]);
+
+    let errors = vec!["Cluster status: forbidden".to_string()];
+    let summary = render_summary("c", "prod", &problems, &errors);
+    assert!(summary.contains("Cluster:   c\n"));
+    assert!(summary.contains("PVC data-c-master-0 is Pending"));
+    assert!(summary.contains("1 item(s) could not be collected, see errors.txt"));
+}
+
+#[test]
+fn test_diagnose_bundle_archive() {
+    use curvine_kube::domain::cluster::diagnose::SupportBundle;
+    use std::io::Read;
+
+    let mut bundle = SupportBundle::new();
+    bundle.add_text("summary.txt", "No problems detected\n");
+    let configmap: k8s_openapi::api::core::v1::ConfigMap =
+        serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "name": "c-config",
+                "managedFields": [{"manager": "curvine-kube"}]
+            },
+            "data": {"password": "p"}
+        }))
+        .unwrap();
+    bundle.add_yaml("configmap.yaml", &configmap).unwrap();
+
+    let yaml = String::from_utf8(bundle.get("configmap.yaml").unwrap().to_vec()).unwrap();
+    assert!(!yaml.contains("managedFields"));
+    assert!(yaml.contains("password: <redacted>"));
+
+    let mut archive = Vec::new();
+    bundle.write_tar_gz("c-diagnose", &mut archive).unwrap();
+
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive.as_slice()));
+    let mut files = Vec::new();
+    for entry in tar.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let path = entry.path().unwrap().to_string_lossy().to_string();
+        let mut content = String::new();
+        entry.read_to_string(&mut content).unwrap();
+        files.push((path, content));
+    }
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].0, "c-diagnose/configmap.yaml");
+    assert_eq!(
+        files[1],
+        (
+            "c-diagnose/summary.txt".to_string(),
+            "No problems detected\n".to_string()
+        )
+    );
+}
+
+// ============================================================================
+// Tests for Doctor Preflight
+// ============================================================================
+
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+
+fn quantities(entries: &[(&str, &str)]) -> std::collections::BTreeMap<String, Quantity> {
+    entries
+        .iter()
+        .map(|(k, v)| (k.to_string(), Quantity(v.to_string())))
+        .collect()
+}
+
+#[test]
+fn test_doctor_parse_quantity() {
+    use curvine_kube::domain::cluster::doctor::parse_quantity;
+
+    assert_eq!(parse_quantity("500m"), Some(0.5));
+    assert_eq!(parse_quantity("2"), Some(2.0));
+    assert_eq!(parse_quantity("2Gi"), Some(2.0 * 1024f64.powi(3)));
+    assert_eq!(parse_quantity("1.5G"), Some(1.5e9));
+    assert_eq!(parse_quantity("1e3"), Some(1000.0));
+    assert_eq!(parse_quantity("10Xi"), None);
+    assert_eq!(parse_quantity("Gi"), None);
+}
+
+#[test]
+fn test_doctor_required_permissions_follow_features() {
+    use curvine_kube::domain::cluster::doctor::required_permissions;
+
+    let mut config = test_utils::create_test_kubernetes_config();
+    let resources = |config: &KubernetesConfig| {
+        required_permissions(config)
+            .into_iter()
+            .map(|p| p.resource)
+            .collect::<std::collections::BTreeSet<_>>()
+    };
+
+    let base = resources(&config);
+    assert!(base.contains("statefulsets"));
+    assert!(base.contains("configmaps"));
+    assert!(!base.contains("secrets"));
+    assert!(!base.contains("roles"));
+
+    config.rbac = Some(RbacConfig {
+        create_service_accounts: true,
+        pod_discovery: true,
+    });
+    config.tls = tls_kubernetes_config(TlsMode::SelfSigned).tls;
+    let extended = resources(&config);
+    assert!(extended.contains("serviceaccounts"));
+    assert!(extended.contains("roles"));
+    assert!(extended.contains("rolebindings"));
+    assert!(extended.contains("secrets"));
+    assert!(!extended.contains("certificates"));
+}
+
+#[test]
+fn test_doctor_requested_resources_and_quota() {
+    use curvine_kube::domain::cluster::doctor::{quota_violations, requested_resources};
+    use k8s_openapi::api::core::v1::{
+        ResourceQuota, ResourceQuotaSpec, ResourceQuotaStatus, ResourceRequirements,
+    };
+
+    let cluster_conf = test_utils::create_test_cluster_conf();
+    let mut config = test_utils::create_test_kubernetes_config();
+    config.worker.resources = Some(ResourceRequirements {
+        requests: Some(quantities(&[("cpu", "1"), ("memory", "4Gi")])),
+        limits: Some(quantities(&[("memory", "16Gi")])),
+        ..Default::default()
+    });
+
+    let requested = requested_resources(&cluster_conf, &config);
+    let gi = 1024f64.powi(3);
+    assert_eq!(requested["requests.cpu"], 3.0);
+    assert_eq!(requested["limits.memory"], 48.0 * gi);
+    assert_eq!(requested["pods"], 6.0);
+    // 2 volumes per master, one per non-MEM data dir per worker
+    assert_eq!(requested["persistentvolumeclaims"], 15.0);
+    assert_eq!(requested["requests.storage"], 150.0 * gi);
+
+    let quota = ResourceQuota {
+        metadata: k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta {
+            name: Some("team".to_string()),
+            ..Default::default()
+        },
+        spec: Some(ResourceQuotaSpec {
+            hard: Some(quantities(&[("cpu", "4"), ("limits.memory", "64Gi")])),
+            ..Default::default()
+        }),
+        status: Some(ResourceQuotaStatus {
+            hard: Some(quantities(&[("cpu", "4"), ("limits.memory", "64Gi")])),
+            used: Some(quantities(&[("cpu", "2"), ("limits.memory", "8Gi")])),
+        }),
+    };
+    let violations = quota_violations(&quota, &requested);
+    assert_eq!(violations.len(), 1, "{:?}", violations);
+    assert!(violations[0].starts_with("cpu in ResourceQuota team"));
+    assert!(violations[0].contains("need 3, 2 of 4 left"));
+}
+
+#[test]
+fn test_doctor_limit_range_violations() {
+    use curvine_kube::domain::cluster::doctor::limit_range_violations;
+    use k8s_openapi::api::core::v1::{
+        LimitRange, LimitRangeItem, LimitRangeSpec, ResourceRequirements,
+    };
+
+    let limit_range = LimitRange {
+        spec: Some(LimitRangeSpec {
+            limits: vec![LimitRangeItem {
+                type_: "Container".to_string(),
+                max: Some(quantities(&[("memory", "8Gi")])),
+                min: Some(quantities(&[("cpu", "100m")])),
+                ..Default::default()
+            }],
+        }),
+        ..Default::default()
+    };
+    let master = ResourceRequirements {
+        requests: Some(quantities(&[("cpu", "500m"), ("memory", "2Gi")])),
+        ..Default::default()
+    };
+    let worker = ResourceRequirements {
+        requests: Some(quantities(&[("cpu", "50m")])),
+        limits: Some(quantities(&[("memory", "16Gi")])),
+        ..Default::default()
+    };
+
+    let violations = limit_range_violations(
+        &limit_range,
+        &[("master", Some(&master)), ("worker", Some(&worker))],
+    );
+    assert_eq!(violations.len(), 2, "{:?}", violations);
+    assert!(violations[0].starts_with("worker memory"));
+    assert!(violations[1].starts_with("worker cpu"));
+}
+
+#[test]
+fn test_doctor_schedulable_nodes() {
+    use curvine_kube::domain::cluster::doctor::{schedulable_nodes, tolerates};
+    use k8s_openapi::api::core::v1::{Node, NodeSpec, Taint, Toleration};
+
+    let node = |name: &str, labels: &[(&str, &str)], taints: Vec<Taint>, cordoned: bool| Node {
+        metadata: k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta {
+            name: Some(name.to_string()),
+            labels: Some(
+                labels
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            ..Default::default()
+        },
+        spec: Some(NodeSpec {
+            taints: Some(taints),
+            unschedulable: Some(cordoned),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let dedicated = Taint {
+        key: "dedicated".to_string(),
+        value: Some("curvine".to_string()),
+        effect: "NoSchedule".to_string(),
+        ..Default::default()
+    };
+    let nodes = vec![
+        node("a", &[("disk", "ssd")], vec![], false),
+        node("b", &[("disk", "ssd")], vec![dedicated.clone()], false),
+        node("c", &[("disk", "ssd")], vec![], true),
+        node("d", &[("disk", "hdd")], vec![], false),
+    ];
+    let names = |matched: Vec<&Node>| {
+        matched
+            .into_iter()
+            .filter_map(|n| n.metadata.name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let selector: HashMap<String, String> = [("disk".to_string(), "ssd".to_string())].into();
+    assert_eq!(
+        names(schedulable_nodes(&nodes, Some(&selector), &[])),
+        ["a"]
+    );
+
+    let toleration = Toleration {
+        key: Some("dedicated".to_string()),
+        operator: Some("Equal".to_string()),
+        value: Some("curvine".to_string()),
+        effect: Some("NoSchedule".to_string()),
+        ..Default::default()
+    };
+    assert!(tolerates(&toleration, &dedicated));
+    assert_eq!(
+        names(schedulable_nodes(&nodes, Some(&selector), &[toleration])),
+        ["a", "b"]
+    );
+
+    let exists_all = Toleration {
+        operator: Some("Exists".to_string()),
+        ..Default::default()
+    };
+    assert!(tolerates(&exists_all, &dedicated));
+    let wrong_value = Toleration {
+        key: Some("dedicated".to_string()),
+        value: Some("other".to_string()),
+        ..Default::default()
+    };
+    assert!(!tolerates(&wrong_value, &dedicated));
+}
+
+#[test]
+fn test_doctor_report_rendering() {
+    use curvine_kube::cli::display::TableRenderer;
+    use curvine_kube::domain::cluster::doctor::{CheckStatus, DoctorReport};
+
+    let mut report = DoctorReport::default();
+    report.push("API server", CheckStatus::Pass, "Kubernetes v1.30.0");
+    report.push(
+        "ResourceQuota",
+        CheckStatus::Warn,
+        "Cannot list ResourceQuotas",
+    );
+    assert!(!report.has_failures());
+    report.push(
+        "Namespace",
+        CheckStatus::Fail,
+        "Namespace curvine does not exist",
+    );
+    assert!(report.has_failures());
+
+    let rendered = TableRenderer::new().render_doctor_report(&report);
+    assert!(rendered.contains("Namespace curvine does not exist"));
+    assert!(rendered.ends_with("1 passed, 1 warnings, 1 failed"));
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["checks"][2]["status"], "fail");
+}
+
+// ============================================================================
+// Tests for Offline Validation
+// ============================================================================
+
+#[test]
+fn test_validate_parse_error_has_line() {
+    use curvine_kube::domain::cluster::lint::parse_cluster_conf;
+
+    let source = "[master]\nmeta_dir = \"/data/meta\"\nrpc_port = \"x\"\n";
+    let diagnostic = parse_cluster_conf("c.toml", source).unwrap_err();
+    assert_eq!(diagnostic.line, Some(3));
+    assert!(diagnostic.message.contains("expected u16"));
+    assert!(diagnostic.to_string().starts_with("c.toml:3: error: "));
+}
+
+#[test]
+fn test_validate_locate_keys() {
+    use curvine_kube::domain::cluster::lint::locate;
+
+    let source = r#"
+[master]
+rpc_port = 8995
+
+[worker]
+data_dir = [
+  "[MEM:10GB]/data/mem",
+  "[SSD:100GB]/data/ssd",
+]
+
+[client.kubernetes]
+storage.master_size = "10Gi"
+
+[client.kubernetes.master]
+replicas = 3
+"#;
+    assert_eq!(locate(source, "master.rpc_port", None), Some(3));
+    assert_eq!(
+        locate(source, "worker.data_dir[1]", Some("[SSD:100GB]/data/ssd")),
+        Some(8)
+    );
+    assert_eq!(
+        locate(source, "client.kubernetes.storage.master_size", None),
+        Some(12)
+    );
+    assert_eq!(
+        locate(source, "client.kubernetes.master.replicas", None),
+        Some(15)
+    );
+    assert_eq!(locate(source, "worker.rpc_port", None), None);
+}
+
+#[test]
+fn test_validate_reports_offline_problems() {
+    use curvine_kube::domain::cluster::lint::{lint, Severity, COMMAND_LINE};
+
+    let source = r#"[master]
+web_port = 8995
+
+[journal]
+journal_dir = "testing/meta"
+
+[worker]
+data_dir = ["[MEM:1MB]/data/mem", "[NVME]/data/nvme"]
+"#;
+    let mut cluster_conf = test_utils::create_test_cluster_conf();
+    cluster_conf.master.web_port = 8995;
+    cluster_conf.journal.journal_dir = cluster_conf.master.meta_dir.clone();
+    cluster_conf.worker.data_dir = vec![
+        "[MEM:1MB]/data/mem".to_string(),
+        "[NVME]/data/nvme".to_string(),
+    ];
+    let mut kube_config = test_utils::create_test_kubernetes_config();
+    kube_config.master.replicas = 2;
+    kube_config.storage.as_mut().unwrap().worker_size = Some("lots".to_string());
+    let properties: HashMap<String, String> = [
+        ("kubernetes.worker.cpu".to_string(), "two".to_string()),
+        (
+            "kubernetes.storage.worker-size".to_string(),
+            "lots".to_string(),
+        ),
+    ]
+    .into();
+
+    let report = lint("c.toml", source, &properties, &cluster_conf, &kube_config);
+    let find = |key: &str| {
+        report
+            .diagnostics
+            .iter()
+            .find(|d| d.key.as_deref() == Some(key))
+            .unwrap_or_else(|| panic!("no diagnostic for {}: {:#?}", key, report.diagnostics))
+    };
+
+    assert!(report.has_errors());
+    assert_eq!(
+        find("client.kubernetes.master.replicas").severity,
+        Severity::Error
+    );
+    assert!(find("master.web_port")
+        .message
+        .contains("already used by master.rpc_port"));
+    assert_eq!(find("master.web_port").line, Some(2));
+    // master meta_dir and journal_dir collide
+    assert!(find("journal.journal_dir")
+        .message
+        .contains("both mounted at"));
+    assert_eq!(find("worker.data_dir[1]").severity, Severity::Warning);
+    assert_eq!(find("worker.data_dir[1]").line, Some(8));
+    assert!(find("worker.data_dir")
+        .message
+        .contains("less than worker block size"));
+    assert_eq!(find("-Dkubernetes.worker.cpu").file, COMMAND_LINE);
+    // Quantity errors from -D overrides point at the property, not the file
+    let size = find("-Dkubernetes.storage.worker-size");
+    assert_eq!(size.file, COMMAND_LINE);
+    assert_eq!(size.line, None);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert_eq!(json["diagnostics"][0]["severity"], "error");
+}
+
+#[test]
+fn test_validate_clean_config() {
+    use curvine_kube::domain::cluster::lint::lint;
+
+    let cluster_conf = test_utils::create_test_cluster_conf();
+    let kube_config = test_utils::create_test_kubernetes_config();
+    let report = lint("c.toml", "", &HashMap::new(), &cluster_conf, &kube_config);
+    assert!(!report.has_errors(), "{:#?}", report.diagnostics);
+}
+
+#[test]
+fn test_validate_has_no_cluster_flags() {
+    use clap::Parser;
+    use curvine_kube::cli::commands::Commands;
+    use curvine_kube::cli::CliArgs;
+
+    let args = CliArgs::try_parse_from([
+        "curvine-kube",
+        "validate",
+        "--config-file",
+        "c.toml",
+        "-c",
+        "demo",
+        "-Dkubernetes.master.replicas=3",
+    ])
+    .unwrap();
+    let Commands::Validate(cmd) = args.command else {
+        panic!("expected validate command");
+    };
+    assert_eq!(cmd.args.cluster_id.as_deref(), Some("demo"));
+
+    for flag in ["--kubeconfig=k", "--context=c", "--skip-preflight"] {
+        assert!(
+            CliArgs::try_parse_from(["curvine-kube", "validate", flag]).is_err(),
+            "validate accepted {}",
+            flag
+        );
+        assert!(CliArgs::try_parse_from(["curvine-kube", "deploy", flag]).is_ok());
+    }
+    assert!(CliArgs::try_parse_from(["curvine-kube", "doctor", "--skip-preflight"]).is_err());
+}
+
+// ============================================================================
+// Tests for Dynamic Property Registry
+// ============================================================================
+
+#[test]
+fn test_property_registry_covers_dynamic_keys() {
+    use curvine_kube::domain::config::properties::find_property;
+
+    let source = include_str!("../src/domain/config/dynamic.rs");
+    let keys: Vec<&str> = source
+        .split("configs.get(\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .collect();
+    assert!(keys.len() > 30);
+    for key in keys {
+        assert!(find_property(key).is_some(), "{} is not registered", key);
+    }
+    assert!(find_property("kubernetes.master.env.JAVA_OPTS").is_some());
+    assert!(find_property("kubernetes.master.env.").is_none());
+    assert!(find_property("kubernetes.master.env.1BAD").is_none());
+}
+
+#[test]
+fn test_property_registry_suggestions() {
+    use curvine_kube::domain::config::properties::{check_property, suggest_key};
+
+    let base = KubernetesConfig::default();
+    assert_eq!(
+        suggest_key("kubernetes.worker.mem"),
+        Some("kubernetes.worker.memory")
+    );
+    assert_eq!(
+        suggest_key("kubernetes.master.replica"),
+        Some("kubernetes.master.replicas")
+    );
+    assert_eq!(
+        suggest_key("kubernetes.storage.sizee"),
+        Some("kubernetes.storage.size")
+    );
+    assert_eq!(suggest_key("spark.executor.memory"), None);
+
+    let error = check_property("kubernetes.worker.mem", "4Gi", "deploy", &base).unwrap_err();
+    assert!(error.contains("did you mean 'kubernetes.worker.memory'"));
+    let error = check_property("something.else", "1", "deploy", &base).unwrap_err();
+    assert!(error.contains("config keys"));
+}
+
+#[test]
+fn test_property_registry_rejects_invalid_values() {
+    use curvine_kube::domain::config::properties::{check_property, validate_properties};
+
+    let base = KubernetesConfig::default();
+    assert!(check_property("kubernetes.master.replicas", "3", "deploy", &base).is_ok());
+    assert!(check_property("kubernetes.master.cpu", "1.5", "deploy", &base).is_ok());
+    assert!(check_property("kubernetes.worker.memory", "512Mi", "update", &base).is_ok());
+    assert!(check_property("kubernetes.service.type", "NodePort", "deploy", &base).is_ok());
+    assert!(check_property("kubernetes.master.labels", "a=b,c=d", "deploy", &base).is_ok());
+    assert!(check_property(
+        "kubernetes.service.external-ips",
+        "10.0.0.1, ::1",
+        "deploy",
+        &base
+    )
+    .is_ok());
+
+    assert!(check_property("kubernetes.worker.memory", "4GB", "deploy", &base).is_err());
+    assert!(check_property("kubernetes.master.cpu", "-1", "deploy", &base).is_err());
+    assert!(check_property("kubernetes.image.pull-policy", "always", "deploy", &base).is_err());
+    assert!(check_property("kubernetes.master.labels", "a", "deploy", &base).is_err());
+    assert!(check_property("kubernetes.service.external-ips", "host", "deploy", &base).is_err());
+    assert!(check_property(
+        "kubernetes.master.pod-template",
+        "/no/such.yaml",
+        "deploy",
+        &base
+    )
+    .is_err());
+
+    // PVC templates and the Raft group size are fixed after deploy
+    let error = check_property("kubernetes.storage.size", "10Gi", "update", &base).unwrap_err();
+    assert!(error.contains("not supported by update"));
+    assert!(check_property("kubernetes.master.replicas", "5", "update", &base).is_err());
+    // Path keys below deploy-only fields follow the same rule
+    let error =
+        check_property("kubernetes.storage.master_size", "10Gi", "update", &base).unwrap_err();
+    assert!(error.contains("not supported by update (applies to: deploy)"));
+    assert!(check_property("kubernetes.worker.storage_class", "fast", "update", &base).is_err());
+    assert!(check_property("kubernetes.storage.master_size", "10Gi", "deploy", &base).is_ok());
+
+    let properties: HashMap<String, String> = [
+        ("kubernetes.master.replicas".to_string(), "abc".to_string()),
+        ("kubernetes.worker.mem".to_string(), "4Gi".to_string()),
+        ("kubernetes.worker.replicas".to_string(), "4".to_string()),
+    ]
+    .into();
+    let error = validate_properties(&properties, "deploy", &base)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("-Dkubernetes.master.replicas: 'abc' is not a non-negative integer"));
+    assert!(error.contains("-Dkubernetes.worker.mem: unknown key"));
+    assert!(!error.contains("kubernetes.worker.replicas"));
+}
+
+#[test]
+fn test_property_registry_serializes_types() {
+    use curvine_kube::domain::config::properties::find_property;
+
+    let spec = find_property("kubernetes.image.pull-policy").unwrap();
+    let json = serde_json::to_value(spec).unwrap();
+    assert_eq!(json["type"], "Always|IfNotPresent|Never");
+    assert_eq!(json["commands"], serde_json::json!(["deploy", "update"]));
+}
+
+#[tokio::test]
+async fn test_config_keys_structured_output() {
+    use clap::Parser;
+    use curvine_kube::cli::CliArgs;
+
+    for format in ["json", "yaml"] {
+        let args =
+            CliArgs::try_parse_from(["curvine-kube", "config", "keys", "-o", format]).unwrap();
+        let curvine_kube::cli::commands::Commands::Config(cmd) = args.command else {
+            panic!("expected config command");
+        };
+        cmd.execute().await.unwrap();
+    }
+}
+
+// ============================================================================
+// Tests for Generic Config Paths
+// ============================================================================
+
+#[test]
+fn test_config_path_sets_structured_fields() {
+    use curvine_kube::domain::config::paths::set_str;
+
+    let mut config = test_utils::create_test_kubernetes_config();
+    set_str(
+        &mut config,
+        "master.tolerations",
+        r#"[{"key": "dedicated", "operator": "Equal", "value": "curvine", "effect": "NoSchedule"}]"#,
+    )
+    .unwrap();
+    set_str(
+        &mut config,
+        "master.affinity.nodeAffinity.requiredDuringSchedulingIgnoredDuringExecution.nodeSelectorTerms",
+        "[{matchExpressions: [{key: disk, operator: In, values: [ssd]}]}]",
+    )
+    .unwrap();
+    set_str(&mut config, "worker.anti-affinity", "true").unwrap();
+    set_str(&mut config, "worker.host-path-storage./data", "/mnt/data").unwrap();
+    set_str(&mut config, "worker.labels.team", "storage").unwrap();
+    set_str(&mut config, "service.session-affinity", "ClientIP").unwrap();
+    set_str(
+        &mut config,
+        "service.load-balancer-source-ranges",
+        "[10.0.0.0/8]",
+    )
+    .unwrap();
+    set_str(&mut config, "cluster_domain", "corp.local").unwrap();
+    set_str(&mut config, "master.image", "1.0").unwrap();
+    set_str(&mut config, "master.resources.limits.cpu", "2").unwrap();
+
+    let toleration = &config.master.tolerations[0];
+    assert_eq!(toleration.key.as_deref(), Some("dedicated"));
+    assert_eq!(toleration.effect.as_deref(), Some("NoSchedule"));
+    let terms = &config
+        .master
+        .affinity
+        .as_ref()
+        .and_then(|a| a.node_affinity.as_ref())
+        .and_then(|n| {
+            n.required_during_scheduling_ignored_during_execution
+                .as_ref()
+        })
+        .unwrap()
+        .node_selector_terms;
+    assert_eq!(terms[0].match_expressions.as_ref().unwrap()[0].key, "disk");
+    assert!(config.worker.anti_affinity);
+    assert_eq!(
+        config.worker.host_path_storage.as_ref().unwrap()["/data"],
+        "/mnt/data"
+    );
+    assert_eq!(config.worker.labels["team"], "storage");
+    assert_eq!(config.service.session_affinity.as_deref(), Some("ClientIP"));
+    assert_eq!(
+        config.service.load_balancer_source_ranges,
+        vec!["10.0.0.0/8"]
+    );
+    assert_eq!(config.cluster_domain, "corp.local");
+    assert_eq!(config.master.image, "1.0");
+    let limits = config.master.resources.as_ref().unwrap().limits.as_ref();
+    assert_eq!(limits.unwrap()["cpu"].0, "2");
+
+    // List entries are addressed by index
+    set_str(&mut config, "master.tolerations.0.value", "other").unwrap();
+    assert_eq!(config.master.tolerations[0].value.as_deref(), Some("other"));
+}
+
+#[test]
+fn test_config_path_rejects_unknown_and_invalid() {
+    use curvine_kube::domain::config::paths::{set_str, PathError};
+
+    let mut config = test_utils::create_test_kubernetes_config();
+    assert_eq!(
+        set_str(&mut config, "master.bogus", "1"),
+        Err(PathError::Unknown(
+            "unknown field 'bogus' in 'master'".to_string()
+        ))
+    );
+    assert!(matches!(
+        set_str(&mut config, "master.affinity.nodeaffinity", "{}"),
+        Err(PathError::Unknown(_))
+    ));
+    assert!(matches!(
+        set_str(&mut config, "worker.anti-affinity", "maybe"),
+        Err(PathError::Invalid(_))
+    ));
+    assert!(matches!(
+        set_str(&mut config, "master.tolerations.3", "{}"),
+        Err(PathError::Invalid(_))
+    ));
+    assert!(!config.worker.anti_affinity);
+}
+
+#[test]
+fn test_config_path_dynamic_properties() {
+    use curvine_kube::domain::config::properties::check_property;
+    use curvine_kube::domain::config::{apply_config_paths, apply_to_kube_config};
+
+    let base = KubernetesConfig::default();
+    assert!(check_property("kubernetes.cluster-domain", "corp.local", "deploy", &base).is_ok());
+    assert!(check_property("kubernetes.worker.tolerations", "[]", "update", &base).is_ok());
+    let error =
+        check_property("kubernetes.worker.anti-affinity", "maybe", "deploy", &base).unwrap_err();
+    assert!(error.contains("worker.anti-affinity"));
+    let error = check_property("kubernetes.worker.bogus", "1", "deploy", &base).unwrap_err();
+    assert!(error.contains("unknown field 'bogus' in 'worker'"));
+
+    // List indices resolve against the configuration the keys apply to
+    let toleration = "kubernetes.master.tolerations.1.key";
+    assert!(check_property(toleration, "dedicated", "update", &base).is_err());
+    let mut from_file = KubernetesConfig::default();
+    from_file.master.tolerations = serde_json::from_value(serde_json::json!([
+        {"key": "gpu", "operator": "Exists"}
+    ]))
+    .unwrap();
+    assert!(check_property(toleration, "dedicated", "update", &from_file).is_ok());
+
+    let properties: HashMap<String, String> = [
+        (
+            "kubernetes.worker.priority-class".to_string(),
+            "high".to_string(),
+        ),
+        (
+            "kubernetes.worker.priority_class".to_string(),
+            "low".to_string(),
+        ),
+        (
+            "kubernetes.service.load-balancer-source-ranges".to_string(),
+            "[10.0.0.0/8, 192.168.0.0/16]".to_string(),
+        ),
+    ]
+    .into();
+    let mut config = test_utils::create_test_kubernetes_config();
+    apply_to_kube_config(&properties, &mut config);
+    apply_config_paths(&properties, &mut config).unwrap();
+    assert_eq!(config.service.load_balancer_source_ranges.len(), 2);
+    // Registered keys are applied by apply_to_kube_config, paths afterwards
+    assert_eq!(config.worker.priority_class.as_deref(), Some("low"));
+}
+
+#[test]
+fn test_config_path_toml_overrides() {
+    use curvine_kube::domain::config::paths::apply_conf_overrides;
+
+    let conf: ClusterConf = toml::from_str(
+        r#"
+[client.kubernetes]
+cluster_id = "paths"
+cluster_domain = "corp.local"
+interpolate_pod_templates = true
+
+[client.kubernetes.master]
+replicas = 3
+tolerations = [{ key = "dedicated", operator = "Exists", effect = "NoSchedule" }]
+
+[client.kubernetes.master.resources.limits]
+cpu = 2
+memory = "4Gi"
+
+[client.kubernetes.worker]
+use_statefulset = true
+anti_affinity = true
+
+[client.kubernetes.service]
+load_balancer_source_ranges = ["10.0.0.0/8"]
+"#,
+    )
+    .unwrap();
+    let kube_conf = conf.client.kubernetes.as_ref().unwrap();
+    let mut config = test_utils::create_test_kubernetes_config();
+    let unknown = apply_conf_overrides(kube_conf, &mut config).unwrap();
+
+    assert_eq!(unknown, vec!["client.kubernetes.worker.use_statefulset"]);
+    assert_eq!(config.cluster_domain, "corp.local");
+    assert!(config.interpolate_pod_templates);
+    assert_eq!(
+        config.master.tolerations[0].operator.as_deref(),
+        Some("Exists")
+    );
+    let limits = config.master.resources.as_ref().unwrap().limits.clone();
+    assert_eq!(limits.as_ref().unwrap()["cpu"].0, "2");
+    assert_eq!(limits.as_ref().unwrap()["memory"].0, "4Gi");
+    assert!(config.worker.anti_affinity);
+    assert_eq!(
+        config.service.load_balancer_source_ranges,
+        vec!["10.0.0.0/8"]
+    );
+
+    // Invalid values are errors
+    let conf: ClusterConf =
+        toml::from_str("[client.kubernetes.worker]\nanti_affinity = \"maybe\"\n").unwrap();
+    let error = apply_conf_overrides(conf.client.kubernetes.as_ref().unwrap(), &mut config)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("client.kubernetes.worker.anti_affinity"));
+
+    // The overrides survive the cluster-side TOML rendering
+    let toml_str = KubernetesConfigBuilder::new(
+        toml::from_str(
+            "[client.kubernetes.master]\ntolerations = [{ key = \"a\", operator = \"Exists\" }]\n",
+        )
+        .unwrap(),
+        test_utils::create_test_kubernetes_config(),
+    )
+    .build_cluster_side_config()
+    .unwrap();
+    assert!(toml_str.contains("tolerations"));
+}
+
+// ============================================================================
+// Tests for Layered Config Resolver
+// ============================================================================
+
+#[test]
+fn test_config_resolver_layer_precedence() {
+    use curvine_kube::domain::config::resolver::{ConfigResolver, Layer};
+
+    let conf: ClusterConf = toml::from_str(
+        r#"
+[client.kubernetes]
+cluster_id = "layers"
+namespace = "from-file"
+
+[client.kubernetes.master]
+replicas = 5
+image = "file/curvine:1"
+
+[client.kubernetes.worker]
+replicas = 4
+"#,
+    )
+    .unwrap();
+
+    let mut resolver = ConfigResolver::new();
+    let unknown = resolver
+        .config_file(conf.client.kubernetes.as_ref().unwrap())
+        .unwrap();
+    assert!(unknown.is_empty());
+    resolver
+        .environment(|name| match name {
+            "CURVINE_NAMESPACE" => Some("from-env".to_string()),
+            "CURVINE_IMAGE" => Some("env/curvine:2".to_string()),
+            _ => None,
+        })
+        .unwrap();
+    let mut properties = HashMap::new();
+    properties.insert("kubernetes.worker.cpu".to_string(), "2".to_string());
+    properties.insert(
+        "kubernetes.namespace".to_string(),
+        "from-property".to_string(),
+    );
+    resolver.properties(&properties).unwrap();
+    // An explicit flag wins even when it equals the built-in default
+    resolver.flag("master.replicas", Some("3")).unwrap();
+    resolver.flag("worker.replicas", None).unwrap();
+    let resolved = resolver.finish();
+
+    assert_eq!(resolved.config.cluster_id, "layers");
+    assert_eq!(resolved.source("cluster_id"), Layer::ConfigFile);
+    assert_eq!(resolved.config.namespace, "from-property");
+    assert_eq!(resolved.source("namespace"), Layer::Property);
+    assert_eq!(resolved.config.master.image, "env/curvine:2");
+    assert_eq!(resolved.source("master.image"), Layer::Environment);
+    assert_eq!(resolved.config.master.replicas, 3);
+    assert_eq!(resolved.source("master.replicas"), Layer::Flag);
+    assert_eq!(resolved.config.worker.replicas, 4);
+    assert_eq!(resolved.source("worker.replicas"), Layer::ConfigFile);
+    assert_eq!(resolved.source("cluster_domain"), Layer::Default);
+
+    // -D fills the remaining resources from defaults
+    assert_eq!(
+        resolved.source("worker.resources.limits.cpu"),
+        Layer::Property
+    );
+    assert_eq!(
+        resolved.source("master.resources.limits.cpu"),
+        Layer::Default
+    );
+
+    let entries = resolved.entries();
+    assert!(entries.iter().all(|entry| !entry.value.is_null()));
+    let namespace = entries.iter().find(|e| e.path == "namespace").unwrap();
+    assert_eq!(namespace.value, serde_json::json!("from-property"));
+    assert_eq!(
+        serde_json::to_value(namespace).unwrap()["source"],
+        "property"
+    );
+}
+
+#[test]
+fn test_config_resolve_command() {
+    use clap::Parser;
+    use curvine_kube::cli::k8s::ConfigAction;
+    use curvine_kube::cli::CliArgs;
+    use curvine_kube::domain::config::resolver::Layer;
+
+    let path = std::env::temp_dir().join(format!("resolve-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "[client.kubernetes]\ncluster_id = \"resolve\"\n\n[client.kubernetes.master]\nreplicas = 5\n",
+    )
+    .unwrap();
+
+    let args = CliArgs::try_parse_from([
+        "curvine-kube",
+        "config",
+        "resolve",
+        "--config-file",
+        path.to_str().unwrap(),
+        "--master-replicas",
+        "3",
+        "--image-pull-policy",
+        "Always",
+        "-o",
+        "json",
+    ])
+    .unwrap();
+    let curvine_kube::cli::commands::Commands::Config(cmd) = args.command else {
+        panic!("expected config command");
+    };
+    let ConfigAction::Resolve { args, .. } = cmd.action else {
+        panic!("expected resolve action");
+    };
+    let (_, resolved) = args.resolve().unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(resolved.config.cluster_id, "resolve");
+    assert_eq!(resolved.config.master.replicas, 3);
+    assert_eq!(resolved.source("master.replicas"), Layer::Flag);
+    assert_eq!(resolved.config.image_pull_policy, "Always");
+    assert_eq!(resolved.source("image_pull_policy"), Layer::Flag);
+}
+
+#[tokio::test]
+async fn test_config_resolve_structured_output() {
+    use clap::Parser;
+    use curvine_kube::cli::CliArgs;
+
+    let path = std::env::temp_dir().join(format!("resolve-output-{}.toml", std::process::id()));
+    std::fs::write(&path, "[client.kubernetes]\ncluster_id = \"resolve\"\n").unwrap();
+
+    for format in ["json", "yaml"] {
+        let args = CliArgs::try_parse_from([
+            "curvine-kube",
+            "config",
+            "resolve",
+            "--config-file",
+            path.to_str().unwrap(),
+            "-o",
+            format,
+        ])
+        .unwrap();
+        let curvine_kube::cli::commands::Commands::Config(cmd) = args.command else {
+            panic!("expected config command");
+        };
+        let result = cmd.execute().await;
+        assert!(result.is_ok(), "-o {}: {:?}", format, result);
+    }
+    std::fs::remove_file(&path).ok();
+}
+
+// ============================================================================
+// Tests for Profile Overlays
+// ============================================================================
+
+#[test]
+fn test_overlay_merge_strategies() {
+    use curvine_kube::domain::config::overlay::merge;
+
+    let base: toml::Table = r#"
+cluster_id = "base"
+
+[worker]
+data_dir = ["[HDD]/data1", "[HDD]/data2"]
+
+[client.kubernetes]
+namespace = "dev"
+image_pull_secrets = ["a"]
+
+[client.kubernetes.master]
+replicas = 1
+image = "curvine:dev"
+"#
+    .parse()
+    .unwrap();
+
+    // Tables merge key by key, lists are replaced by default
+    let mut merged = base.clone();
+    let overlay: toml::Table = r#"
+[worker]
+data_dir = ["[SSD]/fast"]
+
+[client.kubernetes]
+image_pull_secrets = ["b"]
+
+[client.kubernetes.master]
+replicas = 3
+"#
+    .parse()
+    .unwrap();
+    merge(&mut merged, overlay).unwrap();
+    let conf: ClusterConf = toml::Value::Table(merged).try_into().unwrap();
+    assert_eq!(conf.cluster_id, "base");
+    assert_eq!(conf.worker.data_dir, vec!["[SSD]/fast"]);
+    let kube = conf.client.kubernetes.unwrap();
+    assert_eq!(kube.namespace, "dev");
+    assert_eq!(kube.image_pull_secrets, vec!["b"]);
+    assert_eq!(kube.master.replicas, 3);
+    assert_eq!(kube.master.image, "curvine:dev");
+
+    let data_dirs = |strategy: &str| {
+        let mut merged = base.clone();
+        let overlay: toml::Table = format!(
+            "[overlay.merge]\n\"worker.data_dir\" = \"{}\"\n\n[worker]\ndata_dir = [\"[SSD:100GB]/data2\", \"[MEM:2GB]/mem\"]\n",
+            strategy
+        )
+        .parse()
+        .unwrap();
+        merge(&mut merged, overlay).unwrap();
+        assert!(!merged.contains_key("overlay"));
+        merged["worker"]["data_dir"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        data_dirs("replace"),
+        vec!["[SSD:100GB]/data2", "[MEM:2GB]/mem"]
+    );
+    assert_eq!(
+        data_dirs("append"),
+        vec![
+            "[HDD]/data1",
+            "[HDD]/data2",
+            "[SSD:100GB]/data2",
+            "[MEM:2GB]/mem"
+        ]
+    );
+    assert_eq!(
+        data_dirs("prepend"),
+        vec![
+            "[SSD:100GB]/data2",
+            "[MEM:2GB]/mem",
+            "[HDD]/data1",
+            "[HDD]/data2"
+        ]
+    );
+    assert_eq!(
+        data_dirs("by-path"),
+        vec!["[HDD]/data1", "[SSD:100GB]/data2", "[MEM:2GB]/mem"]
+    );
+}
+
+#[test]
+fn test_overlay_merge_errors() {
+    use curvine_kube::domain::config::overlay::merge;
+
+    let cases = [
+        (
+            "[overlay.merge]\n\"worker.data_dir\" = \"zip\"\n\n[worker]\ndata_dir = [\"/d\"]\n",
+            "Invalid list merge strategy",
+        ),
+        (
+            "[overlay.merge]\n\"worker.data_dirs\" = \"append\"\n",
+            "'worker.data_dirs' is not a list",
+        ),
+        (
+            "[overlay]\nmerge_lists = true\n",
+            "unknown key 'overlay.merge_lists'",
+        ),
+    ];
+    for (overlay, expected) in cases {
+        let mut base = toml::Table::new();
+        let error = merge(&mut base, overlay.parse().unwrap())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains(expected), "{}: {}", expected, error);
+    }
+}
+
+#[test]
+fn test_cluster_conf_from_profile() {
+    use curvine_kube::domain::config::overlay::profile_path;
+    use std::path::Path;
+
+    assert_eq!(
+        profile_path(Path::new("/etc/curvine/curvine-cluster.toml"), "prod"),
+        Path::new("/etc/curvine/curvine-cluster.prod.toml")
+    );
+    assert_eq!(
+        profile_path(Path::new("cluster"), "dev"),
+        Path::new("cluster.dev")
+    );
+
+    let dir = std::env::temp_dir().join(format!("profiles-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let base = dir.join("curvine-cluster.toml");
+    std::fs::write(
+        &base,
+        "cluster_id = \"base\"\n\n[client.kubernetes]\nnamespace = \"dev\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("curvine-cluster.prod.toml"),
+        "[client.kubernetes]\nnamespace = \"prod\"\n",
+    )
+    .unwrap();
+    let base = base.to_str().unwrap();
+
+    let conf = ClusterConf::from_profile(base, Some("prod")).unwrap();
+    assert_eq!(conf.cluster_id, "base");
+    assert_eq!(conf.client.kubernetes.unwrap().namespace, "prod");
+    let conf = ClusterConf::from_profile(base, None).unwrap();
+    assert_eq!(conf.client.kubernetes.unwrap().namespace, "dev");
+    let error = ClusterConf::from_profile(base, Some("staging"))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("Profile 'staging' not found"));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ============================================================================
+// Tests for Config Interpolation
+// ============================================================================
+
+#[test]
+fn test_interpolate_references() {
+    use curvine_kube::shared::interpolate::{interpolate_with, Syntax};
+
+    let var = |name: &str| match name {
+        "TAG" => Some("v1.2".to_string()),
+        "EMPTY" => Some(String::new()),
+        _ => None,
+    };
+    let file = |path: &str| (path == "/run/secrets/key").then(|| "s3cr3t\n".to_string());
+
+    let output = interpolate_with(
+        r#"# ${NOT_INTERPOLATED} in comments
+image = "curvine:${TAG}"
+class = "${STORAGE_CLASS:-standard}"
+empty = "${EMPTY:-fallback}"
+key = "${file:/run/secrets/key}"
+literal = "$${TAG}"
+"#,
+        Syntax::Toml,
+        var,
+        file,
+    )
+    .unwrap();
+    assert!(output.contains("# ${NOT_INTERPOLATED} in comments"));
+    assert!(output.contains("image = \"curvine:v1.2\""));
+    assert!(output.contains("class = \"standard\""));
+    assert!(output.contains("empty = \"fallback\""));
+    assert!(output.contains("key = \"s3cr3t\""));
+    assert!(output.contains("literal = \"${TAG}\""));
+
+    // Every unresolved reference is listed once
+    let error = interpolate_with(
+        "a = \"${MISSING_A}\"\nb = \"${MISSING_B}\"\nc = \"${MISSING_A}\"\nd = \"${file:/nope}\"\n",
+        Syntax::Toml,
+        var,
+        file,
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("unresolved references: MISSING_A, MISSING_B, file:/nope"));
+
+    assert!(interpolate_with("a = \"${TAG\"\n", Syntax::Toml, var, file).is_err());
+    assert!(interpolate_with("a = \"${not valid}\"\n", Syntax::Toml, var, file).is_err());
+}
+
+#[test]
+fn test_interpolate_escapes_values() {
+    use curvine_kube::shared::interpolate::{interpolate_with, Syntax};
+
+    let var = |name: &str| match name {
+        "QUOTED" => Some("a\"b\\c\nd".to_string()),
+        "APOSTROPHE" => Some("it's".to_string()),
+        "PORT" => Some("8995".to_string()),
+        "MAPPING" => Some("x: y".to_string()),
+        _ => None,
+    };
+    let file = |_: &str| None;
+    let toml = |content: &str| interpolate_with(content, Syntax::Toml, var, file);
+    let yaml = |content: &str| interpolate_with(content, Syntax::Yaml, var, file);
+
+    // Values cannot end the string they are inserted into
+    let output = toml("a = \"${QUOTED}\"\nport = ${PORT} # ${UNSET}\n").unwrap();
+    let table: toml::Table = toml::from_str(&output).unwrap();
+    assert_eq!(table["a"].as_str(), Some("a\"b\\c\nd"));
+    assert_eq!(table["port"].as_integer(), Some(8995));
+    assert!(toml("a = '${APOSTROPHE}'\n").is_err());
+    let error = toml("a = ${QUOTED}\n").unwrap_err().to_string();
+    assert!(error.contains("cannot insert ${QUOTED}"));
+    assert!(!error.contains("a\"b"));
+
+    let output = yaml("a: '${APOSTROPHE}'\nb: \"${QUOTED}\"\nc: don't ${APOSTROPHE}\n").unwrap();
+    let value: serde_yaml::Value = serde_yaml::from_str(&output).unwrap();
+    assert_eq!(value["a"].as_str(), Some("it's"));
+    assert_eq!(value["b"].as_str(), Some("a\"b\\c\nd"));
+    assert_eq!(value["c"].as_str(), Some("don't it's"));
+    assert!(yaml("a: ${MAPPING}\n").is_err());
+    assert!(yaml("a: '${QUOTED}'\n").is_err());
+
+    let json = interpolate_with("{\"a\": \"${QUOTED}\"}", Syntax::Json, var, file).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["a"].as_str(), Some("a\"b\\c\nd"));
+}
+
+#[test]
+fn test_config_render_redacts_file_references() {
+    use curvine_kube::domain::config::format::ConfigFormat;
+    use curvine_kube::domain::config::overlay;
+
+    let dir = tempfile::tempdir().unwrap();
+    let secret = dir.path().join("access-key");
+    std::fs::write(&secret, "s3cr3t\n").unwrap();
+    let conf = dir.path().join("curvine-cluster.toml");
+    std::fs::write(
+        &conf,
+        format!(
+            "[client]\naccess_key = \"${{file:{}}}\"\n",
+            secret.display()
+        ),
+    )
+    .unwrap();
+    let conf = conf.to_str().unwrap();
+
+    let loaded = overlay::load(conf, None, ConfigFormat::Toml).unwrap();
+    assert_eq!(loaded["client"]["access_key"].as_str(), Some("s3cr3t"));
+    let shown = overlay::load_redacted(conf, None, ConfigFormat::Toml).unwrap();
+    assert_eq!(shown["client"]["access_key"].as_str(), Some("<redacted>"));
+}
+
+#[test]
+fn test_interpolate_config_and_pod_template() {
+    use curvine_kube::infrastructure::kubernetes::resources::pod::template_utils::load_pod_from_template_file;
+
+    std::env::set_var("CURVINE_TEST_INTERP_CLUSTER", "from-env");
+    std::env::set_var("CURVINE_TEST_INTERP_CONTAINER", "curvine-master");
+    let dir = std::env::temp_dir().join(format!("interpolate-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let conf_path = dir.join("curvine-cluster.toml");
+    std::fs::write(
+        &conf_path,
+        "cluster_id = \"${CURVINE_TEST_INTERP_CLUSTER}\"\n\n[worker]\ndata_dir = [\"${CURVINE_TEST_INTERP_DIR:-[SSD]/data}\"]\n",
+    )
+    .unwrap();
+    let conf = ClusterConf::from(conf_path.to_str().unwrap()).unwrap();
+    assert_eq!(conf.cluster_id, "from-env");
+    assert_eq!(conf.worker.data_dir, vec!["[SSD]/data"]);
+
+    std::fs::write(
+        &conf_path,
+        "cluster_id = \"${CURVINE_TEST_INTERP_UNSET}\"\n",
+    )
+    .unwrap();
+    let error = ClusterConf::from(conf_path.to_str().unwrap())
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("CURVINE_TEST_INTERP_UNSET"));
+
+    let template_path = dir.join("master.yaml");
+    std::fs::write(
+        &template_path,
+        "apiVersion: v1\nkind: Pod\nspec:\n  containers:\n    - name: ${CURVINE_TEST_INTERP_CONTAINER}\n",
+    )
+    .unwrap();
+    let template = template_path.to_str().unwrap();
+    assert!(load_pod_from_template_file(template, "curvine-master", true).is_ok());
+
+    std::fs::write(
+        &template_path,
+        "apiVersion: v1\nkind: Pod\nspec:\n  containers:\n    - name: ${CURVINE_TEST_INTERP_NAME}\n      image: ${CURVINE_TEST_INTERP_IMAGE}\n",
+    )
+    .unwrap();
+    let error = load_pod_from_template_file(template, "curvine-master", true)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("CURVINE_TEST_INTERP_NAME, CURVINE_TEST_INTERP_IMAGE"));
+
+    // Without opting in, shell variables in commands are left alone
+    std::fs::write(
+        &template_path,
+        "apiVersion: v1\nkind: Pod\nspec:\n  containers:\n    - name: curvine-master\n      command: [sh, -c, 'echo ${HOSTNAME}']\n",
+    )
+    .unwrap();
+    let pod = load_pod_from_template_file(template, "curvine-master", false).unwrap();
+    let command = pod.get_main_container().command.clone().unwrap_or_default();
+    assert_eq!(command.last().map(String::as_str), Some("echo ${HOSTNAME}"));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+// ============================================================================
+// Tests for Config File Formats
+// ============================================================================
+
+#[test]
+fn test_config_format_detection() {
+    use curvine_kube::domain::config::format::ConfigFormat;
+
+    assert_eq!(ConfigFormat::from_path("c.toml"), ConfigFormat::Toml);
+    assert_eq!(ConfigFormat::from_path("c.YAML"), ConfigFormat::Yaml);
+    assert_eq!(ConfigFormat::from_path("c.yml"), ConfigFormat::Yaml);
+    assert_eq!(ConfigFormat::from_path("c.json"), ConfigFormat::Json);
+    assert_eq!(ConfigFormat::from_path("curvine.conf"), ConfigFormat::Toml);
+    assert_eq!(
+        ConfigFormat::resolve(Some(ConfigFormat::Json), "c.toml"),
+        ConfigFormat::Json
+    );
+    assert_eq!("yml".parse::<ConfigFormat>().unwrap(), ConfigFormat::Yaml);
+    assert!("xml".parse::<ConfigFormat>().is_err());
+}
+
+#[test]
+fn test_cluster_conf_from_yaml_and_json() {
+    use curvine_kube::domain::config::format::ConfigFormat;
+
+    let toml_source = r#"
+cluster_id = "formats"
+
+[worker]
+data_dir = ["[SSD]/data"]
+
+[client.kubernetes]
+namespace = "curvine"
+
+[client.kubernetes.master]
+replicas = 3
+"#;
+    let document: toml::Table = ConfigFormat::Toml.parse(toml_source).unwrap();
+
+    let dir = std::env::temp_dir().join(format!("formats-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, format) in [
+        ("curvine-cluster.yaml", ConfigFormat::Yaml),
+        ("curvine-cluster.json", ConfigFormat::Json),
+        ("curvine-cluster.toml", ConfigFormat::Toml),
+    ] {
+        let path = dir.join(name);
+        let rendered = format.render(&document).unwrap();
+        std::fs::write(&path, &rendered).unwrap();
+
+        // Converting back yields the same document
+        let parsed: toml::Table = format.parse(&rendered).unwrap();
+        assert_eq!(parsed, document, "{}", name);
+
+        let conf = ClusterConf::from(path.to_str().unwrap()).unwrap();
+        assert_eq!(conf.cluster_id, "formats", "{}", name);
+        assert_eq!(conf.worker.data_dir, vec!["[SSD]/data"]);
+        let kube = conf.client.kubernetes.as_ref().unwrap();
+        assert_eq!(kube.namespace, "curvine");
+        assert_eq!(kube.master.replicas, 3);
+
+        // The ConfigMap content stays TOML whatever the input format
+        let cluster_side =
+            KubernetesConfigBuilder::new(conf.clone(), test_utils::create_test_kubernetes_config())
+                .build_cluster_side_config()
+                .unwrap();
+        let reparsed: ClusterConf = toml::from_str(&cluster_side).unwrap();
+        assert_eq!(reparsed.worker.data_dir, vec!["[SSD]/data"]);
+    }
+
+    // --config-format overrides the extension
+    let path = dir.join("cluster.conf");
+    std::fs::write(&path, "cluster_id: forced\n").unwrap();
+    let conf = ClusterConf::load(path.to_str().unwrap(), None, Some(ConfigFormat::Yaml)).unwrap();
+    assert_eq!(conf.cluster_id, "forced");
+    assert!(ClusterConf::from(path.to_str().unwrap()).is_err());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_config_convert_flags() {
+    use clap::Parser;
+    use curvine_kube::cli::k8s::ConfigAction;
+    use curvine_kube::cli::CliArgs;
+    use curvine_kube::domain::config::format::ConfigFormat;
+
+    let args = CliArgs::try_parse_from([
+        "curvine-kube",
+        "config",
+        "convert",
+        "--config-file",
+        "cluster.conf",
+        "--config-format",
+        "toml",
+        "--to",
+        "yaml",
+    ])
+    .unwrap();
+    let curvine_kube::cli::commands::Commands::Config(cmd) = args.command else {
+        panic!("expected config command");
+    };
+    let ConfigAction::Convert {
+        config_format, to, ..
+    } = cmd.action
+    else {
+        panic!("expected convert action");
+    };
+    assert_eq!(config_format, Some(ConfigFormat::Toml));
+    assert_eq!(to, ConfigFormat::Yaml);
+
+    assert!(CliArgs::try_parse_from(["curvine-kube", "config", "convert", "--to", "xml"]).is_err());
+}
+
+// ============================================================================
+// Tests for Unmodeled Key Passthrough
+// ============================================================================
+
+#[test]
+fn test_unmodeled_keys_reach_cluster_side_config() {
+    let source = r#"
+cluster_id = "passthrough"
+
+[master]
+rpc_port = 8995
+new_master_option = "fast"
+
+[worker]
+data_dir = ["[SSD]/data"]
+new_worker_limit = 42
+
+[client]
+write_type = "cache"
+new_client_flag = true
+
+[log]
+level = "debug"
+targets = ["stdout"]
+"#;
+    let conf: ClusterConf = toml::from_str(source).unwrap();
+    assert_eq!(
+        conf.unmodeled_keys(),
+        vec![
+            "log",
+            "master.new_master_option",
+            "worker.new_worker_limit",
+            "client.new_client_flag"
+        ]
+    );
+
+    let cluster_side =
+        KubernetesConfigBuilder::new(conf, test_utils::create_test_kubernetes_config())
+            .build_cluster_side_config()
+            .unwrap();
+    let rendered: toml::Table = toml::from_str(&cluster_side).unwrap();
+    assert_eq!(
+        rendered["master"]["new_master_option"].as_str(),
+        Some("fast")
+    );
+    assert_eq!(rendered["master"]["rpc_port"].as_integer(), Some(8995));
+    assert_eq!(
+        rendered["worker"]["new_worker_limit"].as_integer(),
+        Some(42)
+    );
+    assert_eq!(rendered["client"]["new_client_flag"].as_bool(), Some(true));
+    assert_eq!(rendered["log"]["level"].as_str(), Some("debug"));
+    assert_eq!(rendered["log"]["targets"][0].as_str(), Some("stdout"));
+
+    // Modeled configuration has nothing to pass through
+    assert!(test_utils::create_test_cluster_conf()
+        .unmodeled_keys()
+        .is_empty());
+}
+
+#[test]
+fn test_lint_warns_about_unmodeled_keys() {
+    use curvine_kube::domain::cluster::lint::{lint, Severity};
+
+    let source =
+        "cluster_id = \"c\"\n\n[master]\nnew_master_option = 1\n\n[log]\nlevel = \"debug\"\n";
+    let cluster_conf: ClusterConf = toml::from_str(source).unwrap();
+    let kube_config = test_utils::create_test_kubernetes_config();
+    let report = lint(
+        "c.toml",
+        source,
+        &HashMap::new(),
+        &cluster_conf,
+        &kube_config,
+    );
+
+    assert!(!report.has_errors(), "{:#?}", report.diagnostics);
+    let warnings: Vec<_> = report
+        .diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .map(|d| (d.key.clone().unwrap(), d.line))
+        .collect();
+    assert!(warnings.contains(&("master.new_master_option".to_string(), Some(4))));
+    assert!(warnings.contains(&("log".to_string(), Some(6))));
+}
+
+// ============================================================================
+// Tests for Curvine Version Compatibility
+// ============================================================================
+
+#[test]
+fn test_curvine_version_parse_and_detect() {
+    use curvine_kube::domain::cluster::compat::{detect_version, CurvineVersion};
+
+    let parse = |s: &str| s.parse::<CurvineVersion>().ok();
+    assert_eq!(parse("0.2.1"), Some(CurvineVersion::new(0, 2, 1)));
+    assert_eq!(parse("v0.2"), Some(CurvineVersion::new(0, 2, 0)));
+    assert_eq!(parse("0.2.1-beta"), Some(CurvineVersion::new(0, 2, 1)));
+    assert_eq!(
+        parse("v1.3.0-ubuntu22.04"),
+        Some(CurvineVersion::new(1, 3, 0))
+    );
+    assert_eq!(parse("latest"), None);
+    assert_eq!(parse("1.2.3.4"), None);
+    assert!(CurvineVersion::new(0, 10, 0) > CurvineVersion::new(0, 9, 9));
+
+    assert_eq!(
+        detect_version("registry:5000/curvine:v0.2.1", None).unwrap(),
+        Some(CurvineVersion::new(0, 2, 1))
+    );
+    assert_eq!(detect_version("curvine:latest", None).unwrap(), None);
+    assert_eq!(detect_version("curvine@sha256:abcdef", None).unwrap(), None);
+    // --curvine-version wins over the tag
+    assert_eq!(
+        detect_version("curvine:latest", Some("0.2.0")).unwrap(),
+        Some(CurvineVersion::new(0, 2, 0))
+    );
+    assert!(detect_version("curvine:latest", Some("nightly")).is_err());
+}
+
+#[test]
+fn test_check_versions_against_supported_schemas() {
+    use curvine_kube::domain::cluster::compat::{check_versions, SCHEMAS};
+    use curvine_kube::domain::cluster::doctor::{CheckStatus, DoctorReport};
+
+    let cluster_conf = test_utils::create_test_cluster_conf();
+    let mut kube_config = test_utils::create_test_kubernetes_config();
+    let check = |kube_config: &KubernetesConfig| {
+        let mut report = DoctorReport::default();
+        check_versions(&mut report, &cluster_conf, kube_config, SCHEMAS);
+        report
+            .checks
+            .into_iter()
+            .map(|c| (c.name, c.status, c.message))
+            .collect::<Vec<_>>()
+    };
+
+    // Untagged images cannot be checked
+    let checks = check(&kube_config);
+    assert_eq!(checks.len(), 1);
+    assert_eq!(checks[0].0, "Curvine version");
+    assert_eq!(checks[0].1, CheckStatus::Warn);
+    assert!(checks[0].2.contains("--curvine-version"), "{}", checks[0].2);
+
+    // The builders match the 0.2 series
+    kube_config.curvine_version = Some("0.2.1".to_string());
+    let checks = check(&kube_config);
+    assert_eq!(checks[0].1, CheckStatus::Pass, "{}", checks[0].2);
+
+    // Unknown versions warn; differing images are checked one by one
+    kube_config.curvine_version = None;
+    kube_config.master.image = "curvine:v0.2.1".to_string();
+    kube_config.worker.image = "curvine:v9.0.0".to_string();
+    let checks = check(&kube_config);
+    assert_eq!(checks.len(), 2);
+    assert_eq!(checks[0].0, "Curvine version (master)");
+    assert_eq!(checks[0].1, CheckStatus::Pass, "{}", checks[0].2);
+    assert_eq!(checks[1].0, "Curvine version (worker)");
+    assert_eq!(checks[1].1, CheckStatus::Warn);
+    assert!(checks[1].2.contains("supported: 0.2.x"), "{}", checks[1].2);
+}
+
+#[test]
+fn test_check_versions_fails_on_schema_mismatch() {
+    use curvine_kube::domain::cluster::compat::{check_versions, ConfigSchema, CurvineVersion};
+    use curvine_kube::domain::cluster::doctor::{CheckStatus, DoctorReport};
+
+    const SCHEMAS: &[ConfigSchema] = &[ConfigSchema {
+        series: "1.0",
+        min: CurvineVersion::new(1, 0, 0),
+        until: None,
+        master_args: &["start", "master"],
+        worker_args: &["worker"],
+        unsupported_keys: &["master.meta_dir", "fuse"],
+        defaults: &[],
+    }];
+
+    let cluster_conf = test_utils::create_test_cluster_conf();
+    let mut kube_config = test_utils::create_test_kubernetes_config();
+    kube_config.master.image = "curvine:1.2.0".to_string();
+    kube_config.worker.image = "curvine:1.2.0".to_string();
+
+    let mut report = DoctorReport::default();
+    check_versions(&mut report, &cluster_conf, &kube_config, SCHEMAS);
+    assert_eq!(report.checks.len(), 1);
+    let check = &report.checks[0];
+    assert_eq!(check.status, CheckStatus::Fail);
+    assert!(
+        check.message.contains("master container args"),
+        "{}",
+        check.message
+    );
+    assert!(!check.message.contains("worker container args"));
+    assert!(
+        check
+            .message
+            .contains("unsupported config keys master.meta_dir, fuse"),
+        "{}",
+        check.message
+    );
+}
+
+#[test]
+fn test_check_compatibility_refuses_mismatched_series() {
+    use curvine_kube::domain::cluster::compat::{
+        check_compatibility, ConfigSchema, CurvineVersion, SCHEMAS,
+    };
+
+    const NEXT: &[ConfigSchema] = &[ConfigSchema {
+        series: "0.3",
+        min: CurvineVersion::new(0, 3, 0),
+        until: None,
+        master_args: &["master"],
+        worker_args: &["worker"],
+        unsupported_keys: &["journal.journal_addrs"],
+        defaults: &[],
+    }];
+
+    let cluster_conf = test_utils::create_test_cluster_conf();
+    let mut kube_config = test_utils::create_test_kubernetes_config();
+    kube_config.curvine_version = Some("0.3.1".to_string());
+
+    // Deploy and update refuse an image whose series expects other keys
+    let error = check_compatibility(&cluster_conf, &kube_config, NEXT).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("Curvine version: Curvine 0.3.1 (0.3 schema): unsupported config keys journal.journal_addrs"),
+        "{}",
+        error
+    );
+
+    // The same image is only a warning when its series is not known
+    let warnings = check_compatibility(&cluster_conf, &kube_config, SCHEMAS).unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert!(
+        warnings[0].contains("not a supported version"),
+        "{}",
+        warnings[0]
+    );
+
+    kube_config.curvine_version = Some("0.2.1".to_string());
+    assert!(check_compatibility(&cluster_conf, &kube_config, SCHEMAS)
+        .unwrap()
+        .is_empty());
+    assert!(check_compatibility(&cluster_conf, &kube_config, NEXT)
+        .unwrap()
+        .iter()
+        .any(|warning| warning.contains("supported: 0.3.x")));
+}
+
+#[test]
+fn test_apply_schema_defaults_keeps_configured_values() {
+    use curvine_kube::domain::cluster::compat::{
+        apply_schema_defaults, ConfigSchema, CurvineVersion, SCHEMAS,
+    };
+
+    const CUSTOM: &[ConfigSchema] = &[ConfigSchema {
+        series: "1.0",
+        min: CurvineVersion::new(1, 0, 0),
+        until: Some(CurvineVersion::new(2, 0, 0)),
+        master_args: &["master"],
+        worker_args: &["worker"],
+        unsupported_keys: &[],
+        defaults: &[("master.rpc_port", "9000"), ("master.io_threads", "64")],
+    }];
+
+    let mut kube_config = test_utils::create_test_kubernetes_config();
+    kube_config.curvine_version = Some("1.1".to_string());
+    let mut cluster_conf = ClusterConf::default();
+    cluster_conf.master.io_threads = 8;
+
+    let applied = apply_schema_defaults(&mut cluster_conf, &kube_config, CUSTOM).unwrap();
+    assert_eq!(applied, vec!["master.rpc_port"]);
+    assert_eq!(cluster_conf.master.rpc_port, 9000);
+    assert_eq!(cluster_conf.master.io_threads, 8);
+
+    // The modeled defaults are those of the supported series
+    let mut cluster_conf = ClusterConf::default();
+    let applied = apply_schema_defaults(&mut cluster_conf, &kube_config, SCHEMAS).unwrap();
+    assert!(applied.is_empty());
+    assert_eq!(
+        cluster_conf.master.rpc_port,
+        ClusterConf::default().master.rpc_port
+    );
+}
+
+#[test]
+fn test_curvine_version_flag_and_environment() {
+    use curvine_kube::domain::config::resolver::{ConfigResolver, Layer};
+
+    let mut resolver = ConfigResolver::new();
+    resolver
+        .environment(|name| (name == "CURVINE_VERSION").then(|| "0.2.0".to_string()))
+        .unwrap();
+    let resolved = resolver.clone().finish();
+    assert_eq!(resolved.config.curvine_version.as_deref(), Some("0.2.0"));
+    assert_eq!(resolved.source("curvine_version"), Layer::Environment);
+
+    resolver.flag("curvine_version", Some("0.2.1")).unwrap();
+    let resolved = resolver.finish();
+    assert_eq!(resolved.config.curvine_version.as_deref(), Some("0.2.1"));
+    assert_eq!(resolved.source("curvine_version"), Layer::Flag);
+}
+
+// ============================================================================
+// Tests for Config Schema
+// ============================================================================
+
+#[test]
+fn test_cluster_conf_schema_covers_kubernetes_section() {
+    use curvine_kube::domain::config::schema::cluster_conf_schema;
+
+    let schema = serde_json::to_value(cluster_conf_schema()).unwrap();
+    let definitions = &schema["definitions"];
+
+    // Field defaults and docs
+    assert_eq!(
+        definitions["MasterConf"]["properties"]["rpc_port"]["default"],
+        8995
+    );
+    assert_eq!(
+        definitions["KubernetesWorkerConf"]["properties"]["host_network"]["description"],
+        "Run workers in the host network namespace"
+    );
+
+    // Enums, also for fields stored as strings
+    assert_eq!(
+        definitions["ServiceType"]["enum"],
+        serde_json::json!(["ClusterIP", "NodePort", "LoadBalancer"])
+    );
+    assert_eq!(
+        definitions["KubernetesServiceConf"]["properties"]["service_type"]["allOf"][0]["$ref"],
+        "#/definitions/ServiceType"
+    );
+    let tls_modes: Vec<_> = definitions["TlsMode"]["oneOf"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|variant| variant["enum"][0].clone())
+        .collect();
+    assert_eq!(tls_modes, ["self-signed", "cert-manager"]);
+
+    // Override keys of [client.kubernetes] come from KubernetesConfig, while
+    // the dedicated fields keep their own schema
+    let kubernetes = &definitions["KubernetesConf"]["properties"];
+    assert_eq!(kubernetes["cluster_domain"]["default"], "cluster.local");
+    assert!(kubernetes.get("curvine_version").is_some());
+    assert_eq!(
+        kubernetes["master"]["allOf"][0]["$ref"],
+        "#/definitions/KubernetesMasterConf"
+    );
+    let master = &definitions["KubernetesMasterConf"]["properties"];
+    assert!(master.get("tolerations").is_some());
+    assert!(master.get("resources").is_some());
+    assert!(definitions.get("KubernetesConfig").is_none());
+    assert!(definitions.get("MasterConfig").is_none());
+
+    // Passthrough keys are allowed
+    assert!(schema.get("additionalProperties").is_none());
+}
+
+#[test]
+fn test_cluster_conf_schema_data_dir_pattern() {
+    use curvine_kube::domain::config::schema::cluster_conf_schema;
+    use curvine_kube::domain::config::WorkerDataDir;
+
+    let schema = serde_json::to_value(cluster_conf_schema()).unwrap();
+    let items = &schema["definitions"]["WorkerConf"]["properties"]["data_dir"]["items"];
+    let pattern = regex::Regex::new(items["pattern"].as_str().unwrap()).unwrap();
+
+    for valid in [
+        "[SSD:100GB]/data/ssd",
+        "[mem:2GB]/dev/shm",
+        "[HDD]/data/hdd",
+        "[20GB]/data",
+        "/data/plain",
+    ] {
+        assert!(pattern.is_match(valid), "{}", valid);
+        assert!(WorkerDataDir::parse_data_dir(valid).is_ok());
+    }
+    assert!(!pattern.is_match("[NVME:1TB]/data"));
+    assert!(items["description"]
+        .as_str()
+        .unwrap()
+        .contains("mem, ssd, hdd, disk, ufs"));
+}
+
+#[test]
+fn test_config_schema_command_parses() {
+    use clap::Parser;
+    use curvine_kube::cli::k8s::ConfigAction;
+    use curvine_kube::cli::CliArgs;
+
+    let args = CliArgs::try_parse_from(["curvine-kube", "config", "schema"]).unwrap();
+    let curvine_kube::cli::commands::Commands::Config(cmd) = args.command else {
+        panic!("expected config command");
+    };
+    assert!(matches!(cmd.action, ConfigAction::Schema));
+}
+
+// ============================================================================
+// Tests for Init Scaffold
+// ============================================================================
+
+fn scaffold_options(
+    preset: curvine_kube::domain::config::scaffold::Preset,
+) -> curvine_kube::domain::config::scaffold::ScaffoldOptions {
+    curvine_kube::domain::config::scaffold::ScaffoldOptions {
+        preset,
+        cluster_id: "scaffold".to_string(),
+        namespace: "curvine".to_string(),
+        image: "docker.io/curvine:v0.2.1".to_string(),
+        storage_class: None,
+    }
+}
+
+#[test]
+fn test_init_presets_render_valid_configs() {
+    use curvine_kube::domain::cluster::lint::lint;
+    use curvine_kube::domain::config::resolver::ConfigResolver;
+    use curvine_kube::domain::config::scaffold::{render, ClusterFacts, Preset};
+
+    for (preset, masters, workers) in [
+        (Preset::Dev, 1, 1),
+        (Preset::Prod, 3, 3),
+        (Preset::CacheOnly, 3, 3),
+        (Preset::Ha, 5, 3),
+    ] {
+        let content = render(&scaffold_options(preset), &ClusterFacts::default());
+        let cluster_conf: ClusterConf = toml::from_str(&content)
+            .unwrap_or_else(|e| panic!("{}: {}\n{}", preset.as_str(), e, content));
+        assert!(cluster_conf.unmodeled_keys().is_empty());
+
+        let mut resolver = ConfigResolver::new();
+        let unknown = resolver
+            .config_file(cluster_conf.client.kubernetes.as_ref().unwrap())
+            .unwrap();
+        assert!(unknown.is_empty(), "{}: {:?}", preset.as_str(), unknown);
+        let kube_config = resolver.finish().config;
+        kube_config.validate().unwrap();
+        assert_eq!(kube_config.cluster_id, "scaffold");
+        assert_eq!(kube_config.namespace, "curvine");
+        assert_eq!(kube_config.master.image, "docker.io/curvine:v0.2.1");
+        assert_eq!(kube_config.master.replicas, masters);
+        assert_eq!(kube_config.worker.replicas, workers);
+
+        let report = lint(
+            "curvine-cluster.toml",
+            &content,
+            &HashMap::new(),
+            &cluster_conf,
+            &kube_config,
+        );
+        assert!(
+            report.diagnostics.is_empty(),
+            "{}: {:#?}",
+            preset.as_str(),
+            report.diagnostics
+        );
+    }
+
+    // Presets differ where they should
+    let render_preset = |preset| render(&scaffold_options(preset), &ClusterFacts::default());
+    assert!(render_preset(Preset::CacheOnly).contains("[MEM:4GB]/data/mem"));
+    assert!(render_preset(Preset::Ha).contains("preset = \"zone-aware\""));
+    assert!(!render_preset(Preset::Prod).contains("zone-aware"));
+    assert!(!render_preset(Preset::Dev).contains("[client.kubernetes.master.resources]"));
+}
+
+#[test]
+fn test_init_prefills_from_cluster_facts() {
+    use curvine_kube::domain::config::scaffold::{render, ClusterFacts, Preset};
+    use k8s_openapi::api::core::v1::Node;
+    use k8s_openapi::api::storage::v1::StorageClass;
+    use kube::api::ObjectMeta;
+
+    let storage_class = |name: &str, default: bool| StorageClass {
+        metadata: ObjectMeta {
+            name: Some(name.to_string()),
+            annotations: default.then(|| {
+                [(
+                    "storageclass.kubernetes.io/is-default-class".to_string(),
+                    "true".to_string(),
+                )]
+                .into()
+            }),
+            ..Default::default()
+        },
+        provisioner: "example.com/csi".to_string(),
+        ..Default::default()
+    };
+    let node = |name: &str, zone: &str, extra: &[(&str, &str)]| {
+        let mut labels: std::collections::BTreeMap<String, String> = [
+            ("kubernetes.io/hostname", name),
+            ("kubernetes.io/os", "linux"),
+            ("topology.kubernetes.io/zone", zone),
+        ]
+        .iter()
+        .chain(extra)
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        labels.insert("kubernetes.io/hostname".to_string(), name.to_string());
+        Node {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                labels: Some(labels),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    };
+
+    let facts = ClusterFacts::from_objects(
+        &[
+            storage_class("standard", false),
+            storage_class("fast-ssd", true),
+        ],
+        &[
+            node("n1", "zone-a", &[("disktype", "ssd")]),
+            node("n2", "zone-b", &[("disktype", "hdd")]),
+            node("n3", "zone-b", &[("curvine", "true")]),
+        ],
+    );
+    assert_eq!(facts.storage_classes, vec!["fast-ssd", "standard"]);
+    assert_eq!(facts.default_storage_class.as_deref(), Some("fast-ssd"));
+    assert_eq!(
+        facts.zones.iter().map(String::as_str).collect::<Vec<_>>(),
+        ["zone-a", "zone-b"]
+    );
+    // Labels shared by every node (os) and hostname/topology labels are left out
+    assert_eq!(
+        facts
+            .node_labels
+            .keys()
+            .map(String::as_str)
+            .collect::<Vec<_>>(),
+        ["curvine", "disktype"]
+    );
+
+    let content = render(&scaffold_options(Preset::Prod), &facts);
+    assert!(
+        content.contains("storage_class = \"fast-ssd\""),
+        "{}",
+        content
+    );
+    assert!(content.contains("Available: fast-ssd, standard"));
+    assert!(content.contains("Spread across zones: zone-a, zone-b"));
+    assert!(content.contains("#   disktype = hdd | ssd"));
+    let cluster_conf: ClusterConf = toml::from_str(&content).unwrap();
+    let kubernetes = cluster_conf.client.kubernetes.unwrap();
+    assert_eq!(kubernetes.storage.unwrap().storage_class, "fast-ssd");
+    assert_eq!(
+        kubernetes.master.placement.unwrap().preset.as_deref(),
+        Some("zone-aware")
+    );
+
+    // An explicit storage class wins; dev stays in one zone
+    let mut options = scaffold_options(Preset::Dev);
+    options.storage_class = Some("standard".to_string());
+    let content = render(&options, &facts);
+    assert!(content.contains("storage_class = \"standard\""));
+    assert!(!content.contains("zone-aware"));
+}
+
+#[test]
+fn test_init_command_parses() {
+    use clap::Parser;
+    use curvine_kube::cli::CliArgs;
+    use curvine_kube::domain::config::scaffold::Preset;
+
+    let args = CliArgs::try_parse_from([
+        "curvine-kube",
+        "init",
+        "--preset",
+        "cache-only",
+        "-c",
+        "cache",
+        "--non-interactive",
+        "-o",
+        "-",
+    ])
+    .unwrap();
+    let curvine_kube::cli::commands::Commands::Init(cmd) = args.command else {
+        panic!("expected init command");
+    };
+    assert_eq!(cmd.preset, Some(Preset::CacheOnly));
+    assert_eq!(cmd.cluster_id.as_deref(), Some("cache"));
+    assert!(cmd.non_interactive);
+    assert!(!cmd.discover);
+    assert_eq!(cmd.output, "-");
+
+    assert!(CliArgs::try_parse_from(["curvine-kube", "init", "--preset", "huge"]).is_err());
+}